@@ -1,10 +1,28 @@
+use gloo_timers::future::TimeoutFuture;
 use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use zera_client::buffer::BufferAccount;
+use zera_client::config::fetch_config;
+use zera_client::nft::NftAccount;
+use zera_client::pubkey::decode_pubkey;
+use zera_client::reclaim::ReclaimableAccount;
+use zera_client::tiers::{fetch_fee_status, FeeStatus};
+use zera_shared::config::ProgramConfig;
 
 use crate::components::header::Header;
 use crate::components::scanner::Scanner;
 use crate::components::batch_panel::BatchPanel;
+use crate::components::buffer_panel::BufferPanel;
+use crate::components::nft_panel::NftPanel;
+use crate::components::tx_explainer::TxExplainer;
 use crate::components::tx_status::TxStatus;
+use crate::constants::RPC_HEALTH_INTERVAL_MS;
+use crate::services::referral::referrer_from_url;
+use crate::services::rpc::{check_endpoints, GlooTransport};
+use crate::types::referral::Referral;
+use crate::types::rpc_status::RpcStatus;
 use crate::types::token_account::TokenAccountInfo;
+use crate::types::tx_result::TxResult;
 
 #[component]
 pub fn App() -> impl IntoView {
@@ -12,19 +30,82 @@ pub fn App() -> impl IntoView {
     let (wallet, set_wallet) = signal(String::new());
     // Scanned token accounts
     let (accounts, set_accounts) = signal(Vec::<TokenAccountInfo>::new());
+    // NFTs among the wallet's token accounts, for the burn flow
+    let (nfts, set_nfts) = signal(Vec::<NftAccount>::new());
+    // Program deploy buffers the wallet is the authority of
+    let (buffers, set_buffers) = signal(Vec::<BufferAccount>::new());
+    // Nonce and stake accounts shown in the scanner grid next to the token accounts
+    let (reclaimable, set_reclaimable) = signal(Vec::<ReclaimableAccount>::new());
     // Selected account indices
     let (selected, set_selected) = signal(Vec::<usize>::new());
-    // Transaction signatures
-    let (tx_sigs, set_tx_sigs) = signal(Vec::<(String, String)>::new()); // (sig, status)
+    // Submitted batch outcomes
+    let (tx_sigs, set_tx_sigs) = signal(Vec::<TxResult>::new());
     // Scanning state
     let (scanning, set_scanning) = signal(false);
     // Batch processing state
     let (processing, set_processing) = signal(false);
+    // Program config, loaded once on startup
+    let (config, set_config) = signal(None::<ProgramConfig>);
+    // Referrer from `?ref=`, once the config's referral share is known
+    let (referral, set_referral) = signal(None::<Referral>);
+
+    spawn_local(async move {
+        match fetch_config(&GlooTransport).await {
+            Ok(loaded) => {
+                if let Some(referrer) = referrer_from_url() {
+                    set_referral.set(Some(Referral {
+                        referrer,
+                        referral_bps: loaded.referral_bps,
+                    }));
+                }
+                set_config.set(Some(loaded));
+            }
+            Err(e) => log::error!("Failed to load program config: {}", e),
+        }
+    });
+
+    // Active RPC endpoint and its latency, re-checked periodically
+    let (rpc_status, set_rpc_status) = signal(None::<RpcStatus>);
+    spawn_local(async move {
+        loop {
+            let status = check_endpoints().await;
+            if !status.healthy() {
+                log::warn!("No healthy RPC endpoint, using {}", status.url);
+            }
+            set_rpc_status.set(Some(status));
+            TimeoutFuture::new(RPC_HEALTH_INTERVAL_MS).await;
+        }
+    });
+
+    // Fee tier inputs for the connected wallet, refreshed after every batch run
+    let (fee_status, set_fee_status) = signal(None::<FeeStatus>);
+    Effect::new(move |_| {
+        let owner = wallet.get();
+        if processing.get() {
+            return;
+        }
+        let Ok(owner) = decode_pubkey(&owner) else {
+            set_fee_status.set(None);
+            return;
+        };
+        spawn_local(async move {
+            match fetch_fee_status(&GlooTransport, &owner).await {
+                Ok(status) => set_fee_status.set(Some(status)),
+                Err(e) => log::error!("Failed to load fee tier: {}", e),
+            }
+        });
+    });
 
     provide_context(wallet);
     provide_context(set_wallet);
     provide_context(accounts);
     provide_context(set_accounts);
+    provide_context(nfts);
+    provide_context(set_nfts);
+    provide_context(buffers);
+    provide_context(set_buffers);
+    provide_context(reclaimable);
+    provide_context(set_reclaimable);
     provide_context(selected);
     provide_context(set_selected);
     provide_context(tx_sigs);
@@ -33,6 +114,10 @@ pub fn App() -> impl IntoView {
     provide_context(set_scanning);
     provide_context(processing);
     provide_context(set_processing);
+    provide_context(config);
+    provide_context(referral);
+    provide_context(fee_status);
+    provide_context(rpc_status);
 
     view! {
         <div class="min-h-screen flex flex-col">
@@ -40,7 +125,10 @@ pub fn App() -> impl IntoView {
             <main class="flex-1 max-w-6xl mx-auto w-full px-4 py-8 space-y-6">
                 <Scanner />
                 <BatchPanel />
+                <NftPanel />
+                <BufferPanel />
                 <TxStatus />
+                <TxExplainer />
             </main>
             <footer class="text-center text-text-muted text-sm py-4 font-mono">
                 "Zera Janitor — reclaim your rent"
@@ -1,4 +1,5 @@
 use leptos::prelude::*;
+use zera_client::nft::NftAccount;
 
 use crate::types::token_account::TokenAccountInfo;
 
@@ -14,6 +15,9 @@ pub fn AccountCard(
         "glass-panel hover:border-white/10"
     };
 
+    let nfts = use_context::<ReadSignal<Vec<NftAccount>>>();
+    let address = account.address.clone();
+    let is_nft = move || nfts.is_some_and(|nfts| nfts.get().iter().any(|n| n.account.address == address));
     let rent = account.rent_sol();
     let mint = account.mint_short();
     let addr_short = if account.address.len() > 8 {
@@ -48,16 +52,30 @@ pub fn AccountCard(
                     </div>
                     <span class="font-mono text-xs text-text-muted">{addr_short}</span>
                 </div>
+                {move || is_nft().then(|| view! {
+                    <span class="text-xs font-mono px-2 py-0.5 rounded border border-cyber-cyan/40 text-cyber-cyan">"NFT"</span>
+                })}
             </div>
             <div class="space-y-1.5">
                 <div class="flex justify-between text-sm">
                     <span class="text-text-muted">"Mint"</span>
                     <span class="font-mono text-xs">{mint}</span>
                 </div>
-                <div class="flex justify-between text-sm">
-                    <span class="text-text-muted">"Balance"</span>
-                    <span class="font-mono">{account.amount.to_string()}</span>
-                </div>
+                {if account.is_native {
+                    view! {
+                        <div class="flex justify-between text-sm">
+                            <span class="text-text-muted">"Wrapped SOL"</span>
+                            <span class="font-mono text-green-400">{format!("{:.6} SOL", account.wrapped_sol())}</span>
+                        </div>
+                    }.into_any()
+                } else {
+                    view! {
+                        <div class="flex justify-between text-sm">
+                            <span class="text-text-muted">"Balance"</span>
+                            <span class="font-mono">{account.amount.to_string()}</span>
+                        </div>
+                    }.into_any()
+                }}
                 <div class="flex justify-between text-sm">
                     <span class="text-text-muted">"Rent"</span>
                     <span class="font-mono text-cyber-cyan">{format!("{:.6} SOL", rent)}</span>
@@ -1,7 +1,20 @@
 use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use zera_client::batch::{batch_count, max_accounts_per_tx};
+use zera_client::fees;
+use zera_shared::config::ProgramConfig;
+use zera_shared::instruction::BatchCleanOptions;
+use zera_shared::FEE_BPS;
+use zera_client::pda::find_vault_pda;
+use zera_client::pubkey::decode_pubkey;
+use zera_client::tiers::FeeStatus;
 
+use crate::services::relayer::{fetch_relayer_info, RelayerInfo};
+use crate::services::storage::{recent_destinations, remember_destination};
 use crate::services::transaction::execute_batch_clean;
+use crate::types::referral::Referral;
 use crate::types::token_account::TokenAccountInfo;
+use crate::types::tx_result::TxResult;
 
 #[component]
 pub fn BatchPanel() -> impl IntoView {
@@ -9,26 +22,99 @@ pub fn BatchPanel() -> impl IntoView {
     let accounts = expect_context::<ReadSignal<Vec<TokenAccountInfo>>>();
     let selected = expect_context::<ReadSignal<Vec<usize>>>();
     let set_selected = expect_context::<WriteSignal<Vec<usize>>>();
-    let set_accounts = expect_context::<WriteSignal<Vec<TokenAccountInfo>>>();
     let processing = expect_context::<ReadSignal<bool>>();
     let set_processing = expect_context::<WriteSignal<bool>>();
-    let set_tx_sigs = expect_context::<WriteSignal<Vec<(String, String)>>>();
+    let set_tx_sigs = expect_context::<WriteSignal<Vec<TxResult>>>();
+    let referral = expect_context::<ReadSignal<Option<Referral>>>();
+    let fee_status = expect_context::<ReadSignal<Option<FeeStatus>>>();
+    let config = expect_context::<ReadSignal<Option<ProgramConfig>>>();
+    let paused = move || config.get().is_some_and(|c| c.paused);
+
+    // Optional payout destination (base58), empty = connected wallet
+    let (destination, set_destination) = signal(String::new());
+    let (recent, set_recent) = signal(recent_destinations());
+
+    // Gasless mode: the relayer pays the network fee and is reimbursed from the rent
+    let (gasless, set_gasless) = signal(false);
+    let (relayer, set_relayer) = signal(None::<Result<RelayerInfo, String>>);
+    let relayer_info = move || relayer.get().and_then(Result::ok).filter(|_| gasless.get());
+    let relayer_unavailable = move || gasless.get() && !matches!(relayer.get(), Some(Ok(_)));
+    let on_gasless = move |ev| {
+        let on = event_target_checked(&ev);
+        set_gasless.set(on);
+        if on {
+            set_relayer.set(None);
+            spawn_local(async move {
+                set_relayer.set(Some(fetch_relayer_info().await.map_err(|e| e.to_string())));
+            });
+        }
+    };
+
+    let destination_value = move || {
+        let d = destination.get().trim().to_string();
+        (!d.is_empty()).then_some(d)
+    };
+    let destination_error = move || {
+        let d = destination_value()?;
+        match decode_pubkey(&d) {
+            Err(_) => Some("Not a valid Solana address"),
+            Ok(key) if key == find_vault_pda().0 => Some("Payout cannot go to the janitor vault"),
+            Ok(_) => None,
+        }
+    };
+
+    // Accounts shared by every batch; each one present leaves room for fewer token accounts
+    let batch_options = move || {
+        let destination = destination_value().filter(|d| *d != wallet.get());
+        let mut options = BatchCleanOptions {
+            destination: destination.and_then(|d| decode_pubkey(&d).ok()),
+            referrer: referral.get().and_then(|r| decode_pubkey(&r.referrer).ok()),
+            relayer: relayer_info()
+                .and_then(|info| Some((decode_pubkey(&info.fee_payer).ok()?, info.relayer_fee))),
+            ..Default::default()
+        };
+        if let Some(status) = fee_status.get() {
+            status.apply(&mut options);
+        }
+        options
+    };
+    let max_per_tx = move || max_accounts_per_tx(&batch_options());
 
     let count = move || selected.get().len();
     let has_selection = move || count() > 0;
 
-    let total_rent = move || {
+    let fee_bps = move || fee_status.get().map_or(FEE_BPS as u16, |s| s.fee_bps());
+    let fee_label = move || format!("Fee ({}%)", fee_bps() as f64 / 100.0);
+    let tier_note = move || {
+        let status = fee_status.get()?;
+        let (_, tier) = status.tier()?;
+        let reason = if tier.min_holding > 0 && status.governance_holding() >= tier.min_holding {
+            format!("holding {} governance tokens", status.governance_holding())
+        } else {
+            format!("{} accounts closed", status.closed_accounts())
+        };
+        Some(format!("Reduced fee tier: {}% for {}", tier.fee_bps as f64 / 100.0, reason))
+    };
+
+    let preview = move || {
         let accs = accounts.get();
-        let sel = selected.get();
-        sel.iter()
-            .filter_map(|&i| accs.get(i))
-            .map(|a| a.lamports as f64)
-            .sum::<f64>()
-            / 1_000_000_000.0
+        let chosen: Vec<TokenAccountInfo> = selected
+            .get()
+            .iter()
+            .filter_map(|&i| accs.get(i).cloned())
+            .collect();
+        let referral_bps = referral.get().map_or(0, |r| r.referral_bps);
+        let max_per_tx = max_per_tx();
+        let preview = fees::preview(&chosen, max_per_tx, fee_bps(), referral_bps);
+        match relayer_info() {
+            Some(info) => preview.with_relayer_fee(info.relayer_fee, batch_count(chosen.len(), max_per_tx)),
+            None => preview,
+        }
     };
 
-    let fee_estimate = move || total_rent() * 0.05;
-    let user_gets = move || total_rent() - fee_estimate();
+    let total_rent = move || preview().total_rent_sol();
+    let fee_estimate = move || preview().fee_sol();
+    let user_gets = move || preview().payout_sol();
 
     let select_all = move |_| {
         let len = accounts.get().len();
@@ -40,14 +126,20 @@ pub fn BatchPanel() -> impl IntoView {
     };
 
     let on_incinerate = move |_| {
+        if paused() || destination_error().is_some() || relayer_unavailable() {
+            return;
+        }
+        if let Some(d) = destination_value().filter(|d| *d != wallet.get()) {
+            set_recent.set(remember_destination(&d));
+        }
         execute_batch_clean(
             wallet,
             accounts,
             selected,
+            batch_options(),
             set_processing,
             set_tx_sigs,
             set_selected,
-            set_accounts,
         );
     };
 
@@ -78,46 +170,128 @@ pub fn BatchPanel() -> impl IntoView {
                         <div class="text-2xl font-mono font-bold text-neon-rose">
                             {move || format!("{:.6}", fee_estimate())}
                         </div>
-                        <div class="text-xs text-text-muted mt-1">"Fee (5%)"</div>
+                        <div class="text-xs text-text-muted mt-1">{fee_label}</div>
                     </div>
                     <div class="text-center">
                         <div class="text-2xl font-mono font-bold text-green-400">
                             {move || format!("{:.6}", user_gets())}
                         </div>
-                        <div class="text-xs text-text-muted mt-1">"You Receive"</div>
+                        <div class="text-xs text-text-muted mt-1">
+                            {move || if destination_value().is_some() { "Destination Receives" } else { "You Receive" }}
+                        </div>
                     </div>
                 </div>
 
+                {move || {
+                    let unwrapped = preview().unwrapped_sol();
+                    (unwrapped > 0.0).then(|| view! {
+                        <p class="text-xs text-text-muted text-center">
+                            {format!("Includes {:.6} wrapped SOL unwrapped without a fee", unwrapped)}
+                        </p>
+                    })
+                }}
+
+                {move || tier_note().map(|note| view! {
+                    <p class="text-xs text-green-400 text-center">{note}</p>
+                })}
+
+                <div class="space-y-1">
+                    <label class="text-xs text-text-muted" for="payout-destination">
+                        "Payout destination (optional)"
+                    </label>
+                    <input
+                        id="payout-destination"
+                        list="recent-destinations"
+                        class="w-full bg-vault-dark/50 border border-white/10 rounded-lg px-3 py-2 font-mono text-sm focus:outline-none focus:border-cyber-cyan/50"
+                        placeholder="Connected wallet"
+                        prop:value=destination
+                        on:input=move |ev| set_destination.set(event_target_value(&ev))
+                    />
+                    <datalist id="recent-destinations">
+                        {move || recent.get().into_iter().map(|d| view! { <option value=d /> }).collect::<Vec<_>>()}
+                    </datalist>
+                    {move || destination_error().map(|e| view! {
+                        <p class="text-xs text-neon-rose">{e}</p>
+                    })}
+                </div>
+
+                {move || referral.get().map(|r| {
+                    let p = preview();
+                    let short = format!("{}...{}", &r.referrer[..4], &r.referrer[r.referrer.len() - 4..]);
+                    view! {
+                        <div class="flex justify-between text-xs text-text-muted px-1">
+                            <span>{format!("Treasury {:.6} SOL", p.treasury_fee_sol())}</span>
+                            <span title=r.referrer.clone()>
+                                {format!("Referrer {} {:.6} SOL", short, p.referral_fee_sol())}
+                            </span>
+                        </div>
+                    }
+                })}
+
+                <div class="space-y-1">
+                    <label class="flex items-center gap-2 text-xs text-text-muted cursor-pointer">
+                        <input type="checkbox" prop:checked=gasless on:change=on_gasless />
+                        "Gasless (relayer pays the network fee)"
+                    </label>
+                    {move || gasless.get().then(|| match relayer.get() {
+                        None => view! { <p class="text-xs text-text-muted">"Contacting relayer..."</p> }.into_any(),
+                        Some(Ok(info)) => view! {
+                            <p class="text-xs text-text-muted">
+                                {format!(
+                                    "Relayer fee {:.6} SOL per transaction, {:.6} SOL total",
+                                    fees::lamports_to_sol(info.relayer_fee),
+                                    preview().relayer_fee_sol()
+                                )}
+                            </p>
+                        }.into_any(),
+                        Some(Err(e)) => view! { <p class="text-xs text-neon-rose">{e}</p> }.into_any(),
+                    })}
+                </div>
+
                 {move || {
                     let n = count();
-                    let batches = (n + 24) / 25;
+                    let max_per_tx = max_per_tx();
+                    let batches = batch_count(n, max_per_tx);
                     (batches > 1).then(|| view! {
                         <p class="text-xs text-text-muted text-center">
-                            {format!("{} accounts across {} transactions (max 25/tx)", n, batches)}
+                            {format!("{} accounts across {} transactions (max {}/tx)", n, batches, max_per_tx)}
                         </p>
                     })
                 }}
 
-                <button
-                    class=move || {
-                        let base = "btn-danger w-full text-lg py-4 font-bold tracking-widest";
-                        if processing.get() {
-                            format!("{} opacity-70 pointer-events-none", base)
-                        } else {
-                            base.to_string()
-                        }
-                    }
-                    on:click=on_incinerate
-                    disabled=move || processing.get()
-                >
-                    {move || {
-                        if processing.get() {
-                            "PROCESSING...".to_string()
-                        } else {
-                            format!("INCINERATE {} ACCOUNTS", count())
-                        }
-                    }}
-                </button>
+                {move || if paused() {
+                    view! {
+                        <div class="rounded-lg border border-amber-400/40 bg-amber-400/10 px-4 py-3 text-center">
+                            <p class="font-semibold text-amber-300">"Maintenance in progress"</p>
+                            <p class="text-xs text-text-muted mt-1">
+                                "The janitor program is temporarily paused. Your accounts are safe; cleaning will resume shortly."
+                            </p>
+                        </div>
+                    }.into_any()
+                } else {
+                    view! {
+                        <button
+                            class=move || {
+                                let base = "btn-danger w-full text-lg py-4 font-bold tracking-widest";
+                                if processing.get() || destination_error().is_some() || relayer_unavailable() {
+                                    format!("{} opacity-70 pointer-events-none", base)
+                                } else {
+                                    base.to_string()
+                                }
+                            }
+                            on:click=on_incinerate
+                            disabled=move || processing.get() || destination_error().is_some() || relayer_unavailable()
+                        >
+                            {move || {
+                                if processing.get() {
+                                    "PROCESSING...".to_string()
+                                } else {
+                                    format!("INCINERATE {} ACCOUNTS", count())
+                                }
+                            }}
+                        </button>
+                    }.into_any()
+                }}
 
                 {move || processing.get().then(|| view! {
                     <div class="w-full bg-panel-dark rounded-full h-2 overflow-hidden">
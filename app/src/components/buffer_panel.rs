@@ -0,0 +1,61 @@
+use leptos::prelude::*;
+use zera_client::buffer::BufferAccount;
+use zera_client::fees::lamports_to_sol;
+
+use crate::services::transaction::execute_close_buffer;
+use crate::types::tx_result::TxResult;
+
+#[component]
+pub fn BufferPanel() -> impl IntoView {
+    let wallet = expect_context::<ReadSignal<String>>();
+    let buffers = expect_context::<ReadSignal<Vec<BufferAccount>>>();
+    let set_buffers = expect_context::<WriteSignal<Vec<BufferAccount>>>();
+    let processing = expect_context::<ReadSignal<bool>>();
+    let set_processing = expect_context::<WriteSignal<bool>>();
+    let set_tx_sigs = expect_context::<WriteSignal<Vec<TxResult>>>();
+
+    let total = move || lamports_to_sol(buffers.get().iter().map(|b| b.lamports).sum());
+
+    view! {
+        {move || (!buffers.get().is_empty()).then(|| view! {
+            <div class="glass-panel p-6 space-y-4">
+                <div class="flex items-center justify-between">
+                    <div>
+                        <h2 class="text-lg font-semibold">"Deploy Buffers"</h2>
+                        <p class="text-xs text-text-muted mt-1">
+                            "Leftover program deploy buffers you are the authority of. Closing refunds their full balance."
+                        </p>
+                    </div>
+                    <span class="font-mono text-green-400">{move || format!("{:.6} SOL", total())}</span>
+                </div>
+                <div class="space-y-2">
+                    {move || buffers.get().into_iter().map(|buffer| {
+                        let address = buffer.address.clone();
+                        let lamports = buffer.lamports;
+                        let on_close = move |_| {
+                            if processing.get() {
+                                return;
+                            }
+                            execute_close_buffer(wallet, buffer.clone(), set_processing, set_tx_sigs, set_buffers);
+                        };
+                        view! {
+                            <div class="flex items-center justify-between gap-4 text-sm">
+                                <span class="font-mono text-xs truncate">{address}</span>
+                                <span class="font-mono text-green-400">
+                                    {format!("{:.6} SOL", lamports_to_sol(lamports))}
+                                </span>
+                                <button
+                                    class="btn-primary px-3 py-1 text-xs font-bold"
+                                    disabled=move || processing.get()
+                                    on:click=on_close
+                                >
+                                    "CLOSE"
+                                </button>
+                            </div>
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+            </div>
+        })}
+    }
+}
@@ -1,7 +1,9 @@
 use leptos::prelude::*;
 
 use crate::services::wallet::{connect_wallet, disconnect_wallet};
+use crate::types::rpc_status::RpcStatus;
 use crate::types::token_account::TokenAccountInfo;
+use crate::types::tx_result::TxResult;
 
 #[component]
 pub fn Header() -> impl IntoView {
@@ -9,7 +11,8 @@ pub fn Header() -> impl IntoView {
     let set_wallet = expect_context::<WriteSignal<String>>();
     let set_accounts = expect_context::<WriteSignal<Vec<TokenAccountInfo>>>();
     let set_selected = expect_context::<WriteSignal<Vec<usize>>>();
-    let set_tx_sigs = expect_context::<WriteSignal<Vec<(String, String)>>>();
+    let set_tx_sigs = expect_context::<WriteSignal<Vec<TxResult>>>();
+    let rpc_status = expect_context::<ReadSignal<Option<RpcStatus>>>();
 
     let connected = move || !wallet.get().is_empty();
 
@@ -32,7 +35,20 @@ pub fn Header() -> impl IntoView {
                     "Zera "<span class="text-cyber-cyan">"Janitor"</span>
                 </h1>
             </div>
-            <div>
+            <div class="flex items-center gap-4">
+                {move || rpc_status.get().map(|status| {
+                    let (dot, latency) = match status.latency_ms {
+                        Some(ms) => ("w-2 h-2 rounded-full bg-cyber-cyan", format!("{} ms", ms)),
+                        None => ("w-2 h-2 rounded-full bg-neon-rose", "unreachable".to_string()),
+                    };
+                    view! {
+                        <div class="flex items-center gap-2 text-xs font-mono text-text-muted" title=status.url.clone()>
+                            <span class=dot></span>
+                            <span>{status.host().to_string()}</span>
+                            <span>{latency}</span>
+                        </div>
+                    }
+                })}
                 {move || {
                     if connected() {
                         view! {
@@ -1,7 +1,11 @@
 pub mod account_card;
 pub mod batch_panel;
+pub mod buffer_panel;
 pub mod header;
+pub mod nft_panel;
+pub mod reclaim_card;
 pub mod scanner;
 #[allow(dead_code)]
 pub mod theme;
+pub mod tx_explainer;
 pub mod tx_status;
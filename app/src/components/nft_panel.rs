@@ -0,0 +1,80 @@
+use leptos::prelude::*;
+use zera_client::fees::lamports_to_sol;
+use zera_client::nft::NftAccount;
+use zera_shared::config::ProgramConfig;
+
+use crate::services::transaction::execute_burn_nft;
+use crate::types::tx_result::TxResult;
+
+#[component]
+pub fn NftPanel() -> impl IntoView {
+    let wallet = expect_context::<ReadSignal<String>>();
+    let nfts = expect_context::<ReadSignal<Vec<NftAccount>>>();
+    let set_nfts = expect_context::<WriteSignal<Vec<NftAccount>>>();
+    let processing = expect_context::<ReadSignal<bool>>();
+    let set_processing = expect_context::<WriteSignal<bool>>();
+    let set_tx_sigs = expect_context::<WriteSignal<Vec<TxResult>>>();
+    let config = expect_context::<ReadSignal<Option<ProgramConfig>>>();
+    let paused = move || config.get().is_some_and(|c| c.paused);
+
+    // Burning is irreversible: the first click arms the button, the second burns
+    let (armed, set_armed) = signal(None::<String>);
+
+    let burnable = move || nfts.get().into_iter().filter(NftAccount::is_burnable).collect::<Vec<_>>();
+
+    view! {
+        {move || (!burnable().is_empty()).then(|| view! {
+            <div class="glass-panel p-6 space-y-4">
+                <div>
+                    <h2 class="text-lg font-semibold">"Burn NFTs"</h2>
+                    <p class="text-xs text-text-muted mt-1">
+                        "Burning destroys the NFT and reclaims its token account, metadata and master edition rent."
+                    </p>
+                </div>
+                <div class="space-y-2">
+                    {move || burnable().into_iter().map(|nft| {
+                        let address = nft.account.address.clone();
+                        let (fee, payout) = nft.burn_fee_split();
+                        let is_armed = {
+                            let address = address.clone();
+                            move || armed.get().as_deref() == Some(address.as_str())
+                        };
+                        let mint = nft.account.mint_short();
+                        let on_burn = {
+                            let is_armed = is_armed.clone();
+                            move |_| {
+                                if processing.get() || paused() {
+                                    return;
+                                }
+                                if !is_armed() {
+                                    set_armed.set(Some(address.clone()));
+                                    return;
+                                }
+                                set_armed.set(None);
+                                execute_burn_nft(wallet, nft.clone(), set_processing, set_tx_sigs, set_nfts);
+                            }
+                        };
+                        view! {
+                            <div class="flex items-center justify-between gap-4 text-sm">
+                                <span class="font-mono text-xs">{mint}</span>
+                                <span class="font-mono text-green-400">
+                                    {format!("{:.6} SOL", lamports_to_sol(payout))}
+                                </span>
+                                <span class="font-mono text-xs text-text-muted">
+                                    {format!("fee {:.6}", lamports_to_sol(fee))}
+                                </span>
+                                <button
+                                    class="btn-danger px-3 py-1 text-xs font-bold"
+                                    disabled=move || processing.get() || paused()
+                                    on:click=on_burn
+                                >
+                                    {move || if is_armed() { "CONFIRM BURN" } else { "BURN" }}
+                                </button>
+                            </div>
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+            </div>
+        })}
+    }
+}
@@ -0,0 +1,57 @@
+use leptos::prelude::*;
+use zera_client::reclaim::ReclaimableAccount;
+
+use crate::services::transaction::execute_reclaim;
+use crate::types::tx_result::TxResult;
+
+/// Grid card for a nonce or stake account, withdrawn on its own rather than batched.
+#[component]
+pub fn ReclaimCard(account: ReclaimableAccount) -> impl IntoView {
+    let wallet = expect_context::<ReadSignal<String>>();
+    let processing = expect_context::<ReadSignal<bool>>();
+    let set_processing = expect_context::<WriteSignal<bool>>();
+    let set_tx_sigs = expect_context::<WriteSignal<Vec<TxResult>>>();
+    let set_reclaimable = expect_context::<WriteSignal<Vec<ReclaimableAccount>>>();
+
+    let kind = account.kind.label();
+    let balance = account.sol();
+    let cooling_down = account.cooling_down;
+    let addr_short = if account.address.len() > 8 {
+        format!(
+            "{}...{}",
+            &account.address[..4],
+            &account.address[account.address.len() - 4..]
+        )
+    } else {
+        account.address.clone()
+    };
+
+    let on_reclaim = move |_| {
+        if processing.get() || cooling_down {
+            return;
+        }
+        execute_reclaim(wallet, account.clone(), set_processing, set_tx_sigs, set_reclaimable);
+    };
+
+    view! {
+        <div class="glass-panel hover:border-white/10 p-4 transition-all duration-200">
+            <div class="flex items-start justify-between mb-3">
+                <span class="font-mono text-xs text-text-muted">{addr_short}</span>
+                <span class="text-xs font-mono px-2 py-0.5 rounded border border-cyber-cyan/40 text-cyber-cyan">{kind}</span>
+            </div>
+            <div class="space-y-1.5">
+                <div class="flex justify-between text-sm">
+                    <span class="text-text-muted">{if cooling_down { "Cooling down" } else { "Withdrawable" }}</span>
+                    <span class="font-mono text-cyber-cyan">{format!("{:.6} SOL", balance)}</span>
+                </div>
+                <button
+                    class="btn-primary w-full mt-2 py-1 text-xs font-bold"
+                    disabled=move || processing.get() || cooling_down
+                    on:click=on_reclaim
+                >
+                    {if cooling_down { "COOLING DOWN" } else { "RECLAIM" }}
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -1,6 +1,10 @@
 use leptos::prelude::*;
+use zera_client::buffer::BufferAccount;
+use zera_client::nft::NftAccount;
+use zera_client::reclaim::ReclaimableAccount;
 
 use crate::components::account_card::AccountCard;
+use crate::components::reclaim_card::ReclaimCard;
 use crate::services::scanner::scan_token_accounts;
 use crate::types::token_account::TokenAccountInfo;
 
@@ -11,11 +15,15 @@ pub fn Scanner() -> impl IntoView {
     let scanning = expect_context::<ReadSignal<bool>>();
     let set_scanning = expect_context::<WriteSignal<bool>>();
     let set_accounts = expect_context::<WriteSignal<Vec<TokenAccountInfo>>>();
+    let set_nfts = expect_context::<WriteSignal<Vec<NftAccount>>>();
+    let set_buffers = expect_context::<WriteSignal<Vec<BufferAccount>>>();
+    let reclaimable = expect_context::<ReadSignal<Vec<ReclaimableAccount>>>();
+    let set_reclaimable = expect_context::<WriteSignal<Vec<ReclaimableAccount>>>();
     let selected = expect_context::<ReadSignal<Vec<usize>>>();
     let set_selected = expect_context::<WriteSignal<Vec<usize>>>();
 
     let connected = move || !wallet.get().is_empty();
-    let has_accounts = move || !accounts.get().is_empty();
+    let has_accounts = move || !accounts.get().is_empty() || !reclaimable.get().is_empty();
 
     let on_scan = move |_| {
         let pubkey = wallet.get();
@@ -24,7 +32,15 @@ pub fn Scanner() -> impl IntoView {
         }
         set_scanning.set(true);
         set_selected.set(vec![]);
-        scan_token_accounts(pubkey, set_accounts, set_scanning);
+        scan_token_accounts(
+            pubkey,
+            set_accounts,
+            set_selected,
+            set_nfts,
+            set_buffers,
+            set_reclaimable,
+            set_scanning,
+        );
     };
 
     let toggle = move |idx: usize| {
@@ -61,7 +77,10 @@ pub fn Scanner() -> impl IntoView {
                     </button>
                     {move || has_accounts().then(|| view! {
                         <span class="text-sm text-text-muted">
-                            {move || format!("{} closeable accounts found", accounts.get().len())}
+                            {move || {
+                                let found = accounts.get().len() + reclaimable.get().len();
+                                format!("{} closeable accounts found", found)
+                            }}
                         </span>
                     })}
                 </div>
@@ -74,7 +93,6 @@ pub fn Scanner() -> impl IntoView {
                     <div class="grid grid-cols-1 sm:grid-cols-2 lg:grid-cols-3 gap-3">
                         {accs.into_iter().enumerate().map(|(idx, acc)| {
                             let is_selected = sel.contains(&idx);
-                            let toggle = toggle.clone();
                             view! {
                                 <div
                                     class="animate-cascade"
@@ -88,6 +106,11 @@ pub fn Scanner() -> impl IntoView {
                                 </div>
                             }
                         }).collect::<Vec<_>>()}
+                        {reclaimable.get().into_iter().map(|account| view! {
+                            <div class="animate-cascade">
+                                <ReclaimCard account=account />
+                            </div>
+                        }).collect::<Vec<_>>()}
                     </div>
                 }
             })}
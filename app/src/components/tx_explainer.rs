@@ -0,0 +1,113 @@
+use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use zera_client::explain::{explain_transaction, TxExplanation};
+use zera_client::fees::lamports_to_sol;
+
+use crate::services::rpc::GlooTransport;
+
+#[component]
+pub fn TxExplainer() -> impl IntoView {
+    let (signature, set_signature) = signal(String::new());
+    let (loading, set_loading) = signal(false);
+    let (result, set_result) = signal(None::<Result<TxExplanation, String>>);
+
+    let on_explain = move |_| {
+        let sig = signature.get().trim().to_string();
+        if sig.is_empty() {
+            return;
+        }
+        set_loading.set(true);
+        spawn_local(async move {
+            let outcome = explain_transaction(&GlooTransport, &sig)
+                .await
+                .map_err(|e| e.to_string());
+            set_result.set(Some(outcome));
+            set_loading.set(false);
+        });
+    };
+
+    view! {
+        <div class="glass-panel p-6 space-y-4">
+            <h2 class="text-lg font-semibold">"Explain Transaction"</h2>
+            <div class="flex gap-3">
+                <input
+                    class="flex-1 bg-vault-dark/50 border border-white/10 rounded-lg px-3 py-2 font-mono text-sm focus:outline-none focus:border-cyber-cyan/50"
+                    placeholder="Transaction signature"
+                    prop:value=signature
+                    on:input=move |ev| set_signature.set(event_target_value(&ev))
+                />
+                <button
+                    class="btn-primary text-sm"
+                    on:click=on_explain
+                    disabled=move || loading.get()
+                >
+                    {move || if loading.get() { "Loading..." } else { "Explain" }}
+                </button>
+            </div>
+            {move || result.get().map(|outcome| match outcome {
+                Err(e) => view! {
+                    <p class="text-sm text-neon-rose">{e}</p>
+                }.into_any(),
+                Ok(tx) => view! { <ExplanationView tx=tx /> }.into_any(),
+            })}
+        </div>
+    }
+}
+
+#[component]
+fn ExplanationView(tx: TxExplanation) -> impl IntoView {
+    let sol = |lamports: u64| format!("{:.6} SOL", lamports_to_sol(lamports));
+    let (status, status_color) = match &tx.error {
+        None => ("Success".to_string(), "text-green-400"),
+        Some(err) => (format!("Failed: {}", err), "text-neon-rose"),
+    };
+
+    view! {
+        <div class="space-y-3 text-sm">
+            <div class=format!("font-semibold {}", status_color)>{status}</div>
+            <div class="grid grid-cols-2 gap-x-6 gap-y-1.5">
+                <span class="text-text-muted">"Slot"</span>
+                <span class="font-mono">{tx.slot.to_string()}</span>
+                <span class="text-text-muted">"User"</span>
+                <span class="font-mono text-xs break-all">{tx.user.clone()}</span>
+                {(tx.destination != tx.user).then(|| view! {
+                    <span class="text-text-muted">"Paid to"</span>
+                    <span class="font-mono text-xs break-all">{tx.destination.clone()}</span>
+                })}
+                <span class="text-text-muted">"Rent collected"</span>
+                <span class="font-mono text-cyber-cyan">{sol(tx.rent_collected)}</span>
+                {(tx.unwrapped > 0).then(|| view! {
+                    <span class="text-text-muted">"SOL unwrapped"</span>
+                    <span class="font-mono text-cyber-cyan">{format!("{} (no fee)", sol(tx.unwrapped))}</span>
+                })}
+                <span class="text-text-muted">"Protocol fee"</span>
+                <span class="font-mono text-neon-rose">{format!("{} ({} bps)", sol(tx.fee), tx.fee_bps)}</span>
+                {tx.referrer.clone().map(|referrer| view! {
+                    <span class="text-text-muted">"Referral share"</span>
+                    <span class="font-mono text-xs break-all">
+                        {format!("{} to {}", sol(tx.referral_fee), referrer)}
+                    </span>
+                })}
+                {(tx.relayer_fee > 0).then(|| view! {
+                    <span class="text-text-muted">"Relayer fee"</span>
+                    <span class="font-mono">{sol(tx.relayer_fee)}</span>
+                })}
+                <span class="text-text-muted">"Payout"</span>
+                <span class="font-mono text-green-400">{sol(tx.payout)}</span>
+                <span class="text-text-muted">"Network fee"</span>
+                <span class="font-mono">{sol(tx.network_fee)}</span>
+            </div>
+            <div class="space-y-1">
+                <div class="text-text-muted">
+                    {format!("{} token accounts", tx.closed_accounts.len())}
+                </div>
+                {tx.closed_accounts.into_iter().map(|acc| view! {
+                    <div class="flex justify-between py-1 px-3 rounded-lg bg-vault-dark/50 font-mono text-xs">
+                        <span class="break-all">{acc.address}</span>
+                        <span class="text-cyber-cyan">{sol(acc.lamports)}</span>
+                    </div>
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
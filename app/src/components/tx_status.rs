@@ -1,8 +1,10 @@
 use leptos::prelude::*;
 
+use crate::types::tx_result::TxResult;
+
 #[component]
 pub fn TxStatus() -> impl IntoView {
-    let tx_sigs = expect_context::<ReadSignal<Vec<(String, String)>>>();
+    let tx_sigs = expect_context::<ReadSignal<Vec<TxResult>>>();
 
     let has_sigs = move || !tx_sigs.get().is_empty();
 
@@ -13,34 +15,42 @@ pub fn TxStatus() -> impl IntoView {
                 <div class="glass-panel p-6 space-y-3">
                     <h2 class="text-lg font-semibold">"Transactions"</h2>
                     <div class="space-y-2">
-                        {sigs.into_iter().map(|(sig, status)| {
-                            let sig_short = if sig.len() > 16 {
-                                format!("{}...{}", &sig[..8], &sig[sig.len()-8..])
-                            } else {
-                                sig.clone()
-                            };
-                            let status_color = match status.as_str() {
-                                "confirmed" => "text-green-400",
-                                "error" => "text-neon-rose",
-                                _ => "text-yellow-400",
-                            };
-                            let explorer_url = format!(
-                                "https://explorer.solana.com/tx/{}?cluster=custom&customUrl=http%3A%2F%2F127.0.0.1%3A8899",
-                                sig
-                            );
-                            view! {
-                                <div class="flex items-center justify-between py-2 px-3 rounded-lg bg-vault-dark/50">
-                                    <a
-                                        href=explorer_url
-                                        target="_blank"
-                                        class="font-mono text-sm text-cyber-cyan hover:underline"
-                                    >
-                                        {sig_short}
-                                    </a>
-                                    <span class=format!("text-sm font-semibold {}", status_color)>
-                                        {status}
-                                    </span>
-                                </div>
+                        {sigs.into_iter().map(|result| {
+                            let pending = matches!(result, TxResult::Pending { .. });
+                            match result {
+                                TxResult::Pending { signature } | TxResult::Confirmed { signature } => {
+                                    let (status, status_class) = match pending {
+                                        true => ("pending", "text-sm font-semibold text-text-muted"),
+                                        false => ("confirmed", "text-sm font-semibold text-green-400"),
+                                    };
+                                    let sig_short = if signature.len() > 16 {
+                                        format!("{}...{}", &signature[..8], &signature[signature.len()-8..])
+                                    } else {
+                                        signature.clone()
+                                    };
+                                    let explorer_url = format!(
+                                        "https://explorer.solana.com/tx/{}?cluster=custom&customUrl=http%3A%2F%2F127.0.0.1%3A8899",
+                                        signature
+                                    );
+                                    view! {
+                                        <div class="flex items-center justify-between py-2 px-3 rounded-lg bg-vault-dark/50">
+                                            <a
+                                                href=explorer_url
+                                                target="_blank"
+                                                class="font-mono text-sm text-cyber-cyan hover:underline"
+                                            >
+                                                {sig_short}
+                                            </a>
+                                            <span class=status_class>{status}</span>
+                                        </div>
+                                    }.into_any()
+                                }
+                                TxResult::Failed { error } => view! {
+                                    <div class="flex items-center justify-between gap-4 py-2 px-3 rounded-lg bg-neon-rose/10 border border-neon-rose/20">
+                                        <span class="text-sm text-neon-rose">{error.to_string()}</span>
+                                        <span class="text-sm font-semibold text-neon-rose">"error"</span>
+                                    </div>
+                                }.into_any(),
                             }
                         }).collect::<Vec<_>>()}
                     </div>
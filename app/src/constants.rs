@@ -1,3 +1,14 @@
-pub const RPC_URL: &str = "http://127.0.0.1:8899";
+/// RPC endpoints in order of preference; failover skips unhealthy or lagging ones.
+pub const RPC_URLS: &[&str] = &["http://127.0.0.1:8899"];
 
-pub use zera_shared::{PROGRAM_ID, TREASURY_PUBKEY, VAULT_SEED};
+/// A single RPC round trip is abandoned after this long.
+pub const RPC_TIMEOUT_MS: u32 = 15_000;
+
+/// How often the RPC endpoints are health-checked and re-ranked.
+pub const RPC_HEALTH_INTERVAL_MS: u32 = 30_000;
+
+/// Delay before reopening a dropped PubSub WebSocket.
+pub const WS_RECONNECT_MS: u32 = 3_000;
+
+/// Fee-paying relayer used when gasless cleaning is switched on (`zera-relayer`).
+pub const RELAYER_URL: &str = "http://127.0.0.1:8080";
@@ -0,0 +1,320 @@
+//! Live updates over the RPC node's PubSub WebSocket.
+//!
+//! One socket carries every subscription: `programSubscribe` for new closeable
+//! token accounts of the wallet, `accountSubscribe` on each listed account to
+//! see it closed, and `signatureSubscribe` on submitted transactions. When the
+//! socket drops, everything still watched is subscribed again on a new one.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use gloo_timers::future::TimeoutFuture;
+use leptos::prelude::*;
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{MessageEvent, WebSocket};
+use zera_client::program_error::describe_transaction_error;
+use zera_client::pubkey::decode_pubkey;
+use zera_client::pubsub::{self, PubsubMessage};
+use zera_client::rpc::SPL_TOKEN_PROGRAM;
+use zera_client::scanner::{apply_account_update, owner_filters, AccountChange};
+
+use crate::constants::WS_RECONNECT_MS;
+use crate::services::rpc::active_url;
+use crate::types::token_account::TokenAccountInfo;
+use crate::types::tx_result::{TxError, TxResult};
+
+/// The scanner grid live account updates are applied to.
+#[derive(Clone, Copy)]
+struct Grid {
+    set_accounts: WriteSignal<Vec<TokenAccountInfo>>,
+    set_selected: WriteSignal<Vec<usize>>,
+}
+
+/// What a subscription keeps up to date.
+#[derive(Clone)]
+enum Watch {
+    /// New closeable token accounts of the wallet.
+    Wallet { owner: [u8; 32], grid: Grid },
+    /// One listed token account, until it is closed or stops being closeable.
+    Account { address: String, grid: Grid },
+    /// A submitted transaction, until it is confirmed.
+    Signature { signature: String, set_tx_sigs: WriteSignal<Vec<TxResult>> },
+}
+
+impl Watch {
+    fn subscribe_request(&self) -> (u64, Value) {
+        match self {
+            Watch::Wallet { owner, .. } => pubsub::program_subscribe(SPL_TOKEN_PROGRAM, owner_filters(owner)),
+            Watch::Account { address, .. } => pubsub::account_subscribe(address),
+            Watch::Signature { signature, .. } => pubsub::signature_subscribe(signature),
+        }
+    }
+
+    /// Subscription kind, as in `{kind}Subscribe`.
+    fn kind(&self) -> &'static str {
+        match self {
+            Watch::Wallet { .. } => "program",
+            Watch::Account { .. } => "account",
+            Watch::Signature { .. } => "signature",
+        }
+    }
+}
+
+struct Socket {
+    ws: WebSocket,
+    _on_open: Closure<dyn FnMut()>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut()>,
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        self.ws.set_onopen(None);
+        self.ws.set_onmessage(None);
+        self.ws.set_onclose(None);
+        let _ = self.ws.close();
+    }
+}
+
+#[derive(Default)]
+struct State {
+    socket: Option<Socket>,
+    /// Requests made before the socket opened.
+    queue: Vec<String>,
+    /// Subscribe requests waiting for their subscription id, by request id.
+    pending: HashMap<u64, Watch>,
+    subscriptions: HashMap<u64, Watch>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::default();
+}
+
+/// Follow `owner`'s closeable token accounts: new ones join the grid and listed ones leave it
+/// once the chain confirms they were closed. Replaces the previous wallet's subscriptions.
+pub fn watch_wallet(
+    owner: &str,
+    accounts: &[TokenAccountInfo],
+    set_accounts: WriteSignal<Vec<TokenAccountInfo>>,
+    set_selected: WriteSignal<Vec<usize>>,
+) {
+    unwatch_wallet();
+    let owner = match decode_pubkey(owner) {
+        Ok(owner) => owner,
+        Err(e) => {
+            log::error!("Cannot watch wallet: {}", e);
+            return;
+        }
+    };
+    let grid = Grid { set_accounts, set_selected };
+    subscribe(Watch::Wallet { owner, grid });
+    for account in accounts {
+        subscribe(Watch::Account { address: account.address.clone(), grid });
+    }
+}
+
+/// Stop following the wallet's token accounts.
+pub fn unwatch_wallet() {
+    let cancelled: Vec<(u64, &'static str)> = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.pending.retain(|_, watch| matches!(watch, Watch::Signature { .. }));
+        let wallet: Vec<u64> = state
+            .subscriptions
+            .iter()
+            .filter(|(_, watch)| !matches!(watch, Watch::Signature { .. }))
+            .map(|(&subscription, _)| subscription)
+            .collect();
+        wallet
+            .into_iter()
+            .filter_map(|subscription| {
+                let watch = state.subscriptions.remove(&subscription)?;
+                Some((subscription, watch.kind()))
+            })
+            .collect()
+    });
+    for (subscription, kind) in cancelled {
+        send(pubsub::unsubscribe(kind, subscription).1);
+    }
+}
+
+/// Turn `signature`'s `Pending` entry into `Confirmed` or `Failed` once the chain decides.
+pub fn watch_signature(signature: &str, set_tx_sigs: WriteSignal<Vec<TxResult>>) {
+    subscribe(Watch::Signature { signature: signature.to_string(), set_tx_sigs });
+}
+
+fn subscribe(watch: Watch) {
+    let (id, body) = watch.subscribe_request();
+    STATE.with(|state| state.borrow_mut().pending.insert(id, watch));
+    send(body);
+}
+
+/// Send `body` now if the socket is open, otherwise once it is, connecting if needed.
+fn send(body: Value) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let text = body.to_string();
+        match &state.socket {
+            Some(socket) if socket.ws.ready_state() == WebSocket::OPEN => {
+                if let Err(e) = socket.ws.send_with_str(&text) {
+                    log::warn!("WebSocket send failed: {:?}", e);
+                }
+            }
+            Some(_) => state.queue.push(text),
+            None => {
+                state.queue.push(text);
+                state.socket = connect();
+            }
+        }
+    });
+}
+
+fn connect() -> Option<Socket> {
+    let url = pubsub::ws_url(active_url());
+    let ws = match WebSocket::new(&url) {
+        Ok(ws) => ws,
+        Err(e) => {
+            log::error!("Failed to open {}: {:?}", url, e);
+            return None;
+        }
+    };
+
+    let on_open = {
+        let ws = ws.clone();
+        Closure::<dyn FnMut()>::new(move || {
+            let queue = STATE.with(|state| std::mem::take(&mut state.borrow_mut().queue));
+            for text in queue {
+                if let Err(e) = ws.send_with_str(&text) {
+                    log::warn!("WebSocket send failed: {:?}", e);
+                }
+            }
+        })
+    };
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(|event: MessageEvent| {
+        let Some(text) = event.data().as_string() else { return };
+        match pubsub::parse_message(&text) {
+            Ok(message) => handle(message),
+            Err(e) => log::warn!("Unreadable PubSub message: {}", e),
+        }
+    });
+    let on_close = Closure::<dyn FnMut()>::new(move || {
+        log::warn!("PubSub connection closed, reconnecting in {} ms", WS_RECONNECT_MS);
+        // The socket owns this closure: drop it from a later task, not from inside the callback
+        spawn_local(async {
+            TimeoutFuture::new(WS_RECONNECT_MS).await;
+            reconnect();
+        });
+    });
+    ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+    Some(Socket { ws, _on_open: on_open, _on_message: on_message, _on_close: on_close })
+}
+
+/// Replace a closed socket, subscribing again to everything still watched.
+fn reconnect() {
+    let (socket, watches) = STATE.with(|state| {
+        let state = &mut *state.borrow_mut();
+        state.queue.clear();
+        let watches: Vec<Watch> =
+            state.pending.drain().chain(state.subscriptions.drain()).map(|(_, watch)| watch).collect();
+        (state.socket.take(), watches)
+    });
+    drop(socket);
+    for watch in watches {
+        subscribe(watch);
+    }
+}
+
+fn handle(message: PubsubMessage) {
+    match message {
+        PubsubMessage::Reply { id, result } => STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            // Unsubscribe replies and subscriptions given up on before their reply have no watch
+            let Some(watch) = state.pending.remove(&id) else { return };
+            match result.map(|subscription| subscription.as_u64()) {
+                Ok(Some(subscription)) => {
+                    state.subscriptions.insert(subscription, watch);
+                }
+                Ok(None) => log::warn!("{}Subscribe returned no subscription id", watch.kind()),
+                Err(e) => log::warn!("{}Subscribe failed: {}", watch.kind(), e),
+            }
+        }),
+        PubsubMessage::Signature { subscription, err } => {
+            // Signature subscriptions end with their only notification
+            let watch = STATE.with(|state| state.borrow_mut().subscriptions.remove(&subscription));
+            if let Some(Watch::Signature { signature, set_tx_sigs }) = watch {
+                settle(&signature, err, set_tx_sigs);
+            }
+        }
+        PubsubMessage::Program { subscription, account: (address, lamports, data) } => {
+            if let Some(Watch::Wallet { grid, .. }) = watch(subscription) {
+                apply(grid, &address, lamports, &data);
+            }
+        }
+        PubsubMessage::Account { subscription, lamports, data } => {
+            if let Some(Watch::Account { address, grid }) = watch(subscription) {
+                apply(grid, &address, lamports, &data);
+            }
+        }
+    }
+}
+
+fn watch(subscription: u64) -> Option<Watch> {
+    STATE.with(|state| state.borrow().subscriptions.get(&subscription).cloned())
+}
+
+fn apply(grid: Grid, address: &str, lamports: u64, data: &[u8]) {
+    let mut change = AccountChange::Unchanged;
+    grid.set_accounts.update(|accounts| change = apply_account_update(accounts, address, lamports, data));
+    match change {
+        AccountChange::Added => subscribe(Watch::Account { address: address.to_string(), grid }),
+        AccountChange::Removed(index) => {
+            // Selection is by index: forget the removed account and shift the ones after it
+            grid.set_selected.update(|selected| {
+                selected.retain(|&i| i != index);
+                for i in selected.iter_mut().filter(|i| **i > index) {
+                    *i -= 1;
+                }
+            });
+            unwatch_account(address);
+        }
+        AccountChange::Updated | AccountChange::Unchanged => {}
+    }
+}
+
+fn unwatch_account(address: &str) {
+    let subscription = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let subscription = state
+            .subscriptions
+            .iter()
+            .find(|(_, watch)| matches!(watch, Watch::Account { address: a, .. } if a == address))
+            .map(|(&subscription, _)| subscription)?;
+        state.subscriptions.remove(&subscription);
+        Some(subscription)
+    });
+    if let Some(subscription) = subscription {
+        send(pubsub::unsubscribe("account", subscription).1);
+    }
+}
+
+fn settle(signature: &str, err: Option<Value>, set_tx_sigs: WriteSignal<Vec<TxResult>>) {
+    let outcome = match err {
+        None => TxResult::Confirmed { signature: signature.to_string() },
+        Some(err) => {
+            let reason = describe_transaction_error(&err, &[]);
+            log::error!("Transaction {} failed: {}", signature, reason);
+            TxResult::Failed { error: TxError::Other(format!("Transaction failed: {}", reason)) }
+        }
+    };
+    set_tx_sigs.update(|results| {
+        for result in results.iter_mut() {
+            if matches!(result, TxResult::Pending { signature: s } if s == signature) {
+                *result = outcome.clone();
+            }
+        }
+    });
+}
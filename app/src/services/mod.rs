@@ -1,4 +1,8 @@
+pub mod live;
 pub mod rpc;
+pub mod referral;
+pub mod relayer;
 pub mod scanner;
+pub mod storage;
 pub mod transaction;
 pub mod wallet;
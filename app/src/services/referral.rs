@@ -0,0 +1,14 @@
+use zera_client::pubkey::decode_pubkey;
+
+/// Read a valid `?ref=<pubkey>` from the page URL.
+pub fn referrer_from_url() -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    let referrer = web_sys::UrlSearchParams::new_with_str(&search).ok()?.get("ref")?;
+    match decode_pubkey(&referrer) {
+        Ok(_) => Some(referrer),
+        Err(e) => {
+            log::warn!("Ignoring invalid referrer {}: {}", referrer, e);
+            None
+        }
+    }
+}
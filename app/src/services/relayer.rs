@@ -0,0 +1,25 @@
+use gloo_net::http::Request;
+use serde::Deserialize;
+use zera_client::{ClientError, RpcError};
+
+use crate::constants::RELAYER_URL;
+
+/// What the relayer's `GET /info` reports.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayerInfo {
+    /// Relayer key paying the network fee (base58)
+    pub fee_payer: String,
+    /// Lamports reimbursed to the relayer per transaction.
+    pub relayer_fee: u64,
+}
+
+pub async fn fetch_relayer_info() -> Result<RelayerInfo, ClientError> {
+    Request::get(&format!("{}/info", RELAYER_URL))
+        .send()
+        .await
+        .map_err(|e| RpcError::Transport(format!("Relayer unreachable: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| ClientError::InvalidResponse(format!("Invalid relayer info: {}", e)))
+}
@@ -1,72 +1,135 @@
+use std::rc::Rc;
+
+use futures::future::{select, Either};
 use gloo_net::http::Request;
-use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use gloo_timers::future::TimeoutFuture;
+use serde_json::Value;
+use zera_client::failover::{probe_endpoint, Failover};
+use zera_client::jsonrpc::{self, Backoff};
+use zera_client::{rpc, ClientError, RpcError, RpcTransport};
 
-use crate::constants::RPC_URL;
+use crate::constants::{RPC_TIMEOUT_MS, RPC_URLS};
+use crate::types::rpc_status::RpcStatus;
 
-#[derive(Serialize)]
-struct RpcRequest {
-    jsonrpc: &'static str,
-    id: u32,
-    method: String,
-    params: Value,
+thread_local! {
+    static ENDPOINTS: Rc<Failover<GlooEndpoint>> =
+        Rc::new(Failover::new(RPC_URLS.iter().map(|&url| GlooEndpoint { url }).collect()));
 }
 
-#[derive(Deserialize, Debug)]
-pub struct RpcResponse {
-    pub result: Option<Value>,
-    pub error: Option<Value>,
+fn endpoints() -> Rc<Failover<GlooEndpoint>> {
+    ENDPOINTS.with(Rc::clone)
 }
 
-pub async fn rpc_request(method: &str, params: Value) -> Result<Value, String> {
-    let body = RpcRequest {
-        jsonrpc: "2.0",
-        id: 1,
-        method: method.to_string(),
-        params,
-    };
+/// Browser transport for `zera-client`, backed by `gloo-net`, failing over across [`RPC_URLS`].
+pub struct GlooTransport;
+
+impl RpcTransport for GlooTransport {
+    async fn request(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+        endpoints().request(method, params).await
+    }
+
+    async fn request_batch(&self, requests: Vec<(&str, Value)>) -> Result<Vec<Value>, ClientError> {
+        endpoints().request_batch(requests).await
+    }
+}
+
+/// A single RPC endpoint.
+struct GlooEndpoint {
+    url: &'static str,
+}
 
-    let resp = Request::post(RPC_URL)
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .map_err(|e| format!("Request build error: {}", e))?
-        .send()
-        .await
-        .map_err(|e| format!("Network error: {}", e))?;
-
-    let rpc_resp: RpcResponse = resp
-        .json()
-        .await
-        .map_err(|e| format!("JSON parse error: {}", e))?;
-
-    if let Some(err) = rpc_resp.error {
-        return Err(format!("RPC error: {}", err));
+impl RpcTransport for GlooEndpoint {
+    async fn request(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let (_, body) = jsonrpc::request_body(method, params);
+        let response = post_with_backoff(self.url, &body).await?;
+        Ok(jsonrpc::parse_response(response)?)
     }
 
-    rpc_resp.result.ok_or_else(|| "No result in response".to_string())
+    async fn request_batch(&self, requests: Vec<(&str, Value)>) -> Result<Vec<Value>, ClientError> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (ids, body) = jsonrpc::batch_body(requests);
+        let response = post_with_backoff(self.url, &body).await?;
+        Ok(jsonrpc::parse_batch_response(&ids, response)?)
+    }
+}
+
+/// The endpoint requests currently go to.
+pub fn active_url() -> &'static str {
+    let endpoints = endpoints();
+    endpoints.active().map_or(RPC_URLS[0], |index| endpoints.endpoints()[index].url)
 }
 
-pub async fn get_token_accounts_by_owner(owner: &str) -> Result<Value, String> {
-    let params = json!([
-        owner,
-        { "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" },
-        { "encoding": "jsonParsed" }
-    ]);
-    rpc_request("getTokenAccountsByOwner", params).await
+/// Probe every endpoint, re-rank them and report the one now in use.
+pub async fn check_endpoints() -> RpcStatus {
+    let endpoints = endpoints();
+    let mut slots = Vec::new();
+    let mut latencies = Vec::new();
+    for endpoint in endpoints.endpoints() {
+        let started = js_sys::Date::now();
+        let slot = probe_endpoint(endpoint).await.ok();
+        slots.push(slot);
+        latencies.push(slot.map(|_| (js_sys::Date::now() - started) as u32));
+    }
+    endpoints.update(&slots);
+
+    let active = endpoints.active().unwrap_or_default();
+    RpcStatus {
+        url: active_url().to_string(),
+        latency_ms: latencies.get(active).copied().flatten(),
+        slot: slots.get(active).copied().flatten(),
+    }
+}
+
+/// POST `body`, retrying rate limits and server errors with exponential backoff.
+async fn post_with_backoff(url: &str, body: &Value) -> Result<Value, RpcError> {
+    let backoff = Backoff::default();
+    let mut attempt = 0;
+    loop {
+        match post(url, body).await {
+            Err(e) => match backoff.delay_ms(&e, attempt) {
+                Some(delay) => {
+                    log::warn!("{}, retrying in {} ms", e, delay);
+                    TimeoutFuture::new(delay).await;
+                    attempt += 1;
+                }
+                None => return Err(e),
+            },
+            ok => return ok,
+        }
+    }
+}
+
+/// One POST to the RPC node, abandoned after [`RPC_TIMEOUT_MS`].
+async fn post(url: &str, body: &Value) -> Result<Value, RpcError> {
+    let send = async {
+        let resp = Request::post(url)
+            .header("Content-Type", "application/json")
+            .json(body)
+            .map_err(|e| RpcError::Transport(format!("Request build error: {}", e)))?
+            .send()
+            .await
+            .map_err(|e| RpcError::Transport(format!("Network error: {}", e)))?;
+        if !resp.ok() {
+            return Err(RpcError::Http(resp.status()));
+        }
+        resp.json::<Value>()
+            .await
+            .map_err(|e| RpcError::Decode(format!("JSON parse error: {}", e)))
+    };
+
+    match select(Box::pin(send), TimeoutFuture::new(RPC_TIMEOUT_MS)).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(RpcError::Timeout(RPC_TIMEOUT_MS)),
+    }
 }
 
-pub async fn get_latest_blockhash() -> Result<String, String> {
-    let result = rpc_request("getLatestBlockhash", json!([])).await?;
-    result["value"]["blockhash"]
-        .as_str()
-        .map(|s| s.to_string())
-        .ok_or_else(|| "Missing blockhash".to_string())
+pub async fn get_latest_blockhash() -> Result<String, ClientError> {
+    rpc::get_latest_blockhash(&GlooTransport).await
 }
 
 #[allow(dead_code)]
-pub async fn get_balance(pubkey: &str) -> Result<u64, String> {
-    let result = rpc_request("getBalance", json!([pubkey])).await?;
-    result["value"]
-        .as_u64()
-        .ok_or_else(|| "Invalid balance".to_string())
+pub async fn get_balance(pubkey: &str) -> Result<u64, ClientError> {
+    rpc::get_balance(&GlooTransport, pubkey).await
 }
@@ -1,48 +1,29 @@
 use leptos::prelude::*;
 use wasm_bindgen_futures::spawn_local;
+use zera_client::buffer::{scan_buffers, BufferAccount};
+use zera_client::nft::{scan_nfts, NftAccount};
+use zera_client::reclaim::{scan_reclaimable, ReclaimableAccount};
+use zera_client::scanner::scan_closeable_sliced;
 
-use crate::services::rpc::get_token_accounts_by_owner;
+use crate::services::live::watch_wallet;
+use crate::services::rpc::GlooTransport;
 use crate::types::token_account::TokenAccountInfo;
 
-/// Dust threshold: accounts with balance below this are considered closeable.
-const DUST_THRESHOLD: u64 = 0;
-
+/// Scan the wallet, then keep its closeable token accounts up to date live.
 pub fn scan_token_accounts(
     owner_pubkey: String,
     set_accounts: WriteSignal<Vec<TokenAccountInfo>>,
+    set_selected: WriteSignal<Vec<usize>>,
+    set_nfts: WriteSignal<Vec<NftAccount>>,
+    set_buffers: WriteSignal<Vec<BufferAccount>>,
+    set_reclaimable: WriteSignal<Vec<ReclaimableAccount>>,
     set_scanning: WriteSignal<bool>,
 ) {
     spawn_local(async move {
-        match get_token_accounts_by_owner(&owner_pubkey).await {
-            Ok(result) => {
-                let mut closeable = Vec::new();
-
-                if let Some(accounts) = result["value"].as_array() {
-                    for acc in accounts {
-                        let address = acc["pubkey"].as_str().unwrap_or_default().to_string();
-                        let info = &acc["account"]["data"]["parsed"]["info"];
-
-                        let amount = info["tokenAmount"]["amount"]
-                            .as_str()
-                            .and_then(|s| s.parse::<u64>().ok())
-                            .unwrap_or(0);
-
-                        let mint = info["mint"].as_str().unwrap_or_default().to_string();
-
-                        let lamports = acc["account"]["lamports"].as_u64().unwrap_or(0);
-
-                        if amount <= DUST_THRESHOLD {
-                            closeable.push(TokenAccountInfo {
-                                address,
-                                mint,
-                                amount,
-                                lamports,
-                            });
-                        }
-                    }
-                }
-
+        match scan_closeable_sliced(&GlooTransport, &owner_pubkey).await {
+            Ok(closeable) => {
                 log::info!("Found {} closeable accounts", closeable.len());
+                watch_wallet(&owner_pubkey, &closeable, set_accounts, set_selected);
                 set_accounts.set(closeable);
             }
             Err(e) => {
@@ -50,6 +31,36 @@ pub fn scan_token_accounts(
                 set_accounts.set(vec![]);
             }
         }
+        match scan_nfts(&GlooTransport, &owner_pubkey).await {
+            Ok(nfts) => {
+                log::info!("Found {} NFT accounts", nfts.len());
+                set_nfts.set(nfts);
+            }
+            Err(e) => {
+                log::error!("NFT scan failed: {}", e);
+                set_nfts.set(vec![]);
+            }
+        }
+        match scan_buffers(&GlooTransport, &owner_pubkey).await {
+            Ok(buffers) => {
+                log::info!("Found {} deploy buffers", buffers.len());
+                set_buffers.set(buffers);
+            }
+            Err(e) => {
+                log::error!("Buffer scan failed: {}", e);
+                set_buffers.set(vec![]);
+            }
+        }
+        match scan_reclaimable(&GlooTransport, &owner_pubkey).await {
+            Ok(reclaimable) => {
+                log::info!("Found {} nonce and stake accounts", reclaimable.len());
+                set_reclaimable.set(reclaimable);
+            }
+            Err(e) => {
+                log::error!("Nonce and stake scan failed: {}", e);
+                set_reclaimable.set(vec![]);
+            }
+        }
         set_scanning.set(false);
     });
 }
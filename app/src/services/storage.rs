@@ -0,0 +1,31 @@
+/// `localStorage` key holding recently used payout destinations as a JSON array.
+const RECENT_DESTINATIONS_KEY: &str = "zera.recentDestinations";
+/// How many payout destinations to remember.
+const MAX_RECENT_DESTINATIONS: usize = 5;
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Payout destinations used before, most recent first.
+pub fn recent_destinations() -> Vec<String> {
+    local_storage()
+        .and_then(|storage| storage.get_item(RECENT_DESTINATIONS_KEY).ok()?)
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Move `destination` to the front of the recent list and persist it.
+pub fn remember_destination(destination: &str) -> Vec<String> {
+    let mut recent = recent_destinations();
+    recent.retain(|d| d != destination);
+    recent.insert(0, destination.to_string());
+    recent.truncate(MAX_RECENT_DESTINATIONS);
+
+    if let (Some(storage), Ok(raw)) = (local_storage(), serde_json::to_string(&recent)) {
+        if let Err(e) = storage.set_item(RECENT_DESTINATIONS_KEY, &raw) {
+            log::warn!("Failed to save recent destinations: {:?}", e);
+        }
+    }
+    recent
+}
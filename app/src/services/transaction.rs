@@ -1,11 +1,18 @@
 use leptos::prelude::*;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::spawn_local;
+use zera_client::batch::{build_batches, max_accounts_per_tx};
+use zera_client::buffer::{build_close_buffer_instruction, BufferAccount};
+use zera_client::nft::{build_burn_instruction, NftAccount};
+use zera_client::program_error::classify_send_error;
+use zera_client::pubkey::encode_pubkey;
+use zera_client::reclaim::{build_reclaim_instruction, ReclaimableAccount};
+use zera_shared::instruction::{BatchCleanOptions, Instruction};
 
-use crate::constants::{PROGRAM_ID, TREASURY_PUBKEY, VAULT_SEED};
-use crate::services::rpc::get_latest_blockhash;
-use crate::types::instruction::build_batch_clean_data;
+use crate::services::live::watch_signature;
+use crate::services::rpc::{active_url, get_latest_blockhash};
 use crate::types::token_account::TokenAccountInfo;
+use crate::types::tx_result::{TxError, TxResult};
 
 #[wasm_bindgen]
 extern "C" {
@@ -14,20 +21,33 @@ extern "C" {
         instruction_bytes: &[u8],
         account_metas: JsValue,
         blockhash: &str,
+        fee_payer: &str,
         rpc_url: &str,
     ) -> Result<JsValue, JsValue>;
-}
 
-const MAX_ACCOUNTS_PER_TX: usize = 25;
+    #[wasm_bindgen(js_name = zeraSignAndRelay, catch)]
+    async fn zera_sign_and_relay(
+        instruction_bytes: &[u8],
+        account_metas: JsValue,
+        blockhash: &str,
+        fee_payer: &str,
+        relayer_url: &str,
+    ) -> Result<JsValue, JsValue>;
+}
 
+/// Close the selected accounts in batches as large as `options` leaves room for.
+///
+/// `options` carries the payout destination, referrer and fee tier accounts shared by every batch.
+/// With `options.relayer` set, the wallet only signs and the relayer pays and submits. Closed
+/// accounts leave the grid through the live subscriptions once the chain confirms it.
 pub fn execute_batch_clean(
     wallet: ReadSignal<String>,
     accounts: ReadSignal<Vec<TokenAccountInfo>>,
     selected: ReadSignal<Vec<usize>>,
+    options: BatchCleanOptions,
     set_processing: WriteSignal<bool>,
-    set_tx_sigs: WriteSignal<Vec<(String, String)>>,
+    set_tx_sigs: WriteSignal<Vec<TxResult>>,
     set_selected: WriteSignal<Vec<usize>>,
-    set_accounts: WriteSignal<Vec<TokenAccountInfo>>,
 ) {
     let user_pubkey = wallet.get();
     let all_accounts = accounts.get();
@@ -45,68 +65,161 @@ pub fn execute_batch_clean(
     set_processing.set(true);
 
     spawn_local(async move {
-        let program_id = bs58::encode(&PROGRAM_ID).into_string();
-        let treasury = bs58::encode(&TREASURY_PUBKEY).into_string();
-        let vault_pubkey = derive_vault_pda_bs58();
-        let rpc_url = crate::constants::RPC_URL;
+        let relayer = options.relayer.map(|(fee_payer, _)| encode_pubkey(&fee_payer));
 
-        let chunks: Vec<Vec<TokenAccountInfo>> = selected_accounts
-            .chunks(MAX_ACCOUNTS_PER_TX)
-            .map(|c| c.to_vec())
-            .collect();
+        let max_per_tx = max_accounts_per_tx(&options);
+        let batches = match build_batches(&user_pubkey, &options, &selected_accounts, max_per_tx) {
+            Ok(batches) => batches,
+            Err(e) => {
+                log::error!("Failed to build batches: {}", e);
+                set_tx_sigs.update(|existing| {
+                    existing.push(TxResult::Failed { error: TxError::Other(e.to_string()) })
+                });
+                set_processing.set(false);
+                return;
+            }
+        };
 
         let mut sigs = Vec::new();
+        for batch in &batches {
+            sigs.push(sign_and_send(&batch.instruction, &user_pubkey, relayer.as_deref()).await);
+        }
 
-        for chunk in &chunks {
-            let num = chunk.len() as u8;
-            let ix_data = build_batch_clean_data(num);
-
-            let mut metas = Vec::new();
-            metas.push(account_meta(&user_pubkey, true, true, &program_id));
-            metas.push(account_meta(&vault_pubkey, false, true, &program_id));
-            metas.push(account_meta(&treasury, false, true, &program_id));
-            let spl_token_id = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
-            metas.push(account_meta(spl_token_id, false, false, &program_id));
-            for acc in chunk {
-                metas.push(account_meta(&acc.address, false, true, &program_id));
-            }
+        record_results(sigs, set_tx_sigs);
+        set_processing.set(false);
+        set_selected.set(vec![]);
+    });
+}
 
-            let metas_js = serde_wasm_bindgen::to_value(&metas).unwrap_or(JsValue::NULL);
-
-            match get_latest_blockhash().await {
-                Ok(blockhash) => {
-                    match zera_sign_and_send(&ix_data, metas_js, &blockhash, rpc_url).await {
-                        Ok(sig_val) => {
-                            let sig = sig_val.as_string().unwrap_or_default();
-                            log::info!("Transaction sent: {}", sig);
-                            sigs.push((sig, "confirmed".to_string()));
-                        }
-                        Err(e) => {
-                            let err_msg = format!("{:?}", e);
-                            log::error!("Transaction failed: {}", err_msg);
-                            sigs.push((err_msg, "error".to_string()));
-                        }
-                    }
-                }
-                Err(e) => {
-                    log::error!("Failed to get blockhash: {}", e);
-                    sigs.push((e, "error".to_string()));
-                }
-            }
+/// Burn one NFT through the janitor, reclaiming its token, metadata and edition rent.
+pub fn execute_burn_nft(
+    wallet: ReadSignal<String>,
+    nft: NftAccount,
+    set_processing: WriteSignal<bool>,
+    set_tx_sigs: WriteSignal<Vec<TxResult>>,
+    set_nfts: WriteSignal<Vec<NftAccount>>,
+) {
+    let user_pubkey = wallet.get();
+    if user_pubkey.is_empty() {
+        return;
+    }
+    set_processing.set(true);
+
+    spawn_local(async move {
+        let result = match build_burn_instruction(&user_pubkey, &nft) {
+            Ok(ix) => sign_and_send(&ix, &user_pubkey, None).await,
+            Err(e) => TxResult::Failed { error: TxError::Other(e.to_string()) },
+        };
+        if matches!(result, TxResult::Pending { .. }) {
+            set_nfts.update(|nfts| nfts.retain(|n| n.account.address != nft.account.address));
         }
+        record_results(vec![result], set_tx_sigs);
+        set_processing.set(false);
+    });
+}
 
-        set_tx_sigs.update(|existing| existing.extend(sigs));
+/// Close one of the wallet's deploy buffers with the loader's `Close`, refunding its lamports.
+pub fn execute_close_buffer(
+    wallet: ReadSignal<String>,
+    buffer: BufferAccount,
+    set_processing: WriteSignal<bool>,
+    set_tx_sigs: WriteSignal<Vec<TxResult>>,
+    set_buffers: WriteSignal<Vec<BufferAccount>>,
+) {
+    let user_pubkey = wallet.get();
+    if user_pubkey.is_empty() {
+        return;
+    }
+    set_processing.set(true);
+
+    spawn_local(async move {
+        let result = match build_close_buffer_instruction(&buffer, &user_pubkey) {
+            Ok(ix) => sign_and_send(&ix, &user_pubkey, None).await,
+            Err(e) => TxResult::Failed { error: TxError::Other(e.to_string()) },
+        };
+        if matches!(result, TxResult::Pending { .. }) {
+            set_buffers.update(|buffers| buffers.retain(|b| b.address != buffer.address));
+        }
+        record_results(vec![result], set_tx_sigs);
+        set_processing.set(false);
+    });
+}
+
+/// Withdraw a nonce or stake account's full balance back to the wallet.
+pub fn execute_reclaim(
+    wallet: ReadSignal<String>,
+    account: ReclaimableAccount,
+    set_processing: WriteSignal<bool>,
+    set_tx_sigs: WriteSignal<Vec<TxResult>>,
+    set_reclaimable: WriteSignal<Vec<ReclaimableAccount>>,
+) {
+    let user_pubkey = wallet.get();
+    if user_pubkey.is_empty() {
+        return;
+    }
+    set_processing.set(true);
+
+    spawn_local(async move {
+        let result = match build_reclaim_instruction(&account, &user_pubkey) {
+            Ok(ix) => sign_and_send(&ix, &user_pubkey, None).await,
+            Err(e) => TxResult::Failed { error: TxError::Other(e.to_string()) },
+        };
+        if matches!(result, TxResult::Pending { .. }) {
+            set_reclaimable.update(|accounts| accounts.retain(|a| a.address != account.address));
+        }
+        record_results(vec![result], set_tx_sigs);
         set_processing.set(false);
-        set_selected.set(vec![]);
-        let remaining: Vec<TokenAccountInfo> = accounts
-            .get()
-            .into_iter()
-            .filter(|a| !selected_accounts.iter().any(|s| s.address == a.address))
-            .collect();
-        set_accounts.set(remaining);
     });
 }
 
+/// List submitted transactions and follow the pending ones until the chain confirms them.
+fn record_results(results: Vec<TxResult>, set_tx_sigs: WriteSignal<Vec<TxResult>>) {
+    for result in &results {
+        if let TxResult::Pending { signature } = result {
+            watch_signature(signature, set_tx_sigs);
+        }
+    }
+    set_tx_sigs.update(|existing| existing.extend(results));
+}
+
+/// Have the wallet sign `ix` and submit it, through the relayer when `relayer` is set.
+async fn sign_and_send(ix: &Instruction, wallet: &str, relayer: Option<&str>) -> TxResult {
+    let blockhash = match get_latest_blockhash().await {
+        Ok(blockhash) => blockhash,
+        Err(e) => {
+            log::error!("Failed to get blockhash: {}", e);
+            return TxResult::Failed { error: TxError::Other(e.to_string()) };
+        }
+    };
+    let metas_js = serde_wasm_bindgen::to_value(&account_metas(ix)).unwrap_or(JsValue::NULL);
+    let fee_payer = fee_payer(wallet, relayer);
+    let sent = match relayer {
+        Some(_) => {
+            let relayer_url = crate::constants::RELAYER_URL;
+            zera_sign_and_relay(&ix.data, metas_js, &blockhash, fee_payer, relayer_url).await
+        }
+        None => zera_sign_and_send(&ix.data, metas_js, &blockhash, fee_payer, active_url()).await,
+    };
+    match sent {
+        Ok(sig_val) => {
+            let signature = sig_val.as_string().unwrap_or_default();
+            log::info!("Transaction sent: {}", signature);
+            TxResult::Pending { signature }
+        }
+        Err(e) => {
+            log::error!("Transaction failed: {:?}", e);
+            TxResult::Failed { error: decode_js_error(&e) }
+        }
+    }
+}
+
+/// The account paying `ix`'s fee: the relayer when set, otherwise the connected wallet.
+///
+/// Never the instruction's first account, which is the buffer or stake account for some.
+fn fee_payer<'a>(wallet: &'a str, relayer: Option<&'a str>) -> &'a str {
+    relayer.unwrap_or(wallet)
+}
+
 #[derive(serde::Serialize)]
 struct AccountMeta {
     pubkey: String,
@@ -118,22 +231,74 @@ struct AccountMeta {
     program_id: String,
 }
 
-fn account_meta(pubkey: &str, is_signer: bool, is_writable: bool, program_id: &str) -> AccountMeta {
-    AccountMeta {
-        pubkey: pubkey.to_string(),
-        is_signer,
-        is_writable,
-        program_id: program_id.to_string(),
-    }
+/// Convert a shared instruction into the account list the JS shim expects.
+fn account_metas(ix: &Instruction) -> Vec<AccountMeta> {
+    let program_id = encode_pubkey(&ix.program_id);
+    ix.accounts
+        .iter()
+        .map(|meta| AccountMeta {
+            pubkey: encode_pubkey(&meta.pubkey),
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+            program_id: program_id.clone(),
+        })
+        .collect()
 }
 
-fn derive_vault_pda_bs58() -> String {
-    let program_id = bs58::encode(&PROGRAM_ID).into_string();
-    let seed_str = std::str::from_utf8(VAULT_SEED).unwrap_or("zera-vault");
-    log::info!(
-        "Vault PDA derivation pending — program: {}, seed: {}",
-        program_id,
-        seed_str
-    );
-    String::from("11111111111111111111111111111111")
+/// Decode a wallet or `sendRawTransaction` rejection into a [`TxError`].
+///
+/// Reads `message`, the wallet adapter `code` and web3.js simulation `logs`
+/// off the thrown object when present.
+fn decode_js_error(err: &JsValue) -> TxError {
+    let field = |key: &str| {
+        js_sys::Reflect::get(err, &JsValue::from_str(key))
+            .ok()
+            .filter(|v| !v.is_undefined() && !v.is_null())
+    };
+
+    let message = err
+        .as_string()
+        .or_else(|| field("message").and_then(|m| m.as_string()))
+        .unwrap_or_else(|| format!("{:?}", err));
+    let code = field("code").and_then(|c| c.as_f64()).map(|c| c as i64);
+    let logs: Vec<String> = field("logs")
+        .filter(|l| l.is_array())
+        .map(|l| js_sys::Array::from(&l).iter().filter_map(|v| v.as_string()).collect())
+        .unwrap_or_default();
+
+    classify_send_error(&message, code, &logs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zera_client::reclaim::ReclaimKind;
+
+    const WALLET: [u8; 32] = [1u8; 32];
+
+    #[test]
+    fn the_wallet_pays_to_close_a_buffer() {
+        let wallet = encode_pubkey(&WALLET);
+        let buffer =
+            BufferAccount { address: encode_pubkey(&[2u8; 32]), authority: wallet.clone(), lamports: 1 };
+        let ix = build_close_buffer_instruction(&buffer, &wallet).unwrap();
+
+        assert_eq!(account_metas(&ix)[0].pubkey, buffer.address);
+        assert_eq!(fee_payer(&wallet, None), wallet);
+        assert_eq!(fee_payer(&wallet, Some("relayer")), "relayer");
+    }
+
+    #[test]
+    fn the_wallet_pays_to_reclaim_nonce_and_stake_accounts() {
+        let wallet = encode_pubkey(&WALLET);
+        for kind in [ReclaimKind::Nonce, ReclaimKind::Stake] {
+            let address = encode_pubkey(&[3u8; 32]);
+            let authority = wallet.clone();
+            let account = ReclaimableAccount { address, kind, lamports: 1, authority, cooling_down: false };
+            let ix = build_reclaim_instruction(&account, &wallet).unwrap();
+
+            assert_eq!(account_metas(&ix)[0].pubkey, account.address);
+            assert_eq!(fee_payer(&wallet, None), wallet);
+        }
+    }
 }
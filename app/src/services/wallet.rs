@@ -2,7 +2,9 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 
 use leptos::prelude::*;
+use crate::services::live::unwatch_wallet;
 use crate::types::token_account::TokenAccountInfo;
+use crate::types::tx_result::TxResult;
 
 /// Get the wallet provider object (`window.phantom.solana`, `window.solana`, or `window.backpack`).
 fn get_provider() -> Option<js_sys::Object> {
@@ -107,7 +109,7 @@ pub fn disconnect_wallet(
     set_wallet: WriteSignal<String>,
     set_accounts: WriteSignal<Vec<TokenAccountInfo>>,
     set_selected: WriteSignal<Vec<usize>>,
-    set_tx_sigs: WriteSignal<Vec<(String, String)>>,
+    set_tx_sigs: WriteSignal<Vec<TxResult>>,
 ) {
     spawn_local(async move {
         if let Some(provider) = get_provider() {
@@ -120,6 +122,7 @@ pub fn disconnect_wallet(
             }
         }
 
+        unwatch_wallet();
         set_wallet.set(String::new());
         set_accounts.set(vec![]);
         set_selected.set(vec![]);
@@ -1,2 +1,4 @@
-pub mod instruction;
+pub mod referral;
+pub mod rpc_status;
 pub mod token_account;
+pub mod tx_result;
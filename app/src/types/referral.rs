@@ -0,0 +1,8 @@
+/// Partner who routed the user here via `?ref=<pubkey>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Referral {
+    /// Referrer wallet (base58), already validated.
+    pub referrer: String,
+    /// Referrer share of the protocol fee from the program config, in basis points.
+    pub referral_bps: u16,
+}
@@ -0,0 +1,20 @@
+/// The RPC endpoint requests go to, as of the last health check.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcStatus {
+    pub url: String,
+    /// Round trip of the health check, `None` if the endpoint failed it.
+    pub latency_ms: Option<u32>,
+    pub slot: Option<u64>,
+}
+
+impl RpcStatus {
+    pub fn healthy(&self) -> bool {
+        self.latency_ms.is_some()
+    }
+
+    /// `host:port` without the scheme, for display.
+    pub fn host(&self) -> &str {
+        let host = self.url.split_once("://").map_or(self.url.as_str(), |(_, rest)| rest);
+        host.trim_end_matches('/')
+    }
+}
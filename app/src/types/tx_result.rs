@@ -0,0 +1,10 @@
+pub use zera_client::program_error::TxError;
+
+/// Outcome of one submitted batch, as listed in `TxStatus`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TxResult {
+    /// Submitted, waiting for the chain to confirm it.
+    Pending { signature: String },
+    Confirmed { signature: String },
+    Failed { error: TxError },
+}
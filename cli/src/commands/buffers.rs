@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use serde::Serialize;
+use solana_sdk::signature::Signer;
+use zera_client::buffer::{build_close_buffer_instruction, scan_buffers, BufferAccount};
+use zera_client::RpcTransport;
+
+use super::tx::{read_keypair, send_and_confirm};
+use super::{print_json, sol, CommandResult};
+
+#[derive(Serialize)]
+struct CloseBuffersReport {
+    authority: String,
+    dry_run: bool,
+    buffers: Vec<BufferAccount>,
+    total_lamports: u64,
+    signatures: Vec<String>,
+    errors: Vec<String>,
+}
+
+/// Close the keypair's leftover program deploy buffers, returning their lamports to it.
+pub async fn close<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    dry_run: bool,
+    json: bool,
+) -> CommandResult {
+    let keypair = read_keypair(keypair_path)?;
+    let authority = keypair.pubkey().to_string();
+
+    let buffers = scan_buffers(transport, &authority).await?;
+    let mut report = CloseBuffersReport {
+        total_lamports: buffers.iter().map(|b| b.lamports).sum(),
+        authority,
+        dry_run,
+        buffers,
+        signatures: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    if !dry_run {
+        for buffer in &report.buffers {
+            let ix = build_close_buffer_instruction(buffer, &report.authority)?;
+            match send_and_confirm(transport, &keypair, &ix).await {
+                Ok(signature) => report.signatures.push(signature),
+                Err(e) => report.errors.push(format!("{}: {}", buffer.address, e)),
+            }
+        }
+    }
+
+    if json {
+        print_json(&report)?;
+    } else {
+        let verb = if dry_run { "Would close" } else { "Closed" };
+        println!("{} {} deploy buffers of {}", verb, report.buffers.len(), report.authority);
+        for buffer in &report.buffers {
+            println!("  {}  {}", buffer.address, sol(buffer.lamports));
+        }
+        println!("Total: {}", sol(report.total_lamports));
+        for error in &report.errors {
+            println!("FAILED {}", error);
+        }
+    }
+
+    if !report.errors.is_empty() {
+        let total = report.buffers.len();
+        return Err(format!("{} of {} buffers failed to close", report.errors.len(), total).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use std::path::Path;
+
+use serde::Serialize;
+use solana_sdk::signature::Signer;
+use zera_client::batch::{build_batches, max_accounts_per_tx};
+use zera_client::fees::{self, FeePreview};
+use zera_client::pubkey::decode_pubkey;
+use zera_client::scanner::{scan_closeable, scan_closeable_sliced};
+use zera_client::tiers::fetch_fee_status;
+use zera_client::RpcTransport;
+use zera_shared::instruction::BatchCleanOptions;
+
+use super::tx::{read_keypair, send_and_confirm};
+use super::{print_json, sol, CommandResult};
+
+#[derive(Serialize)]
+struct CleanReport {
+    owner: String,
+    /// Wallet receiving the payout; the owner if not set.
+    destination: Option<String>,
+    referrer: Option<String>,
+    /// Index of the fee tier applied, `None` at the standard rate.
+    fee_tier: Option<usize>,
+    dry_run: bool,
+    preview: FeePreview,
+    batches: Vec<BatchReport>,
+}
+
+#[derive(Serialize)]
+struct BatchReport {
+    accounts: Vec<String>,
+    preview: FeePreview,
+    signature: Option<String>,
+    error: Option<String>,
+}
+
+/// Flags of the `clean` subcommand.
+pub struct CleanOptions {
+    /// Wallet receiving the payout instead of the keypair (base58).
+    pub destination: Option<String>,
+    /// Partner wallet sharing the protocol fee (base58).
+    pub referrer: Option<String>,
+    pub dry_run: bool,
+    /// Scan full accounts with `getTokenAccountsByOwner` instead of the sliced scan.
+    pub full: bool,
+    pub max_per_tx: usize,
+}
+
+pub async fn run<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    options: CleanOptions,
+    json: bool,
+) -> CommandResult {
+    let CleanOptions { destination, referrer, dry_run, full, max_per_tx } = options;
+    let keypair = read_keypair(keypair_path)?;
+    let owner = keypair.pubkey().to_string();
+
+    let accounts = match full {
+        true => scan_closeable(transport, &owner).await?,
+        false => scan_closeable_sliced(transport, &owner).await?,
+    };
+    let fee_status = fetch_fee_status(transport, &keypair.pubkey().to_bytes()).await?;
+    if fee_status.config.paused && !dry_run {
+        return Err("The janitor program is paused for maintenance".into());
+    }
+    let mut batch_options = BatchCleanOptions {
+        destination: destination.as_deref().map(decode_pubkey).transpose()?,
+        referrer: referrer.as_deref().map(decode_pubkey).transpose()?,
+        ..Default::default()
+    };
+    fee_status.apply(&mut batch_options);
+    // Every optional account leaves room for one token account fewer
+    let max_per_tx = max_per_tx.min(max_accounts_per_tx(&batch_options));
+    let batches = build_batches(&owner, &batch_options, &accounts, max_per_tx)?;
+    let fee_bps = fee_status.fee_bps();
+    let referral_bps = match batch_options.referrer {
+        Some(_) => fee_status.config.referral_bps,
+        None => 0,
+    };
+
+    let mut report = CleanReport {
+        owner,
+        destination,
+        referrer,
+        fee_tier: fee_status.tier().map(|(index, _)| index),
+        dry_run,
+        preview: fees::preview(&accounts, max_per_tx, fee_bps, referral_bps),
+        batches: Vec::with_capacity(batches.len()),
+    };
+
+    for batch in &batches {
+        let mut entry = BatchReport {
+            accounts: batch.accounts.iter().map(|a| a.address.clone()).collect(),
+            preview: fees::preview(&batch.accounts, max_per_tx, fee_bps, referral_bps),
+            signature: None,
+            error: None,
+        };
+        if !dry_run {
+            match send_and_confirm(transport, &keypair, &batch.instruction).await {
+                Ok(sig) => entry.signature = Some(sig),
+                Err(e) => entry.error = Some(e.to_string()),
+            }
+        }
+        if !json {
+            print_batch(&entry);
+        }
+        report.batches.push(entry);
+    }
+
+    let failed = report.batches.iter().filter(|b| b.error.is_some()).count();
+
+    if json {
+        print_json(&report)?;
+    } else {
+        print_summary(&report);
+    }
+
+    if failed > 0 {
+        return Err(format!("{} of {} batches failed", failed, report.batches.len()).into());
+    }
+    Ok(())
+}
+
+fn print_batch(entry: &BatchReport) {
+    let outcome = match (&entry.signature, &entry.error) {
+        (Some(sig), _) => format!("confirmed {}", sig),
+        (_, Some(err)) => format!("FAILED {}", err),
+        _ => "planned".to_string(),
+    };
+    println!(
+        "{} accounts, {} rent, {} fee: {}",
+        entry.accounts.len(),
+        sol(entry.preview.total_rent),
+        sol(entry.preview.fee),
+        outcome
+    );
+}
+
+fn print_summary(report: &CleanReport) {
+    if report.batches.is_empty() {
+        println!("Nothing to clean for {}", report.owner);
+        return;
+    }
+    // Only confirmed batches count as closed; a dry run plans every batch
+    let (done, failed): (Vec<&BatchReport>, Vec<&BatchReport>) =
+        report.batches.iter().partition(|b| b.error.is_none());
+    let verb = if report.dry_run { "Would close" } else { "Closed" };
+    println!(
+        "{} {} accounts in {} transactions for {}",
+        verb,
+        done.iter().map(|b| b.accounts.len()).sum::<usize>(),
+        done.len(),
+        report.owner
+    );
+    let preview = done.iter().map(|b| b.preview).fold(
+        FeePreview { fee_bps: report.preview.fee_bps, ..Default::default() },
+        |acc, p| FeePreview {
+            total_rent: acc.total_rent + p.total_rent,
+            fee: acc.fee + p.fee,
+            referral_fee: acc.referral_fee + p.referral_fee,
+            unwrapped: acc.unwrapped + p.unwrapped,
+            relayer_fee: acc.relayer_fee + p.relayer_fee,
+            payout: acc.payout + p.payout,
+            ..acc
+        },
+    );
+    println!("Total rent:  {}", sol(preview.total_rent));
+    let rate = match report.fee_tier {
+        Some(tier) => format!("tier {}, {} bps", tier, preview.fee_bps),
+        None => format!("{} bps", preview.fee_bps),
+    };
+    println!("Fee:         {} ({})", sol(preview.fee), rate);
+    if let Some(referrer) = &report.referrer {
+        println!("  referrer {}: {}", referrer, sol(preview.referral_fee));
+    }
+    match &report.destination {
+        Some(destination) => println!("Paid to {}: {}", destination, sol(preview.payout)),
+        None => println!("You receive: {}", sol(preview.payout)),
+    }
+    if !failed.is_empty() {
+        println!("Failed {} transactions:", failed.len());
+        for batch in failed {
+            println!("  {} accounts: {}", batch.accounts.len(), batch.error.as_deref().unwrap_or_default());
+        }
+    }
+}
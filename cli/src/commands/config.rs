@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use serde_json::json;
+use solana_sdk::signature::Signer;
+use zera_client::pda::{find_config_pda, find_program_data_address};
+use zera_client::pubkey::{decode_pubkey, encode_pubkey};
+use zera_client::RpcTransport;
+use zera_shared::config::{FeeTier, MAX_FEE_TIERS};
+use zera_shared::instruction::{set_config, set_fee_tiers, set_paused};
+use zera_shared::FEE_BPS;
+
+use super::tx::{read_keypair, send_and_confirm};
+use super::{print_json, CommandResult};
+
+/// Create or update the program config. The keypair must be the upgrade authority.
+pub async fn set<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    referral_bps: u16,
+    json: bool,
+) -> CommandResult {
+    let admin = read_keypair(keypair_path)?;
+    let config = find_config_pda().0;
+
+    let ix = set_config(admin.pubkey().to_bytes(), find_program_data_address(), config, referral_bps);
+    let signature = send_and_confirm(transport, &admin, &ix).await?;
+
+    let config = encode_pubkey(&config);
+    if json {
+        return print_json(&json!({
+            "config": config,
+            "referral_bps": referral_bps,
+            "signature": signature,
+        }));
+    }
+    println!("Config {} set to {} bps referral share: {}", config, referral_bps, signature);
+    Ok(())
+}
+
+/// Pause or resume every non-admin instruction. The keypair must be the upgrade authority.
+pub async fn pause<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    paused: bool,
+    json: bool,
+) -> CommandResult {
+    let admin = read_keypair(keypair_path)?;
+    let config = find_config_pda().0;
+
+    let ix = set_paused(admin.pubkey().to_bytes(), find_program_data_address(), config, paused);
+    let signature = send_and_confirm(transport, &admin, &ix).await?;
+
+    let config = encode_pubkey(&config);
+    if json {
+        return print_json(&json!({ "config": config, "paused": paused, "signature": signature }));
+    }
+    let state = if paused { "paused" } else { "resumed" };
+    println!("Program {} via config {}: {}", state, config, signature);
+    Ok(())
+}
+
+/// Replace the fee tiers and governance mint. The keypair must be the upgrade authority.
+///
+/// Unused tier slots are cleared; no governance mint disables holding-based tiers.
+pub async fn set_tiers<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    governance_mint: Option<&str>,
+    tiers: &[FeeTier],
+    json: bool,
+) -> CommandResult {
+    if tiers.len() > MAX_FEE_TIERS {
+        return Err(format!("At most {} fee tiers are supported", MAX_FEE_TIERS).into());
+    }
+    let admin = read_keypair(keypair_path)?;
+    let config = find_config_pda().0;
+    let mint = governance_mint.map(decode_pubkey).transpose()?.unwrap_or_default();
+    let mut fee_tiers = [FeeTier::default(); MAX_FEE_TIERS];
+    fee_tiers[..tiers.len()].copy_from_slice(tiers);
+
+    let ix = set_fee_tiers(admin.pubkey().to_bytes(), find_program_data_address(), config, mint, fee_tiers);
+    let signature = send_and_confirm(transport, &admin, &ix).await?;
+
+    let config = encode_pubkey(&config);
+    if json {
+        return print_json(&json!({
+            "config": config,
+            "governance_mint": governance_mint,
+            "fee_tiers": tiers.iter().map(tier_json).collect::<Vec<_>>(),
+            "signature": signature,
+        }));
+    }
+    println!("Config {} set to {} fee tiers: {}", config, tiers.len(), signature);
+    for tier in tiers {
+        println!(
+            "  {} bps from {} closed accounts or {} governance tokens",
+            tier.fee_bps, tier.min_closed, tier.min_holding
+        );
+    }
+    Ok(())
+}
+
+fn tier_json(tier: &FeeTier) -> serde_json::Value {
+    json!({ "min_closed": tier.min_closed, "min_holding": tier.min_holding, "fee_bps": tier.fee_bps })
+}
+
+/// Parse a `min_closed:min_holding:fee_bps` tier argument.
+pub fn parse_fee_tier(arg: &str) -> Result<FeeTier, String> {
+    let parts: Vec<&str> = arg.split(':').collect();
+    let [min_closed, min_holding, fee_bps] = parts[..] else {
+        return Err("expected min_closed:min_holding:fee_bps".to_string());
+    };
+    let tier = FeeTier {
+        min_closed: min_closed.parse().map_err(|e| format!("invalid min_closed: {}", e))?,
+        min_holding: min_holding.parse().map_err(|e| format!("invalid min_holding: {}", e))?,
+        fee_bps: fee_bps.parse().map_err(|e| format!("invalid fee_bps: {}", e))?,
+    };
+    if tier.fee_bps as u64 > FEE_BPS {
+        return Err(format!("fee_bps must not exceed the standard {} bps", FEE_BPS));
+    }
+    Ok(tier)
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use serde::Serialize;
+use solana_sdk::signature::Signer;
+use zera_client::consolidate::{
+    build_consolidate_instructions, scan_consolidations, Consolidation, MAX_CONSOLIDATE_ACCOUNTS_PER_TX,
+};
+use zera_client::RpcTransport;
+
+use super::tx::{read_keypair, send_and_confirm};
+use super::{print_json, sol, CommandResult};
+
+#[derive(Serialize)]
+struct ConsolidateReport {
+    owner: String,
+    dry_run: bool,
+    consolidations: Vec<Consolidation>,
+    signatures: Vec<String>,
+    errors: Vec<String>,
+}
+
+/// Fold the keypair's auxiliary token accounts into its ATAs, mint by mint.
+pub async fn run<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    dry_run: bool,
+    json: bool,
+) -> CommandResult {
+    let keypair = read_keypair(keypair_path)?;
+    let owner = keypair.pubkey().to_string();
+
+    let consolidations = scan_consolidations(transport, &owner).await?;
+    let mut report = ConsolidateReport {
+        owner,
+        dry_run,
+        consolidations,
+        signatures: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let mut transactions = 0;
+    for consolidation in &report.consolidations {
+        let instructions =
+            build_consolidate_instructions(&report.owner, consolidation, MAX_CONSOLIDATE_ACCOUNTS_PER_TX)?;
+        transactions += instructions.len();
+        if dry_run {
+            continue;
+        }
+        for ix in &instructions {
+            match send_and_confirm(transport, &keypair, ix).await {
+                Ok(signature) => report.signatures.push(signature),
+                Err(e) => report.errors.push(e.to_string()),
+            }
+        }
+    }
+
+    if json {
+        print_json(&report)?;
+    } else {
+        let verb = if dry_run { "Would consolidate" } else { "Consolidated" };
+        println!("{} {} mints for {}", verb, report.consolidations.len(), report.owner);
+        for consolidation in &report.consolidations {
+            let (fee, payout) = consolidation.fee_split();
+            println!(
+                "  mint {}  {} accounts -> {}  {} tokens, rent {} (fee {})",
+                consolidation.mint,
+                consolidation.auxiliaries.len(),
+                consolidation.target,
+                consolidation.amount(),
+                sol(payout),
+                sol(fee)
+            );
+        }
+        for error in &report.errors {
+            println!("FAILED {}", error);
+        }
+    }
+
+    if !report.errors.is_empty() {
+        return Err(format!("{} of {} transactions failed", report.errors.len(), transactions).into());
+    }
+    Ok(())
+}
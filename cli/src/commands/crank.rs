@@ -0,0 +1,131 @@
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::json;
+use solana_sdk::signature::Signer;
+use zera_client::batch::MAX_ACCOUNTS_PER_TX;
+use zera_client::crank::{self, build_crank_batches, scan_crankable, CrankPreview};
+use zera_client::pda::{find_config_pda, find_janitor_pda};
+use zera_client::pubkey::decode_pubkey;
+use zera_client::{ClientError, RpcTransport};
+use zera_shared::instruction::{authorize, revoke, Instruction, TooManyAccounts};
+
+use super::tx::{read_keypair, send_and_confirm};
+use super::{print_json, sol, CommandResult};
+
+#[derive(Serialize)]
+struct CrankReport {
+    cranker: String,
+    dry_run: bool,
+    accounts: usize,
+    preview: CrankPreview,
+    signatures: Vec<String>,
+    errors: Vec<String>,
+}
+
+/// Delegate the close authority of `accounts` to the janitor PDA.
+pub async fn authorize_accounts<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    accounts: &[String],
+    json: bool,
+) -> CommandResult {
+    let owner = read_keypair(keypair_path)?;
+    let config = find_config_pda().0;
+    delegate(transport, &owner, accounts, "authorized", json, |owner, janitor, keys| {
+        authorize(owner, janitor, config, keys)
+    })
+    .await
+}
+
+/// Take the close authority of `accounts` back from the janitor PDA.
+pub async fn revoke_accounts<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    accounts: &[String],
+    json: bool,
+) -> CommandResult {
+    let owner = read_keypair(keypair_path)?;
+    delegate(transport, &owner, accounts, "revoked", json, revoke).await
+}
+
+async fn delegate<T: RpcTransport>(
+    transport: &T,
+    owner: &solana_sdk::signature::Keypair,
+    accounts: &[String],
+    action: &str,
+    json: bool,
+    build: impl Fn([u8; 32], [u8; 32], &[[u8; 32]]) -> Result<Instruction, TooManyAccounts>,
+) -> CommandResult {
+    let keys = accounts
+        .iter()
+        .map(|a| decode_pubkey(a))
+        .collect::<Result<Vec<_>, _>>()?;
+    let janitor = find_janitor_pda().0;
+
+    let mut signatures = Vec::new();
+    for chunk in keys.chunks(MAX_ACCOUNTS_PER_TX) {
+        let ix = build(owner.pubkey().to_bytes(), janitor, chunk).map_err(ClientError::from)?;
+        signatures.push(send_and_confirm(transport, owner, &ix).await?);
+    }
+
+    if json {
+        return print_json(&json!({ "accounts": accounts, "action": action, "signatures": signatures }));
+    }
+    println!("Auto-clean {} for {} accounts", action, accounts.len());
+    for signature in &signatures {
+        println!("  {}", signature);
+    }
+    Ok(())
+}
+
+/// Close every empty delegated account, optionally only `owner`'s, earning the crank reward.
+pub async fn run<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    owner: Option<&str>,
+    dry_run: bool,
+    json: bool,
+) -> CommandResult {
+    let keypair = read_keypair(keypair_path)?;
+    let cranker = keypair.pubkey().to_string();
+
+    let accounts = scan_crankable(transport, owner).await?;
+    let batches = build_crank_batches(&cranker, &accounts, crank::MAX_CRANK_ACCOUNTS_PER_TX)?;
+    let mut report = CrankReport {
+        cranker,
+        dry_run,
+        accounts: accounts.len(),
+        preview: crank::preview(&accounts),
+        signatures: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    if !dry_run {
+        for batch in &batches {
+            match send_and_confirm(transport, &keypair, &batch.instruction).await {
+                Ok(signature) => report.signatures.push(signature),
+                Err(e) => report.errors.push(e.to_string()),
+            }
+        }
+    }
+
+    if json {
+        print_json(&report)?;
+    } else {
+        let verb = if dry_run { "Would crank" } else { "Cranked" };
+        println!("{} {} delegated accounts in {} transactions", verb, report.accounts, batches.len());
+        println!("Total rent:    {}", sol(report.preview.total_rent));
+        println!("Fee:           {}", sol(report.preview.fee));
+        println!("Crank reward:  {}", sol(report.preview.crank_reward));
+        println!("To owners:     {}", sol(report.preview.payout));
+        for error in &report.errors {
+            println!("FAILED {}", error);
+        }
+    }
+
+    if !report.errors.is_empty() {
+        return Err(format!("{} of {} batches failed", report.errors.len(), batches.len()).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use zera_client::explain::explain_transaction;
+use zera_client::RpcTransport;
+
+use super::{print_json, sol, CommandResult};
+
+pub async fn run<T: RpcTransport>(transport: &T, signature: &str, json: bool) -> CommandResult {
+    let explanation = explain_transaction(transport, signature).await?;
+
+    if json {
+        return print_json(&explanation);
+    }
+
+    println!("Signature  {}", explanation.signature);
+    println!("Slot       {}", explanation.slot);
+    println!("User       {}", explanation.user);
+    if explanation.destination != explanation.user {
+        println!("Paid to    {}", explanation.destination);
+    }
+    match &explanation.error {
+        None => println!("Status     success"),
+        Some(err) => println!("Status     FAILED: {}", err),
+    }
+    println!("Accounts   {}", explanation.closed_accounts.len());
+    for acc in &explanation.closed_accounts {
+        println!("  {}  {}", acc.address, sol(acc.lamports));
+    }
+    println!("Rent collected  {}", sol(explanation.rent_collected));
+    if explanation.unwrapped > 0 {
+        println!("SOL unwrapped   {} (no fee)", sol(explanation.unwrapped));
+    }
+    println!("Protocol fee    {} ({} bps)", sol(explanation.fee), explanation.fee_bps);
+    if let Some(referrer) = &explanation.referrer {
+        println!("  referrer {}  {}", referrer, sol(explanation.referral_fee));
+    }
+    if explanation.relayer_fee > 0 {
+        println!("Relayer fee     {}", sol(explanation.relayer_fee));
+    }
+    println!("Payout          {}", sol(explanation.payout));
+    println!("Network fee     {}", sol(explanation.network_fee));
+    println!(
+        "User balance    {:+.6} SOL",
+        explanation.user_balance_change as f64 / 1_000_000_000.0
+    );
+    Ok(())
+}
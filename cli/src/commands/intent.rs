@@ -0,0 +1,109 @@
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use solana_sdk::signature::Signer;
+use zera_client::fees::FeePreview;
+use zera_client::intent::{
+    self, build_intent_batches, scan_intent_accounts, SignedIntent, MAX_INTENT_ACCOUNTS_PER_TX,
+};
+use zera_client::pubkey::decode_pubkey;
+use zera_client::RpcTransport;
+use zera_shared::intent::{CleanIntent, MAX_INTENT_MINTS};
+
+use super::tx::{read_keypair, send_and_confirm_all};
+use super::{print_json, sol, CommandResult};
+
+#[derive(Serialize)]
+struct SubmitReport {
+    user: String,
+    submitter: String,
+    dry_run: bool,
+    accounts: usize,
+    preview: FeePreview,
+    signatures: Vec<String>,
+    errors: Vec<String>,
+}
+
+/// Sign an intent for `mints` and print it as JSON for a relayer to submit.
+pub fn sign(
+    keypair_path: &Path,
+    mints: &[String],
+    destination: Option<&str>,
+    expires_in: u64,
+) -> CommandResult {
+    if mints.len() > MAX_INTENT_MINTS {
+        return Err(format!("An intent allows at most {} mints", MAX_INTENT_MINTS).into());
+    }
+    let keypair = read_keypair(keypair_path)?;
+    let user = keypair.pubkey().to_bytes();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    let intent = CleanIntent {
+        user,
+        destination: destination.map(decode_pubkey).transpose()?.unwrap_or(user),
+        allowed_mints: mints.iter().map(|m| decode_pubkey(m)).collect::<Result<_, _>>()?,
+        expires_at: now.saturating_add(expires_in) as i64,
+    };
+    let signature: [u8; 64] = keypair.sign_message(&intent.message()).into();
+    print_json(&SignedIntent::new(&intent, &signature))
+}
+
+/// Close the accounts a signed intent allows, with `keypair` paying the fees.
+pub async fn submit<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    intent_path: &Path,
+    dry_run: bool,
+    json: bool,
+) -> CommandResult {
+    let keypair = read_keypair(keypair_path)?;
+    let submitter = keypair.pubkey().to_string();
+    let signed: SignedIntent = serde_json::from_str(&fs::read_to_string(intent_path)?)?;
+
+    let accounts = scan_intent_accounts(transport, &signed).await?;
+    let batches = build_intent_batches(&submitter, &signed, &accounts, MAX_INTENT_ACCOUNTS_PER_TX)?;
+    let mut report = SubmitReport {
+        user: signed.user.clone(),
+        submitter,
+        dry_run,
+        accounts: accounts.len(),
+        preview: intent::preview(&batches),
+        signatures: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    if !dry_run {
+        for batch in &batches {
+            match send_and_confirm_all(transport, &keypair, &batch.instructions).await {
+                Ok(signature) => report.signatures.push(signature),
+                Err(e) => report.errors.push(e.to_string()),
+            }
+        }
+    }
+
+    if json {
+        print_json(&report)?;
+    } else {
+        let verb = if dry_run { "Would close" } else { "Closed" };
+        println!(
+            "{} {} accounts of {} in {} transactions",
+            verb,
+            report.accounts,
+            report.user,
+            batches.len()
+        );
+        println!("Total rent:    {}", sol(report.preview.total_rent));
+        println!("Fee:           {}", sol(report.preview.fee));
+        println!("To user:       {}", sol(report.preview.payout));
+        for error in &report.errors {
+            println!("FAILED {}", error);
+        }
+    }
+
+    if !report.errors.is_empty() {
+        return Err(format!("{} of {} batches failed", report.errors.len(), batches.len()).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,28 @@
+pub mod buffers;
+pub mod clean;
+pub mod config;
+pub mod consolidate;
+pub mod crank;
+pub mod explain;
+pub mod intent;
+pub mod reclaim;
+pub mod scan;
+pub mod stats;
+pub mod tx;
+pub mod user_stats;
+pub mod vault;
+
+use std::error::Error;
+
+use serde::Serialize;
+
+pub type CommandResult = Result<(), Box<dyn Error>>;
+
+fn print_json<T: Serialize>(value: &T) -> CommandResult {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn sol(lamports: u64) -> String {
+    format!("{:.6} SOL", zera_client::fees::lamports_to_sol(lamports))
+}
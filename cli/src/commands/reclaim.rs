@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use serde::Serialize;
+use solana_sdk::signature::Signer;
+use zera_client::reclaim::{build_reclaim_instruction, scan_reclaimable, ReclaimableAccount};
+use zera_client::RpcTransport;
+
+use super::tx::{read_keypair, send_and_confirm};
+use super::{print_json, sol, CommandResult};
+
+#[derive(Serialize)]
+struct ReclaimReport {
+    authority: String,
+    dry_run: bool,
+    accounts: Vec<ReclaimableAccount>,
+    /// Stake accounts left alone until their cooldown ends
+    cooling_down: Vec<ReclaimableAccount>,
+    total_lamports: u64,
+    signatures: Vec<String>,
+    errors: Vec<String>,
+}
+
+/// Withdraw the keypair's idle nonce and stake accounts back to it.
+///
+/// Stake accounts still cooling down are listed but not withdrawn.
+pub async fn run<T: RpcTransport>(
+    transport: &T,
+    keypair_path: &Path,
+    dry_run: bool,
+    json: bool,
+) -> CommandResult {
+    let keypair = read_keypair(keypair_path)?;
+    let authority = keypair.pubkey().to_string();
+
+    let (cooling_down, accounts): (Vec<_>, Vec<_>) =
+        scan_reclaimable(transport, &authority).await?.into_iter().partition(|a| a.cooling_down);
+    let mut report = ReclaimReport {
+        total_lamports: accounts.iter().map(|a| a.lamports).sum(),
+        authority,
+        dry_run,
+        accounts,
+        cooling_down,
+        signatures: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    if !dry_run {
+        for account in &report.accounts {
+            let ix = build_reclaim_instruction(account, &report.authority)?;
+            match send_and_confirm(transport, &keypair, &ix).await {
+                Ok(signature) => report.signatures.push(signature),
+                Err(e) => report.errors.push(format!("{}: {}", account.address, e)),
+            }
+        }
+    }
+
+    if json {
+        print_json(&report)?;
+    } else {
+        let verb = if dry_run { "Would reclaim" } else { "Reclaimed" };
+        println!("{} {} accounts of {}", verb, report.accounts.len(), report.authority);
+        for account in &report.accounts {
+            println!("  {}  {}  {}", account.address, account.kind.label(), sol(account.lamports));
+        }
+        println!("Total: {}", sol(report.total_lamports));
+        for account in &report.cooling_down {
+            let (address, kind) = (&account.address, account.kind.label());
+            println!("  {}  {}  {}  cooling down", address, kind, sol(account.lamports));
+        }
+        for error in &report.errors {
+            println!("FAILED {}", error);
+        }
+    }
+
+    if !report.errors.is_empty() {
+        let total = report.accounts.len();
+        return Err(format!("{} of {} withdrawals failed", report.errors.len(), total).into());
+    }
+    Ok(())
+}
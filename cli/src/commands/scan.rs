@@ -0,0 +1,69 @@
+use serde_json::json;
+use zera_client::batch::max_accounts_per_tx;
+use zera_client::buffer::scan_buffers;
+use zera_client::fees;
+use zera_client::pubkey::decode_pubkey;
+use zera_client::reclaim::scan_reclaimable;
+use zera_client::scanner::{scan_closeable, scan_closeable_sliced};
+use zera_client::tiers::fetch_fee_status;
+use zera_client::RpcTransport;
+use zera_shared::instruction::BatchCleanOptions;
+
+use super::{print_json, sol, CommandResult};
+
+pub async fn run<T: RpcTransport>(transport: &T, owner: &str, full: bool, json: bool) -> CommandResult {
+    let accounts = match full {
+        true => scan_closeable(transport, owner).await?,
+        false => scan_closeable_sliced(transport, owner).await?,
+    };
+    let fee_status = fetch_fee_status(transport, &decode_pubkey(owner)?).await?;
+    let mut batch_options = BatchCleanOptions::default();
+    fee_status.apply(&mut batch_options);
+    let max_per_tx = max_accounts_per_tx(&batch_options);
+    let preview = fees::preview(&accounts, max_per_tx, fee_status.fee_bps(), 0);
+    let buffers = scan_buffers(transport, owner).await?;
+    let reclaimable = scan_reclaimable(transport, owner).await?;
+
+    if json {
+        return print_json(&json!({
+            "owner": owner,
+            "accounts": accounts,
+            "preview": preview,
+            "buffers": buffers,
+            "reclaimable": reclaimable,
+        }));
+    }
+
+    println!("{} closeable accounts for {}", accounts.len(), owner);
+    for acc in &accounts {
+        match acc.is_native {
+            true => println!(
+                "  {}  wrapped SOL  {} + {} unwrapped",
+                acc.address,
+                sol(acc.rent_lamports()),
+                sol(acc.wrapped_lamports())
+            ),
+            false => println!("  {}  mint {}  {}", acc.address, acc.mint_short(), sol(acc.lamports)),
+        }
+    }
+    println!("Total rent:  {}", sol(preview.total_rent));
+    println!("Fee:         {} ({} bps)", sol(preview.fee), preview.fee_bps);
+    if preview.unwrapped > 0 {
+        println!("Unwrapped:   {} (no fee)", sol(preview.unwrapped));
+    }
+    println!("You receive: {}", sol(preview.payout));
+    if !reclaimable.is_empty() {
+        println!("{} nonce and stake accounts (withdraw with `reclaim`)", reclaimable.len());
+        for account in &reclaimable {
+            let cooling = if account.cooling_down { "  cooling down" } else { "" };
+            println!("  {}  {}  {}{}", account.address, account.kind.label(), sol(account.lamports), cooling);
+        }
+    }
+    if !buffers.is_empty() {
+        println!("{} deploy buffers (close with `close-buffers`)", buffers.len());
+        for buffer in &buffers {
+            println!("  {}  {}", buffer.address, sol(buffer.lamports));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use serde_json::json;
+use zera_client::config::fetch_config;
+use zera_client::pda::find_vault_pda;
+use zera_client::pubkey::encode_pubkey;
+use zera_client::rpc::get_balance;
+use zera_client::stats::recent_stats;
+use zera_client::RpcTransport;
+use zera_shared::TREASURY_PUBKEY;
+
+use super::{print_json, sol, CommandResult};
+
+pub async fn run<T: RpcTransport>(transport: &T, limit: usize, json: bool) -> CommandResult {
+    let vault = encode_pubkey(&find_vault_pda().0);
+    let treasury = encode_pubkey(&TREASURY_PUBKEY);
+    let vault_balance = get_balance(transport, &vault).await?;
+    let treasury_balance = get_balance(transport, &treasury).await?;
+    let stats = recent_stats(transport, limit).await?;
+    let config = fetch_config(transport).await?;
+
+    if json {
+        return print_json(&json!({
+            "vault": { "address": vault, "lamports": vault_balance },
+            "treasury": { "address": treasury, "lamports": treasury_balance },
+            "referral_bps": config.referral_bps,
+            "paused": config.paused,
+            "recent": stats,
+        }));
+    }
+
+    println!("Vault     {}  {}", vault, sol(vault_balance));
+    println!("Treasury  {}  {}", treasury, sol(treasury_balance));
+    println!("Referral share  {} bps of the fee", config.referral_bps);
+    if config.paused {
+        println!("Program is PAUSED");
+    }
+    println!("Last {} transactions:", limit);
+    println!("  cleanups:         {}", stats.transactions);
+    println!("  accounts closed:  {}", stats.accounts_closed);
+    println!("  rent reclaimed:   {}", sol(stats.rent_collected));
+    println!("  SOL unwrapped:    {}", sol(stats.unwrapped));
+    println!("  fees collected:   {}", sol(stats.fees));
+    println!("  to referrers:     {}", sol(stats.referral_fees));
+    println!("  crank rewards:    {}", sol(stats.crank_rewards));
+    println!("  paid out:         {}", sol(stats.payouts));
+    Ok(())
+}
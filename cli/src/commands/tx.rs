@@ -0,0 +1,80 @@
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use zera_client::rpc::{get_latest_blockhash, get_signature_status, send_transaction};
+use zera_client::{ClientError, RpcTransport};
+
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const CONFIRM_ATTEMPTS: usize = 60;
+
+pub fn read_keypair(path: &Path) -> Result<Keypair, String> {
+    read_keypair_file(path).map_err(|e| format!("Failed to read keypair {}: {}", path.display(), e))
+}
+
+/// Sign `ix` with `keypair` as fee payer, send it and wait for confirmation.
+pub async fn send_and_confirm<T: RpcTransport>(
+    transport: &T,
+    keypair: &Keypair,
+    ix: &zera_shared::instruction::Instruction,
+) -> Result<String, ClientError> {
+    send_and_confirm_all(transport, keypair, std::slice::from_ref(ix)).await
+}
+
+/// Like [`send_and_confirm`], for several instructions in one transaction.
+pub async fn send_and_confirm_all<T: RpcTransport>(
+    transport: &T,
+    keypair: &Keypair,
+    ixs: &[zera_shared::instruction::Instruction],
+) -> Result<String, ClientError> {
+    let blockhash = get_latest_blockhash(transport).await?;
+    let blockhash = blockhash
+        .parse()
+        .map_err(|_| ClientError::InvalidResponse(format!("Invalid blockhash {}", blockhash)))?;
+
+    let ixs: Vec<Instruction> = ixs.iter().map(to_sdk_instruction).collect();
+    let tx = Transaction::new_signed_with_payer(&ixs, Some(&keypair.pubkey()), &[keypair], blockhash);
+    let wire = bincode::serialize(&tx)
+        .map_err(|e| ClientError::Serialize(e.to_string()))?;
+
+    let signature = send_transaction(transport, &STANDARD.encode(wire)).await?;
+    confirm(transport, &signature).await?;
+    Ok(signature)
+}
+
+async fn confirm<T: RpcTransport>(transport: &T, signature: &str) -> Result<(), ClientError> {
+    for _ in 0..CONFIRM_ATTEMPTS {
+        let status = get_signature_status(transport, signature).await?;
+        if !status.is_null() {
+            if !status["err"].is_null() {
+                return Err(ClientError::TransactionFailed(signature.to_string(), status["err"].to_string()));
+            }
+            if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                return Ok(());
+            }
+        }
+        thread::sleep(CONFIRM_POLL_INTERVAL);
+    }
+    Err(ClientError::ConfirmTimeout(signature.to_string()))
+}
+
+fn to_sdk_instruction(ix: &zera_shared::instruction::Instruction) -> Instruction {
+    Instruction {
+        program_id: Pubkey::new_from_array(ix.program_id),
+        accounts: ix
+            .accounts
+            .iter()
+            .map(|meta| AccountMeta {
+                pubkey: Pubkey::new_from_array(meta.pubkey),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: ix.data.clone(),
+    }
+}
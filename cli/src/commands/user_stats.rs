@@ -0,0 +1,27 @@
+use std::path::Path;
+
+use serde_json::json;
+use solana_sdk::signature::Signer;
+use zera_client::pda::{find_config_pda, find_user_stats_pda};
+use zera_client::pubkey::encode_pubkey;
+use zera_client::RpcTransport;
+use zera_shared::instruction::init_user_stats;
+
+use super::tx::{read_keypair, send_and_confirm};
+use super::{print_json, CommandResult};
+
+/// Create the keypair's stats PDA so later cleans count toward volume fee tiers.
+pub async fn init<T: RpcTransport>(transport: &T, keypair_path: &Path, json: bool) -> CommandResult {
+    let user = read_keypair(keypair_path)?;
+    let user_stats = find_user_stats_pda(&user.pubkey().to_bytes()).0;
+
+    let ix = init_user_stats(user.pubkey().to_bytes(), user_stats, find_config_pda().0);
+    let signature = send_and_confirm(transport, &user, &ix).await?;
+
+    let user_stats = encode_pubkey(&user_stats);
+    if json {
+        return print_json(&json!({ "user_stats": user_stats, "signature": signature }));
+    }
+    println!("User stats {} initialized: {}", user_stats, signature);
+    Ok(())
+}
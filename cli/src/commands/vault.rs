@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use serde_json::json;
+use solana_sdk::signature::Signer;
+use zera_client::pda::{find_config_pda, find_program_data_address, find_vault_pda};
+use zera_client::pubkey::encode_pubkey;
+use zera_client::RpcTransport;
+use zera_shared::instruction::{initialize_vault, sweep_vault};
+
+use super::tx::{read_keypair, send_and_confirm};
+use super::{print_json, CommandResult};
+
+/// Fund the vault PDA to rent-exemption and hand it to the program.
+pub async fn init<T: RpcTransport>(transport: &T, keypair_path: &Path, json: bool) -> CommandResult {
+    let payer = read_keypair(keypair_path)?;
+    let vault = find_vault_pda().0;
+
+    let ix = initialize_vault(payer.pubkey().to_bytes(), vault, find_config_pda().0);
+    let signature = send_and_confirm(transport, &payer, &ix).await?;
+    report("initialized", vault, &signature, json)
+}
+
+/// Sweep vault lamports above rent-exemption to the treasury.
+///
+/// The keypair must be the program's upgrade authority.
+pub async fn sweep<T: RpcTransport>(transport: &T, keypair_path: &Path, json: bool) -> CommandResult {
+    let admin = read_keypair(keypair_path)?;
+    let vault = find_vault_pda().0;
+
+    let ix = sweep_vault(admin.pubkey().to_bytes(), find_program_data_address(), vault);
+    let signature = send_and_confirm(transport, &admin, &ix).await?;
+    report("swept", vault, &signature, json)
+}
+
+fn report(action: &str, vault: [u8; 32], signature: &str, json: bool) -> CommandResult {
+    let vault = encode_pubkey(&vault);
+    if json {
+        return print_json(&json!({ "vault": vault, "signature": signature }));
+    }
+    println!("Vault {} {}: {}", vault, action, signature);
+    Ok(())
+}
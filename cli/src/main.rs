@@ -0,0 +1,315 @@
+mod commands;
+mod transport;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::builder::TypedValueParser;
+use clap::{Parser, Subcommand};
+use zera_client::batch::MAX_ACCOUNTS_PER_TX;
+use zera_client::failover::Failover;
+use zera_shared::config::FeeTier;
+
+use crate::transport::HttpTransport;
+
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+
+#[derive(Parser)]
+#[command(name = "zera-cli", version, about = "Scan and clean empty SPL token accounts")]
+struct Cli {
+    /// Solana JSON-RPC endpoints, comma-separated in order of preference
+    #[arg(long, global = true, env = "ZERA_RPC_URL", value_delimiter = ',', default_value = DEFAULT_RPC_URL)]
+    url: Vec<String>,
+
+    /// Print machine-readable JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List closeable token accounts owned by a wallet
+    Scan {
+        /// Wallet address (base58)
+        owner: String,
+
+        /// Scan full accounts with `getTokenAccountsByOwner` instead of sliced `getProgramAccounts`
+        #[arg(long)]
+        full: bool,
+    },
+    /// Close every empty token account owned by a keypair
+    Clean {
+        /// Path to a Solana keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Send reclaimed rent to this wallet instead of the keypair (base58)
+        #[arg(long)]
+        destination: Option<String>,
+
+        /// Partner wallet sharing the protocol fee (base58)
+        #[arg(long)]
+        referrer: Option<String>,
+
+        /// Show the batches and fee preview without sending anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Scan full accounts with `getTokenAccountsByOwner` instead of sliced `getProgramAccounts`
+        #[arg(long)]
+        full: bool,
+
+        /// Token accounts closed per transaction, at most what fits with the optional accounts
+        #[arg(
+            long,
+            default_value_t = MAX_ACCOUNTS_PER_TX,
+            value_parser = clap::value_parser!(u8).range(1..=MAX_ACCOUNTS_PER_TX as i64).map(usize::from)
+        )]
+        max_per_tx: usize,
+    },
+    /// Close leftover program deploy buffers whose authority is a keypair
+    CloseBuffers {
+        /// Path to the buffer authority keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// List the buffers without closing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Withdraw idle durable nonce and deactivated stake accounts of a keypair
+    Reclaim {
+        /// Path to the withdraw authority keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// List the accounts without withdrawing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Move balances of extra token accounts into the ATA of their mint and close them
+    Consolidate {
+        /// Path to a Solana keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Show the suggested consolidations without sending anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Explain what a janitor transaction did, or why it failed
+    Explain {
+        /// Transaction signature (base58)
+        signature: String,
+    },
+    /// Show vault and treasury balances and recent cleaning activity
+    Stats {
+        /// Number of recent program transactions to aggregate
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+    },
+    /// Fund the vault PDA to rent-exemption and assign it to the program
+    InitVault {
+        /// Path to the paying keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+    },
+    /// Move vault lamports above rent-exemption to the treasury (upgrade authority only)
+    SweepVault {
+        /// Path to the upgrade authority keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+    },
+    /// Create or update the program config (upgrade authority only)
+    SetConfig {
+        /// Path to the upgrade authority keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Referrer share of the protocol fee, in basis points
+        #[arg(long, value_parser = clap::value_parser!(u16).range(0..=10_000))]
+        referral_bps: u16,
+    },
+    /// Replace the fee tiers and governance mint (upgrade authority only)
+    SetFeeTiers {
+        /// Path to the upgrade authority keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Mint whose holders qualify for holding-based tiers (base58)
+        #[arg(long)]
+        governance_mint: Option<String>,
+
+        /// Discounted tier as min_closed:min_holding:fee_bps; 0 disables a threshold
+        #[arg(long = "tier", value_parser = commands::config::parse_fee_tier)]
+        tiers: Vec<FeeTier>,
+    },
+    /// Pause or resume every non-admin instruction (upgrade authority only)
+    SetPaused {
+        /// Path to the upgrade authority keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// `true` to pause, `false` to resume
+        #[arg(long, action = clap::ArgAction::Set)]
+        paused: bool,
+    },
+    /// Let the janitor close token accounts once they are empty
+    Authorize {
+        /// Path to the owner keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Token accounts to delegate (base58)
+        #[arg(required = true)]
+        accounts: Vec<String>,
+    },
+    /// Take back close authority delegated with `authorize`
+    Revoke {
+        /// Path to the owner keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Token accounts to revoke (base58)
+        #[arg(required = true)]
+        accounts: Vec<String>,
+    },
+    /// Close empty delegated accounts of any owner and earn the crank reward
+    Crank {
+        /// Path to the cranker keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Only crank accounts of this owner (base58)
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Show what would be closed without sending anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Sign an intent letting a relayer clean delegated accounts of some mints
+    SignIntent {
+        /// Path to the owner keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Mint whose empty delegated accounts may be closed (base58)
+        #[arg(long = "mint", required = true)]
+        mints: Vec<String>,
+
+        /// Send reclaimed rent to this wallet instead of the keypair (base58)
+        #[arg(long)]
+        destination: Option<String>,
+
+        /// Seconds until the intent expires
+        #[arg(long, default_value_t = 86_400)]
+        expires_in: u64,
+    },
+    /// Submit a signed intent produced by `sign-intent`, paying the fees
+    SubmitIntent {
+        /// Path to the submitting keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Signed intent JSON file
+        intent: PathBuf,
+
+        /// Show what would be closed without sending anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Create the stats account that tracks a wallet's volume for fee tiers
+    InitStats {
+        /// Path to the wallet keypair JSON file
+        #[arg(long)]
+        keypair: PathBuf,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let transport = Failover::new(cli.url.iter().map(|url| HttpTransport::new(url)).collect());
+
+    let result = futures::executor::block_on(async {
+        // A single endpoint gets used whatever its health; ranking needs several
+        if cli.url.len() > 1 {
+            transport.check_health().await;
+        }
+        match cli.command {
+            Command::Scan { owner, full } => {
+                commands::scan::run(&transport, &owner, full, cli.json).await
+            }
+            Command::Clean {
+                keypair,
+                destination,
+                referrer,
+                dry_run,
+                full,
+                max_per_tx,
+            } => {
+                let options =
+                    commands::clean::CleanOptions { destination, referrer, dry_run, full, max_per_tx };
+                commands::clean::run(&transport, &keypair, options, cli.json).await
+            }
+            Command::CloseBuffers { keypair, dry_run } => {
+                commands::buffers::close(&transport, &keypair, dry_run, cli.json).await
+            }
+            Command::Reclaim { keypair, dry_run } => {
+                commands::reclaim::run(&transport, &keypair, dry_run, cli.json).await
+            }
+            Command::Consolidate { keypair, dry_run } => {
+                commands::consolidate::run(&transport, &keypair, dry_run, cli.json).await
+            }
+            Command::Explain { signature } => {
+                commands::explain::run(&transport, &signature, cli.json).await
+            }
+            Command::Stats { limit } => commands::stats::run(&transport, limit, cli.json).await,
+            Command::InitVault { keypair } => {
+                commands::vault::init(&transport, &keypair, cli.json).await
+            }
+            Command::SweepVault { keypair } => {
+                commands::vault::sweep(&transport, &keypair, cli.json).await
+            }
+            Command::SetConfig { keypair, referral_bps } => {
+                commands::config::set(&transport, &keypair, referral_bps, cli.json).await
+            }
+            Command::SetFeeTiers { keypair, governance_mint, tiers } => {
+                let mint = governance_mint.as_deref();
+                commands::config::set_tiers(&transport, &keypair, mint, &tiers, cli.json).await
+            }
+            Command::SetPaused { keypair, paused } => {
+                commands::config::pause(&transport, &keypair, paused, cli.json).await
+            }
+            Command::Authorize { keypair, accounts } => {
+                commands::crank::authorize_accounts(&transport, &keypair, &accounts, cli.json).await
+            }
+            Command::Revoke { keypair, accounts } => {
+                commands::crank::revoke_accounts(&transport, &keypair, &accounts, cli.json).await
+            }
+            Command::Crank { keypair, owner, dry_run } => {
+                commands::crank::run(&transport, &keypair, owner.as_deref(), dry_run, cli.json).await
+            }
+            Command::SignIntent { keypair, mints, destination, expires_in } => {
+                commands::intent::sign(&keypair, &mints, destination.as_deref(), expires_in)
+            }
+            Command::SubmitIntent { keypair, intent, dry_run } => {
+                commands::intent::submit(&transport, &keypair, &intent, dry_run, cli.json).await
+            }
+            Command::InitStats { keypair } => {
+                commands::user_stats::init(&transport, &keypair, cli.json).await
+            }
+        }
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
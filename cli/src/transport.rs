@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use serde_json::Value;
+use zera_client::jsonrpc::{self, Backoff};
+use zera_client::{ClientError, RpcError, RpcTransport};
+
+/// Blocking HTTP transport for native use.
+pub struct HttpTransport {
+    url: String,
+    agent: ureq::Agent,
+    backoff: Backoff,
+}
+
+impl HttpTransport {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+            backoff: Backoff::default(),
+        }
+    }
+
+    /// POST `body`, retrying rate limits and server errors with exponential backoff.
+    fn post(&self, body: &Value) -> Result<Value, RpcError> {
+        let mut attempt = 0;
+        loop {
+            match self.post_once(body) {
+                Err(e) => match self.backoff.delay_ms(&e, attempt) {
+                    Some(delay) => {
+                        std::thread::sleep(Duration::from_millis(delay.into()));
+                        attempt += 1;
+                    }
+                    None => return Err(e),
+                },
+                ok => return ok,
+            }
+        }
+    }
+
+    fn post_once(&self, body: &Value) -> Result<Value, RpcError> {
+        self.agent
+            .post(&self.url)
+            .send_json(body)
+            .map_err(|e| match e {
+                ureq::Error::Status(code, _) => RpcError::Http(code),
+                ureq::Error::Transport(t) => RpcError::Transport(t.to_string()),
+            })?
+            .into_json()
+            .map_err(|e| RpcError::Decode(format!("JSON parse error: {}", e)))
+    }
+}
+
+impl RpcTransport for HttpTransport {
+    async fn request(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let (_, body) = jsonrpc::request_body(method, params);
+        Ok(jsonrpc::parse_response(self.post(&body)?)?)
+    }
+
+    async fn request_batch(&self, requests: Vec<(&str, Value)>) -> Result<Vec<Value>, ClientError> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (ids, body) = jsonrpc::batch_body(requests);
+        Ok(jsonrpc::parse_batch_response(&ids, self.post(&body)?)?)
+    }
+}
@@ -0,0 +1,186 @@
+use zera_shared::instruction::{self, BatchCleanOptions, Instruction};
+
+use crate::error::ClientError;
+use crate::pda::{find_config_pda, find_vault_pda};
+use crate::pubkey::decode_pubkey;
+use crate::scanner::TokenAccountInfo;
+
+/// Maximum token accounts closed by a single `BatchClean` transaction; fewer fit once the
+/// batch carries optional accounts, see [`max_accounts_per_tx`].
+pub const MAX_ACCOUNTS_PER_TX: usize = 25;
+
+/// Largest serialized transaction the cluster accepts.
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+/// Serialized size of a legacy transaction carrying only `instruction`, signed by its signers.
+pub fn transaction_size(instruction: &Instruction) -> usize {
+    let mut keys: Vec<([u8; 32], bool)> = vec![(instruction.program_id, false)];
+    for meta in &instruction.accounts {
+        match keys.iter_mut().find(|(key, _)| *key == meta.pubkey) {
+            Some((_, is_signer)) => *is_signer |= meta.is_signer,
+            None => keys.push((meta.pubkey, meta.is_signer)),
+        }
+    }
+    let signers = keys.iter().filter(|(_, is_signer)| *is_signer).count();
+    let (accounts, data) = (instruction.accounts.len(), instruction.data.len());
+    // Signatures, then the message: header, account keys, blockhash and the single instruction
+    let message = 3 + compact_len(keys.len()) + 32 * keys.len() + 32;
+    let instruction = 1 + 1 + compact_len(accounts) + accounts + compact_len(data) + data;
+    compact_len(signers) + 64 * signers + message + instruction
+}
+
+/// Bytes taken by `len` as a compact-u16.
+fn compact_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+/// Most token accounts one `BatchClean` carrying `options` can close while its transaction
+/// stays within [`PACKET_DATA_SIZE`], at most [`MAX_ACCOUNTS_PER_TX`].
+pub fn max_accounts_per_tx(options: &BatchCleanOptions) -> usize {
+    let (vault, _) = find_vault_pda();
+    let (config, _) = find_config_pda();
+    // Distinct placeholder keys: the size depends on how many keys there are, not which
+    let key = |i: usize| {
+        let mut key = [0xa5; 32];
+        key[..8].copy_from_slice(&(i as u64).to_le_bytes());
+        key
+    };
+    let token_accounts: Vec<[u8; 32]> = (1..=MAX_ACCOUNTS_PER_TX).map(key).collect();
+    (1..=MAX_ACCOUNTS_PER_TX)
+        .rev()
+        .find(|&n| {
+            let ix = instruction::batch_clean(key(0), vault, config, options, &token_accounts[..n]);
+            transaction_size(&ix) <= PACKET_DATA_SIZE
+        })
+        .unwrap_or(1)
+}
+
+/// Number of transactions closing `accounts` token accounts at most `max_per_tx` at a time.
+pub fn batch_count(accounts: usize, max_per_tx: usize) -> usize {
+    accounts.div_ceil(max_per_tx.clamp(1, u8::MAX as usize))
+}
+
+/// One `BatchClean` transaction worth of work.
+#[derive(Clone, Debug)]
+pub struct Batch {
+    pub instruction: Instruction,
+    pub accounts: Vec<TokenAccountInfo>,
+}
+
+/// Split `accounts` into `BatchClean` instructions of at most `max_per_tx` accounts each,
+/// and never more than [`max_accounts_per_tx`] allows for `options`.
+///
+/// Every batch carries the same `options`, e.g. one payout destination and referrer for the whole run.
+pub fn build_batches(
+    user: &str,
+    options: &BatchCleanOptions,
+    accounts: &[TokenAccountInfo],
+    max_per_tx: usize,
+) -> Result<Vec<Batch>, ClientError> {
+    let user = decode_pubkey(user)?;
+    let (vault, _) = find_vault_pda();
+    let (config, _) = find_config_pda();
+
+    accounts
+        .chunks(max_per_tx.clamp(1, max_accounts_per_tx(options)))
+        .map(|chunk| {
+            let token_accounts = chunk
+                .iter()
+                .map(|acc| decode_pubkey(&acc.address))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Batch {
+                instruction: instruction::batch_clean(user, vault, config, options, &token_accounts),
+                accounts: chunk.to_vec(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::message::Message;
+    use solana_program::pubkey::Pubkey;
+
+    use super::*;
+    use crate::pubkey::encode_pubkey;
+
+    fn token_account(i: u8) -> TokenAccountInfo {
+        TokenAccountInfo {
+            address: encode_pubkey(&[i; 32]),
+            mint: encode_pubkey(&[0xee; 32]),
+            amount: 0,
+            lamports: 2_039_280,
+            is_native: false,
+            close_authority: None,
+            frozen: false,
+        }
+    }
+
+    /// Serialized size of `ix` signed and paid for by `payer`, as the SDK lays it out.
+    fn sdk_transaction_size(ix: &Instruction, payer: [u8; 32]) -> usize {
+        let ix = solana_program::instruction::Instruction {
+            program_id: Pubkey::new_from_array(ix.program_id),
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|m| solana_program::instruction::AccountMeta {
+                    pubkey: Pubkey::new_from_array(m.pubkey),
+                    is_signer: m.is_signer,
+                    is_writable: m.is_writable,
+                })
+                .collect(),
+            data: ix.data.clone(),
+        };
+        let message = Message::new(&[ix], Some(&Pubkey::new_from_array(payer)));
+        let signers = message.header.num_required_signatures as usize;
+        compact_len(signers) + 64 * signers + message.serialize().len()
+    }
+
+    #[test]
+    fn worst_case_batch_fits_in_a_packet() {
+        let options = BatchCleanOptions {
+            destination: Some([0xd1; 32]),
+            referrer: Some([0xd2; 32]),
+            user_stats: Some([0xd3; 32]),
+            governance_account: Some([0xd4; 32]),
+            relayer: Some(([0xd5; 32], 5_000)),
+        };
+        let accounts: Vec<_> = (1..=MAX_ACCOUNTS_PER_TX as u8).map(token_account).collect();
+        let user = encode_pubkey(&[0xd0; 32]);
+        let batches = build_batches(&user, &options, &accounts, MAX_ACCOUNTS_PER_TX).unwrap();
+
+        let cap = max_accounts_per_tx(&options);
+        assert!(cap < MAX_ACCOUNTS_PER_TX);
+        assert_eq!(batches[0].accounts.len(), cap);
+        let size = sdk_transaction_size(&batches[0].instruction, [0xd5; 32]);
+        assert_eq!(size, transaction_size(&batches[0].instruction));
+        assert!(size <= PACKET_DATA_SIZE, "{} bytes", size);
+
+        // One more account would not fit
+        let over = build_batches(&user, &options, &accounts[..cap + 1], cap + 1).unwrap();
+        let (vault, _) = find_vault_pda();
+        let (config, _) = find_config_pda();
+        let keys: Vec<_> = (1..=cap as u8 + 1).map(|i| [i; 32]).collect();
+        let ix = instruction::batch_clean([0xd0; 32], vault, config, &options, &keys);
+        assert_eq!(over[0].accounts.len(), cap);
+        assert!(sdk_transaction_size(&ix, [0xd5; 32]) > PACKET_DATA_SIZE);
+    }
+
+    #[test]
+    fn plain_batch_keeps_the_full_cap() {
+        assert_eq!(max_accounts_per_tx(&BatchCleanOptions::default()), MAX_ACCOUNTS_PER_TX);
+    }
+
+    #[test]
+    fn counts_transactions_rounding_up() {
+        assert_eq!(batch_count(0, MAX_ACCOUNTS_PER_TX), 0);
+        assert_eq!(batch_count(1, MAX_ACCOUNTS_PER_TX), 1);
+        assert_eq!(batch_count(25, 25), 1);
+        assert_eq!(batch_count(26, 25), 2);
+        assert_eq!(batch_count(3, 0), 3);
+    }
+}
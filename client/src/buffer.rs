@@ -0,0 +1,131 @@
+use serde::Serialize;
+use serde_json::Value;
+use zera_shared::instruction::{AccountMeta, Instruction};
+use zera_shared::BPF_LOADER_UPGRADEABLE_ID;
+
+use crate::error::ClientError;
+use crate::pubkey::{decode_pubkey, encode_pubkey};
+use crate::rpc::{get_program_accounts_sliced, memcmp};
+use crate::transport::RpcTransport;
+
+/// `UpgradeableLoaderState::Buffer` header: a `u32` variant tag, then an optional authority.
+const BUFFER_TAG: [u8; 4] = [1, 0, 0, 0];
+const AUTHORITY_OPTION_OFFSET: u64 = 4;
+const BUFFER_METADATA_LEN: usize = 37;
+
+/// `UpgradeableLoaderInstruction::Close` variant tag.
+const CLOSE_TAG: u32 = 5;
+
+/// A program deploy buffer left behind by an interrupted or failed deploy.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BufferAccount {
+    /// Buffer address (base58)
+    pub address: String,
+    /// Buffer authority (base58), the only signer able to close it
+    pub authority: String,
+    pub lamports: u64,
+}
+
+/// `getProgramAccounts` filters for loader buffers whose authority is `authority`.
+pub fn buffer_filters(authority: &[u8; 32]) -> Value {
+    let mut some_authority = vec![1];
+    some_authority.extend_from_slice(authority);
+    Value::Array(vec![memcmp(0, &BUFFER_TAG), memcmp(AUTHORITY_OPTION_OFFSET, &some_authority)])
+}
+
+/// Authority of a raw buffer header, `None` if the data isn't a buffer with an authority.
+pub fn parse_buffer_authority(data: &[u8]) -> Option<[u8; 32]> {
+    let header = data.get(..BUFFER_METADATA_LEN)?;
+    if header[..4] != BUFFER_TAG || header[4] != 1 {
+        return None;
+    }
+    header[5..].try_into().ok()
+}
+
+/// Find the deploy buffers `authority` can close. Only the buffer headers are downloaded.
+pub async fn scan_buffers<T: RpcTransport>(
+    transport: &T,
+    authority: &str,
+) -> Result<Vec<BufferAccount>, ClientError> {
+    let authority_key = decode_pubkey(authority)?;
+    let loader = encode_pubkey(&BPF_LOADER_UPGRADEABLE_ID);
+    let accounts =
+        get_program_accounts_sliced(transport, &loader, buffer_filters(&authority_key), BUFFER_METADATA_LEN)
+            .await?;
+    Ok(accounts
+        .into_iter()
+        .filter(|(_, _, data)| parse_buffer_authority(data) == Some(authority_key))
+        .map(|(address, lamports, _)| BufferAccount { address, authority: authority.to_string(), lamports })
+        .collect())
+}
+
+/// Build the loader's `Close` instruction, sending the buffer's lamports to `recipient`.
+pub fn build_close_buffer_instruction(
+    buffer: &BufferAccount,
+    recipient: &str,
+) -> Result<Instruction, ClientError> {
+    Ok(Instruction {
+        program_id: BPF_LOADER_UPGRADEABLE_ID,
+        accounts: vec![
+            AccountMeta::new(decode_pubkey(&buffer.address)?, false),
+            AccountMeta::new(decode_pubkey(recipient)?, false),
+            AccountMeta::new_readonly(decode_pubkey(&buffer.authority)?, true),
+        ],
+        data: CLOSE_TAG.to_le_bytes().to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_match_buffer_tag_and_authority() {
+        let filters = buffer_filters(&[7u8; 32]);
+        let bytes = |i: usize| {
+            bs58::decode(filters[i]["memcmp"]["bytes"].as_str().unwrap()).into_vec().unwrap()
+        };
+        assert_eq!(filters[0]["memcmp"]["offset"], 0);
+        assert_eq!(bytes(0), BUFFER_TAG);
+        assert_eq!(filters[1]["memcmp"]["offset"], 4);
+        assert_eq!(bytes(1)[0], 1);
+        assert_eq!(&bytes(1)[1..], &[7u8; 32]);
+    }
+
+    #[test]
+    fn parses_buffer_header() {
+        let mut data = BUFFER_TAG.to_vec();
+        data.push(1);
+        data.extend_from_slice(&[7u8; 32]);
+        assert_eq!(parse_buffer_authority(&data), Some([7u8; 32]));
+
+        // Immutable buffers have no authority and can never be closed
+        data[4] = 0;
+        assert_eq!(parse_buffer_authority(&data), None);
+        data[4] = 1;
+        data[0] = 3;
+        assert_eq!(parse_buffer_authority(&data), None);
+        assert_eq!(parse_buffer_authority(&data[..20]), None);
+    }
+
+    #[test]
+    fn close_matches_loader_layout() {
+        let buffer = BufferAccount {
+            address: encode_pubkey(&[2u8; 32]),
+            authority: encode_pubkey(&[7u8; 32]),
+            lamports: 1_000_000_000,
+        };
+        let ix = build_close_buffer_instruction(&buffer, &encode_pubkey(&[7u8; 32])).unwrap();
+
+        let expected = solana_program::bpf_loader_upgradeable::close(
+            &solana_program::pubkey::Pubkey::new_from_array([2u8; 32]),
+            &solana_program::pubkey::Pubkey::new_from_array([7u8; 32]),
+            &solana_program::pubkey::Pubkey::new_from_array([7u8; 32]),
+        );
+        assert_eq!(ix.program_id, expected.program_id.to_bytes());
+        assert_eq!(ix.data, expected.data);
+        assert_eq!(ix.accounts.len(), 3);
+        assert!(ix.accounts[0].is_writable && ix.accounts[1].is_writable);
+        assert!(ix.accounts[2].is_signer && !ix.accounts[2].is_writable);
+    }
+}
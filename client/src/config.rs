@@ -0,0 +1,17 @@
+use zera_shared::config::ProgramConfig;
+
+use crate::error::ClientError;
+use crate::pda::find_config_pda;
+use crate::pubkey::encode_pubkey;
+use crate::rpc::get_account_data;
+use crate::transport::RpcTransport;
+
+/// Fetch the program config, or the defaults the program uses before it's created.
+pub async fn fetch_config<T: RpcTransport>(transport: &T) -> Result<ProgramConfig, ClientError> {
+    let address = encode_pubkey(&find_config_pda().0);
+    match get_account_data(transport, &address).await? {
+        None => Ok(ProgramConfig::default()),
+        Some(data) => ProgramConfig::unpack(&data)
+            .ok_or_else(|| ClientError::InvalidResponse("Invalid config account".to_string())),
+    }
+}
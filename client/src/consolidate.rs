@@ -0,0 +1,193 @@
+use serde::Serialize;
+use zera_shared::instruction::{self, Instruction};
+use zera_shared::FEE_BPS;
+
+use crate::error::ClientError;
+use crate::fees::split_rent;
+use crate::pda::{find_associated_token_address, find_config_pda, find_vault_pda};
+use crate::pubkey::{decode_pubkey, encode_pubkey};
+use crate::rpc::get_token_accounts_by_owner;
+use crate::scanner::{decode_token_accounts, group_by_mint, TokenAccountInfo};
+use crate::transport::RpcTransport;
+
+/// Maximum auxiliary accounts folded by one `Consolidate`; each costs a transfer and a close.
+pub const MAX_CONSOLIDATE_ACCOUNTS_PER_TX: usize = 10;
+
+/// Auxiliary token accounts of one mint that can be folded into the owner's ATA.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Consolidation {
+    /// Mint address (base58)
+    pub mint: String,
+    /// The owner's associated token account receiving the balances (base58)
+    pub target: String,
+    pub auxiliaries: Vec<TokenAccountInfo>,
+}
+
+impl Consolidation {
+    /// Tokens moved into the ATA, in base units.
+    pub fn amount(&self) -> u64 {
+        self.auxiliaries.iter().map(|acc| acc.amount).sum()
+    }
+
+    /// Rent released by closing the auxiliaries.
+    pub fn rent(&self) -> u64 {
+        self.auxiliaries.iter().map(|acc| acc.lamports).sum()
+    }
+
+    /// `(fee, payout)` of consolidating, split per transaction like the program does.
+    pub fn fee_split(&self) -> (u64, u64) {
+        self.auxiliaries
+            .chunks(MAX_CONSOLIDATE_ACCOUNTS_PER_TX)
+            .map(|chunk| split_rent(chunk.iter().map(|acc| acc.lamports).sum(), FEE_BPS as u16))
+            .fold((0, 0), |(fee, payout), (f, p)| (fee + f, payout + p))
+    }
+}
+
+/// Suggest consolidations for `owner`: every mint with an ATA and at least one
+/// other account holding a balance. Wrapped SOL, delegated and frozen accounts are
+/// left out, as are mints whose ATA is frozen.
+pub fn suggest_consolidations(
+    owner: &str,
+    accounts: &[TokenAccountInfo],
+) -> Result<Vec<Consolidation>, ClientError> {
+    let owner = decode_pubkey(owner)?;
+    let mut suggestions = Vec::new();
+    for (mint, group) in group_by_mint(accounts) {
+        let target = encode_pubkey(&find_associated_token_address(&owner, &decode_pubkey(&mint)?));
+        match group.iter().find(|acc| acc.address == target) {
+            Some(ata) if !ata.frozen => {}
+            _ => continue,
+        }
+        let auxiliaries: Vec<TokenAccountInfo> = group
+            .into_iter()
+            .filter(|acc| {
+                acc.address != target && !acc.is_native && acc.close_authority.is_none() && !acc.frozen
+            })
+            .collect();
+        if auxiliaries.iter().any(|acc| acc.amount > 0) {
+            suggestions.push(Consolidation { mint, target, auxiliaries });
+        }
+    }
+    Ok(suggestions)
+}
+
+/// Scan `owner`'s token accounts and suggest consolidations.
+pub async fn scan_consolidations<T: RpcTransport>(
+    transport: &T,
+    owner: &str,
+) -> Result<Vec<Consolidation>, ClientError> {
+    let accounts = decode_token_accounts(get_token_accounts_by_owner(transport, owner).await?)?;
+    suggest_consolidations(owner, &accounts)
+}
+
+/// Build the `Consolidate` instructions for `consolidation`, at most `max_per_tx` auxiliaries each.
+pub fn build_consolidate_instructions(
+    owner: &str,
+    consolidation: &Consolidation,
+    max_per_tx: usize,
+) -> Result<Vec<Instruction>, ClientError> {
+    let owner = decode_pubkey(owner)?;
+    let target = decode_pubkey(&consolidation.target)?;
+    let (vault, _) = find_vault_pda();
+    let (config, _) = find_config_pda();
+
+    consolidation
+        .auxiliaries
+        .chunks(max_per_tx.clamp(1, MAX_CONSOLIDATE_ACCOUNTS_PER_TX))
+        .map(|chunk| {
+            let keys = chunk
+                .iter()
+                .map(|acc| decode_pubkey(&acc.address))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(instruction::consolidate(owner, target, vault, config, &keys)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(address: [u8; 32], mint: [u8; 32], amount: u64) -> TokenAccountInfo {
+        TokenAccountInfo {
+            address: encode_pubkey(&address),
+            mint: encode_pubkey(&mint),
+            amount,
+            lamports: 2_039_280,
+            is_native: false,
+            close_authority: None,
+            frozen: false,
+        }
+    }
+
+    #[test]
+    fn suggests_auxiliaries_of_mints_with_an_ata() {
+        let owner = [1u8; 32];
+        let (mint, other_mint) = ([2u8; 32], [3u8; 32]);
+        let ata = find_associated_token_address(&owner, &mint);
+        let accounts = vec![
+            account(ata, mint, 10),
+            account([4u8; 32], mint, 5),
+            account([5u8; 32], mint, 0),
+            // No ATA for this mint: nothing to consolidate into
+            account([6u8; 32], other_mint, 7),
+            account([7u8; 32], other_mint, 7),
+        ];
+
+        let suggestions = suggest_consolidations(&encode_pubkey(&owner), &accounts).unwrap();
+        assert_eq!(suggestions.len(), 1);
+        let consolidation = &suggestions[0];
+        assert_eq!(consolidation.target, encode_pubkey(&ata));
+        assert_eq!(consolidation.auxiliaries, accounts[1..3].to_vec());
+        assert_eq!(consolidation.amount(), 5);
+        assert_eq!(consolidation.fee_split(), split_rent(2 * 2_039_280, FEE_BPS as u16));
+    }
+
+    #[test]
+    fn skips_groups_without_balances_to_move() {
+        let owner = [1u8; 32];
+        let mint = [2u8; 32];
+        let ata = find_associated_token_address(&owner, &mint);
+        let accounts = vec![account(ata, mint, 10), account([4u8; 32], mint, 0)];
+        assert!(suggest_consolidations(&encode_pubkey(&owner), &accounts).unwrap().is_empty());
+    }
+
+    #[test]
+    fn leaves_out_frozen_accounts() {
+        let owner = [1u8; 32];
+        let (mint, other_mint) = ([2u8; 32], [3u8; 32]);
+        let ata = find_associated_token_address(&owner, &mint);
+        let frozen =
+            |address, mint, amount| TokenAccountInfo { frozen: true, ..account(address, mint, amount) };
+        let accounts = vec![
+            account(ata, mint, 10),
+            account([4u8; 32], mint, 5),
+            frozen([5u8; 32], mint, 8),
+            // Frozen ATA: nothing can be moved into it
+            frozen(find_associated_token_address(&owner, &other_mint), other_mint, 1),
+            account([6u8; 32], other_mint, 7),
+        ];
+
+        let suggestions = suggest_consolidations(&encode_pubkey(&owner), &accounts).unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].auxiliaries, accounts[1..2].to_vec());
+    }
+
+    #[test]
+    fn instructions_target_the_ata() {
+        let owner = [1u8; 32];
+        let mint = [2u8; 32];
+        let ata = find_associated_token_address(&owner, &mint);
+        let consolidation = Consolidation {
+            mint: encode_pubkey(&mint),
+            target: encode_pubkey(&ata),
+            auxiliaries: (10..22).map(|i| account([i; 32], mint, 1)).collect(),
+        };
+
+        let ixs = build_consolidate_instructions(&encode_pubkey(&owner), &consolidation, 100).unwrap();
+        assert_eq!(ixs.len(), 2);
+        assert_eq!(ixs[0].accounts[1].pubkey, ata);
+        assert_eq!(ixs[0].accounts.len(), 6 + MAX_CONSOLIDATE_ACCOUNTS_PER_TX);
+        assert_eq!(ixs[1].accounts.len(), 6 + 2);
+    }
+}
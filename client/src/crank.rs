@@ -0,0 +1,189 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use zera_shared::instruction::{self, CrankTarget, Instruction};
+use zera_shared::token::{TokenAccount, ACCOUNT_LEN};
+use zera_shared::{BPS_DENOMINATOR, CRANK_REWARD_BPS, FEE_BPS};
+
+use crate::error::ClientError;
+use crate::pda::{find_config_pda, find_janitor_pda, find_vault_pda};
+use crate::pubkey::{decode_pubkey, encode_pubkey};
+use crate::rpc::{get_program_accounts, memcmp, KeyedAccount, SPL_TOKEN_PROGRAM};
+use crate::transport::RpcTransport;
+
+/// Maximum delegated accounts closed by one `CrankClean`; each needs two account keys.
+pub const MAX_CRANK_ACCOUNTS_PER_TX: usize = 12;
+
+/// SPL token account layout offsets used by the `getProgramAccounts` filters.
+const AMOUNT_OFFSET: u64 = 64;
+const CLOSE_AUTHORITY_OFFSET: u64 = 129;
+
+/// A token account whose close authority was delegated to the janitor PDA.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DelegatedAccount {
+    /// Token account address (base58)
+    pub address: String,
+    /// Owner wallet receiving the rent (base58)
+    pub owner: String,
+    /// Token mint (base58)
+    pub mint: String,
+    pub amount: u64,
+    pub lamports: u64,
+}
+
+/// Expected outcome of cranking a set of delegated accounts, in lamports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CrankPreview {
+    pub total_rent: u64,
+    pub fee: u64,
+    pub crank_reward: u64,
+    /// Paid to the account owners.
+    pub payout: u64,
+}
+
+/// One `CrankClean` transaction worth of work.
+#[derive(Clone, Debug)]
+pub struct CrankBatch {
+    pub instruction: Instruction,
+    pub accounts: Vec<DelegatedAccount>,
+}
+
+/// Find every empty token account delegated to the janitor, optionally for one owner.
+pub async fn scan_crankable<T: RpcTransport>(
+    transport: &T,
+    owner: Option<&str>,
+) -> Result<Vec<DelegatedAccount>, ClientError> {
+    let accounts = get_program_accounts(transport, SPL_TOKEN_PROGRAM, delegated_filters(true)).await?;
+    Ok(decode_delegated_accounts(accounts)?
+        .into_iter()
+        .filter(|acc| owner.is_none_or(|o| acc.owner == o))
+        .collect())
+}
+
+/// `getProgramAccounts` filters for token accounts delegated to the janitor PDA.
+pub fn delegated_filters(empty_only: bool) -> Value {
+    let mut close_authority = vec![1, 0, 0, 0];
+    close_authority.extend_from_slice(&find_janitor_pda().0);
+    let mut filters = vec![
+        json!({ "dataSize": ACCOUNT_LEN }),
+        memcmp(CLOSE_AUTHORITY_OFFSET, &close_authority),
+    ];
+    if empty_only {
+        filters.push(memcmp(AMOUNT_OFFSET, &[0u8; 8]));
+    }
+    Value::Array(filters)
+}
+
+/// Decode raw delegated token accounts, failing on the first one that doesn't match the layout.
+pub fn decode_delegated_accounts(accounts: Vec<KeyedAccount>) -> Result<Vec<DelegatedAccount>, ClientError> {
+    accounts
+        .into_iter()
+        .map(|(address, lamports, data)| {
+            let account = match TokenAccount::unpack(&data) {
+                Ok(account) => account,
+                Err(e) => return Err(ClientError::InvalidAccount(address, e)),
+            };
+            Ok(DelegatedAccount {
+                address,
+                owner: encode_pubkey(&account.owner),
+                mint: encode_pubkey(&account.mint),
+                amount: account.amount,
+                lamports,
+            })
+        })
+        .collect()
+}
+
+/// Split `accounts` into `CrankClean` instructions of at most `max_per_tx` accounts each.
+pub fn build_crank_batches(
+    cranker: &str,
+    accounts: &[DelegatedAccount],
+    max_per_tx: usize,
+) -> Result<Vec<CrankBatch>, ClientError> {
+    let cranker = decode_pubkey(cranker)?;
+    let (vault, _) = find_vault_pda();
+    let (config, _) = find_config_pda();
+    let (janitor, _) = find_janitor_pda();
+
+    accounts
+        .chunks(max_per_tx.clamp(1, MAX_CRANK_ACCOUNTS_PER_TX))
+        .map(|chunk| {
+            let targets = chunk
+                .iter()
+                .map(|acc| {
+                    Ok(CrankTarget {
+                        token_account: decode_pubkey(&acc.address)?,
+                        owner: decode_pubkey(&acc.owner)?,
+                    })
+                })
+                .collect::<Result<Vec<_>, ClientError>>()?;
+            Ok(CrankBatch {
+                instruction: instruction::crank_clean(cranker, vault, config, janitor, &targets)?,
+                accounts: chunk.to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// Preview a crank, rounding per account like the program does.
+pub fn preview(accounts: &[DelegatedAccount]) -> CrankPreview {
+    accounts.iter().fold(CrankPreview::default(), |acc, account| {
+        let rent = account.lamports;
+        let fee = rent * FEE_BPS / BPS_DENOMINATOR;
+        let reward = rent * CRANK_REWARD_BPS / BPS_DENOMINATOR;
+        CrankPreview {
+            total_rent: acc.total_rent + rent,
+            fee: acc.fee + fee,
+            crank_reward: acc.crank_reward + reward,
+            payout: acc.payout + rent - fee - reward,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delegated(owner: &str) -> DelegatedAccount {
+        DelegatedAccount {
+            address: encode_pubkey(&[5u8; 32]),
+            owner: owner.to_string(),
+            mint: encode_pubkey(&[6u8; 32]),
+            amount: 0,
+            lamports: 2_039_280,
+        }
+    }
+
+    #[test]
+    fn filters_match_close_authority_and_empty_balance() {
+        let filters = delegated_filters(true);
+        let close_authority = bs58::decode(filters[1]["memcmp"]["bytes"].as_str().unwrap())
+            .into_vec()
+            .unwrap();
+        assert_eq!(filters[0]["dataSize"], 165);
+        assert_eq!(filters[1]["memcmp"]["offset"], 129);
+        assert_eq!(&close_authority[..4], &[1, 0, 0, 0]);
+        assert_eq!(&close_authority[4..], &find_janitor_pda().0);
+        assert_eq!(filters[2]["memcmp"]["offset"], 64);
+        assert_eq!(delegated_filters(false).as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn batches_pair_accounts_with_owners() {
+        let owner = encode_pubkey(&[7u8; 32]);
+        let accounts = vec![delegated(&owner); 13];
+        let batches = build_crank_batches(&encode_pubkey(&[1u8; 32]), &accounts, 25).unwrap();
+
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].accounts.len(), MAX_CRANK_ACCOUNTS_PER_TX);
+        assert_eq!(batches[1].instruction.accounts.len(), 6 + 2);
+        assert_eq!(batches[1].instruction.accounts[7].pubkey, [7u8; 32]);
+    }
+
+    #[test]
+    fn preview_splits_rent_three_ways() {
+        let preview = preview(&[delegated("owner")]);
+        assert_eq!(preview.fee, 101_964);
+        assert_eq!(preview.crank_reward, 20_392);
+        assert_eq!(preview.payout, 2_039_280 - 101_964 - 20_392);
+    }
+}
@@ -0,0 +1,89 @@
+use serde_json::Value;
+use thiserror::Error;
+use zera_shared::instruction::TooManyAccounts;
+use zera_shared::token::DecodeError;
+
+/// Failure of a single JSON-RPC call, as reported by a [`crate::RpcTransport`].
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RpcError {
+    /// The request never got an HTTP response: DNS, connection, CORS…
+    #[error("Transport error: {0}")]
+    Transport(String),
+
+    #[error("Request timed out after {0} ms")]
+    Timeout(u32),
+
+    /// Non-2xx HTTP status from the RPC node.
+    #[error("HTTP {0} from RPC node")]
+    Http(u16),
+
+    /// JSON-RPC `error` member of the response.
+    #[error("JSON-RPC error {code}: {message}")]
+    JsonRpc { code: i64, message: String, data: Option<Value> },
+
+    /// The response isn't a JSON-RPC envelope we understand.
+    #[error("Invalid JSON-RPC response: {0}")]
+    Decode(String),
+}
+
+impl RpcError {
+    /// Rate limits and server errors, which are worth retrying after a pause.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, RpcError::Http(status) if *status == 429 || (500..600).contains(status))
+    }
+
+    /// Errors that say the endpoint is unreachable or unwell rather than the request wrong:
+    /// worth trying another endpoint.
+    pub fn is_endpoint_failure(&self) -> bool {
+        match self {
+            RpcError::Transport(_) | RpcError::Timeout(_) | RpcError::Http(_) => true,
+            // `NodeUnhealthy`: the node is behind and refuses to serve
+            RpcError::JsonRpc { code, .. } => *code == -32005,
+            RpcError::Decode(_) => false,
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ClientError {
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+
+    /// A [`crate::failover::Failover`] without endpoints.
+    #[error("No RPC endpoint configured")]
+    NoEndpoint,
+
+    #[error("Failed to serialize transaction: {0}")]
+    Serialize(String),
+
+    #[error("Transaction {0} failed: {1}")]
+    TransactionFailed(String, String),
+
+    /// The transaction was sent but not seen confirmed in time; it may still land.
+    #[error("Timed out confirming {0}")]
+    ConfirmTimeout(String),
+
+    #[error("Invalid RPC response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Invalid account {0}: {1}")]
+    InvalidAccount(String, DecodeError),
+
+    #[error("Invalid pubkey: {0}")]
+    InvalidPubkey(String),
+
+    #[error("Invalid signed intent: {0}")]
+    InvalidIntent(String),
+
+    #[error("Transaction {0} contains no janitor BatchClean instruction")]
+    NotJanitorTransaction(String),
+
+    #[error("{0}")]
+    TooManyAccounts(TooManyAccounts),
+}
+
+impl From<TooManyAccounts> for ClientError {
+    fn from(e: TooManyAccounts) -> Self {
+        ClientError::TooManyAccounts(e)
+    }
+}
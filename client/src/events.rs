@@ -0,0 +1,72 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use zera_shared::event::JanitorEvent;
+use zera_shared::PROGRAM_ID;
+
+use crate::pubkey::encode_pubkey;
+
+/// Decode every `JanitorEvent` logged directly by the janitor program.
+///
+/// `Program data:` lines are only attributed to the janitor when it is the
+/// innermost program on the invocation stack, so data logged by CPI targets
+/// is ignored.
+pub fn parse_events(logs: &[String]) -> Vec<JanitorEvent> {
+    let program_id = encode_pubkey(&PROGRAM_ID);
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(rest) = line.strip_prefix("Program data: ") {
+            if stack.last() == Some(&program_id.as_str()) {
+                events.extend(rest.split(' ').filter_map(|chunk| {
+                    STANDARD.decode(chunk).ok().and_then(|b| JanitorEvent::unpack(&b))
+                }));
+            }
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut parts = rest.split(' ');
+            let id = parts.next().unwrap_or_default();
+            match parts.next() {
+                Some("invoke") => stack.push(id),
+                Some("success") | Some("failed:") => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_janitor_events_are_decoded() {
+        let event = JanitorEvent::BatchClean {
+            user: [7u8; 32],
+            destination: [7u8; 32],
+            referrer: None,
+            accounts_closed: 1,
+            rent_collected: 2_039_280,
+            unwrapped: 0,
+            fee_bps: 500,
+            fee: 101_964,
+            referral_fee: 0,
+            relayer_fee: 0,
+            payout: 1_937_316,
+        };
+        let data = STANDARD.encode(event.pack());
+        let janitor = encode_pubkey(&PROGRAM_ID);
+        let logs = vec![
+            format!("Program {} invoke [1]", janitor),
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]".to_string(),
+            format!("Program data: {}", data),
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success".to_string(),
+            format!("Program data: {}", data),
+            format!("Program {} success", janitor),
+        ];
+
+        assert_eq!(parse_events(&logs), vec![event]);
+    }
+}
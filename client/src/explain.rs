@@ -0,0 +1,411 @@
+use serde::Serialize;
+use serde_json::Value;
+use zera_shared::event::JanitorEvent;
+use zera_shared::instruction::JanitorInstruction;
+use zera_shared::{FEE_BPS, PROGRAM_ID};
+
+use crate::error::ClientError;
+use crate::events::parse_events;
+use crate::fees::split_rent;
+use crate::program_error::describe_transaction_error;
+use crate::pubkey::encode_pubkey;
+use crate::rpc::get_transaction;
+use crate::stats::transaction_logs;
+use crate::transport::RpcTransport;
+
+/// A token account targeted by a `BatchClean`, with the lamports it released.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ClosedAccount {
+    pub address: String,
+    pub lamports: u64,
+}
+
+/// What a janitor transaction did, reconstructed from the ledger.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TxExplanation {
+    pub signature: String,
+    pub slot: u64,
+    pub success: bool,
+    /// Decoded failure reason, `None` for successful transactions.
+    pub error: Option<String>,
+    pub user: String,
+    /// Wallet the payout was sent to; the user unless a destination was given.
+    pub destination: String,
+    /// Referrer sharing the protocol fee, if one was passed.
+    pub referrer: Option<String>,
+    /// Accounts the instruction asked to close; `lamports` is 0 if the transaction failed.
+    pub closed_accounts: Vec<ClosedAccount>,
+    pub rent_collected: u64,
+    /// Wrapped SOL returned fee-free from native-mint accounts, included in `payout`.
+    pub unwrapped: u64,
+    /// Fee rate charged, lower than the standard rate when a fee tier applied.
+    pub fee_bps: u16,
+    /// Total protocol fee, including `referral_fee`.
+    pub fee: u64,
+    pub referral_fee: u64,
+    /// Reimbursed to the relayer that paid the network fee, out of the payout.
+    pub relayer_fee: u64,
+    pub payout: u64,
+    /// Network transaction fee paid by the fee payer.
+    pub network_fee: u64,
+    /// Net change of the user's balance, including the network fee if they paid it.
+    pub user_balance_change: i64,
+}
+
+/// Fetch `signature` and explain the janitor `BatchClean` it contains.
+pub async fn explain_transaction<T: RpcTransport>(
+    transport: &T,
+    signature: &str,
+) -> Result<TxExplanation, ClientError> {
+    let tx = get_transaction(transport, signature).await?;
+    if tx.is_null() {
+        return Err(ClientError::InvalidResponse(format!("Transaction {} not found", signature)));
+    }
+    explain(signature, &tx)
+}
+
+/// Explain a `getTransaction` (`json` encoding) result.
+pub fn explain(signature: &str, tx: &Value) -> Result<TxExplanation, ClientError> {
+    let keys = account_keys(tx);
+    let janitor = encode_pubkey(&PROGRAM_ID);
+    let meta = &tx["meta"];
+
+    let ix = tx["transaction"]["message"]["instructions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|ix| {
+            ix["programIdIndex"]
+                .as_u64()
+                .and_then(|i| keys.get(i as usize))
+                == Some(&janitor)
+        })
+        .ok_or_else(|| ClientError::NotJanitorTransaction(signature.to_string()))?;
+
+    let data = bs58::decode(ix["data"].as_str().unwrap_or_default())
+        .into_vec()
+        .map_err(|e| ClientError::InvalidResponse(format!("Invalid instruction data: {}", e)))?;
+    let Some(JanitorInstruction::BatchClean {
+        num_accounts,
+        has_destination,
+        has_referrer,
+        has_user_stats,
+        has_governance_account,
+        relayer_fee,
+    }) = JanitorInstruction::unpack(&data)
+    else {
+        return Err(ClientError::NotJanitorTransaction(signature.to_string()));
+    };
+
+    let ix_accounts: Vec<usize> = ix["accounts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|i| i.as_u64().map(|i| i as usize))
+        .collect();
+    let balance_change = |idx: usize| -> i64 {
+        let pre = meta["preBalances"][idx].as_i64().unwrap_or(0);
+        let post = meta["postBalances"][idx].as_i64().unwrap_or(0);
+        post - pre
+    };
+
+    // Header: user, vault, treasury, token program, config, then the optional accounts
+    let user_idx = ix_accounts.first().copied().unwrap_or(0);
+    let destination_idx = match has_destination {
+        true => ix_accounts.get(5).copied().unwrap_or(user_idx),
+        false => user_idx,
+    };
+    let referrer_idx = has_referrer
+        .then(|| ix_accounts.get(5 + has_destination as usize).copied())
+        .flatten();
+    let optional_accounts = [
+        has_destination,
+        has_referrer,
+        has_user_stats,
+        has_governance_account,
+        relayer_fee.is_some(),
+    ]
+    .into_iter()
+        .filter(|&present| present)
+        .count();
+    let closed_accounts: Vec<ClosedAccount> = ix_accounts
+        .iter()
+        .skip(5 + optional_accounts)
+        .take(num_accounts as usize)
+        .map(|&idx| ClosedAccount {
+            address: keys.get(idx).cloned().unwrap_or_default(),
+            lamports: (-balance_change(idx)).max(0) as u64,
+        })
+        .collect();
+
+    let logs = transaction_logs(tx);
+    let success = meta["err"].is_null();
+    let error = (!success).then(|| describe_transaction_error(&meta["err"], &logs));
+    let relayer_fee = if success { relayer_fee.unwrap_or(0) } else { 0 };
+
+    let (rent_collected, unwrapped, fee_bps, fee, referral_fee, payout) = if !success {
+        (0, 0, fee_bps_from_logs(&logs), 0, 0, 0)
+    } else if let Some(JanitorEvent::BatchClean {
+        rent_collected,
+        unwrapped,
+        fee_bps,
+        fee,
+        referral_fee,
+        payout,
+        ..
+    }) = parse_events(&logs).into_iter().next()
+    {
+        (rent_collected, unwrapped, fee_bps, fee, referral_fee, payout)
+    } else {
+        let fee_bps = fee_bps_from_logs(&logs);
+        let unwrapped = unwrapped_from_logs(&logs);
+        let (rent, fee, payout) = split_from_logs(&logs).unwrap_or_else(|| {
+            let released: u64 = closed_accounts.iter().map(|a| a.lamports).sum();
+            let rent = released.saturating_sub(unwrapped);
+            let (fee, payout) = split_rent(rent, fee_bps);
+            (rent, fee, (payout + unwrapped).saturating_sub(relayer_fee))
+        });
+        let referral_fee = referrer_idx.map_or(0, |idx| balance_change(idx).max(0) as u64);
+        (rent, unwrapped, fee_bps, fee, referral_fee, payout)
+    };
+
+    Ok(TxExplanation {
+        signature: signature.to_string(),
+        slot: tx["slot"].as_u64().unwrap_or(0),
+        success,
+        error,
+        user: keys.get(user_idx).cloned().unwrap_or_default(),
+        destination: keys.get(destination_idx).cloned().unwrap_or_default(),
+        referrer: referrer_idx.and_then(|idx| keys.get(idx).cloned()),
+        closed_accounts,
+        rent_collected,
+        unwrapped,
+        fee_bps,
+        fee,
+        referral_fee,
+        relayer_fee,
+        payout,
+        network_fee: meta["fee"].as_u64().unwrap_or(0),
+        user_balance_change: balance_change(user_idx),
+    })
+}
+
+/// Static account keys followed by addresses loaded from lookup tables.
+fn account_keys(tx: &Value) -> Vec<String> {
+    let loaded = &tx["meta"]["loadedAddresses"];
+    [
+        &tx["transaction"]["message"]["accountKeys"],
+        &loaded["writable"],
+        &loaded["readonly"],
+    ]
+    .into_iter()
+    .filter_map(Value::as_array)
+    .flatten()
+    .filter_map(|k| k.as_str().map(|s| s.to_string()))
+    .collect()
+}
+
+/// Fee rate from the program's "Fee tier" log, or the standard rate if none applied.
+fn fee_bps_from_logs(logs: &[String]) -> u16 {
+    logs.iter()
+        .find_map(|l| l.strip_prefix("Program log: Fee tier: ")?.strip_suffix(" bps")?.parse().ok())
+        .unwrap_or(FEE_BPS as u16)
+}
+
+/// Wrapped SOL from the program's "Unwrapped" log, 0 if no native-mint account was closed.
+fn unwrapped_from_logs(logs: &[String]) -> u64 {
+    logs.iter()
+        .find_map(|l| l.strip_prefix("Program log: Unwrapped: ")?.strip_suffix(" lamports")?.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Recover `(rent, fee, payout)` from the program's `msg!` lines.
+fn split_from_logs(logs: &[String]) -> Option<(u64, u64, u64)> {
+    let rent = logs.iter().find_map(|l| {
+        l.strip_prefix("Program log: Rent collected: ")?
+            .strip_suffix(" lamports")?
+            .parse()
+            .ok()
+    })?;
+    let (fee, payout) = logs.iter().find_map(|l| {
+        let rest = l.strip_prefix("Program log: Fee: ")?;
+        let (fee, payout) = rest.split_once(" lamports, User payout: ")?;
+        Some((fee.parse().ok()?, payout.strip_suffix(" lamports")?.parse().ok()?))
+    })?;
+    Some((rent, fee, payout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use zera_shared::{SPL_TOKEN_PROGRAM_ID, TREASURY_PUBKEY};
+
+    fn sample_tx(err: Value, logs: Vec<String>, post_token: u64) -> Value {
+        let data = bs58::encode(JanitorInstruction::BatchClean {
+            num_accounts: 1,
+            has_destination: false,
+            has_referrer: false,
+            has_user_stats: false,
+            has_governance_account: false,
+            relayer_fee: None,
+        }
+        .pack()).into_string();
+        json!({
+            "slot": 42,
+            "transaction": {
+                "message": {
+                    "accountKeys": [
+                        "3XGXduiyZGPNvxyrhbdacXswZjCVgQqEPPRJ3B93TZVs",
+                        "Vault111111111111111111111111111111111111111",
+                        encode_pubkey(&TREASURY_PUBKEY),
+                        encode_pubkey(&SPL_TOKEN_PROGRAM_ID),
+                        "Token11111111111111111111111111111111111111",
+                        encode_pubkey(&PROGRAM_ID),
+                        "Config1111111111111111111111111111111111111",
+                    ],
+                    "instructions": [
+                        { "programIdIndex": 5, "accounts": [0, 1, 2, 3, 6, 4], "data": data }
+                    ]
+                }
+            },
+            "meta": {
+                "err": err,
+                "fee": 5000,
+                "preBalances": [1_000_000, 0, 0, 1, 2_039_280, 1],
+                "postBalances": [2_932_316, 0, 101_964, 1, post_token, 1],
+                "logMessages": logs,
+            }
+        })
+    }
+
+    #[test]
+    fn explains_successful_clean_from_logs() {
+        let logs = vec![
+            "Program log: Rent collected: 2039280 lamports".to_string(),
+            "Program log: Fee: 101964 lamports, User payout: 1937316 lamports".to_string(),
+        ];
+        let explanation = explain("sig", &sample_tx(Value::Null, logs, 0)).unwrap();
+
+        assert!(explanation.success);
+        assert_eq!(explanation.slot, 42);
+        assert_eq!(explanation.user, "3XGXduiyZGPNvxyrhbdacXswZjCVgQqEPPRJ3B93TZVs");
+        assert_eq!(explanation.destination, explanation.user);
+        assert_eq!(
+            explanation.closed_accounts,
+            vec![ClosedAccount {
+                address: "Token11111111111111111111111111111111111111".to_string(),
+                lamports: 2_039_280,
+            }]
+        );
+        assert_eq!((explanation.rent_collected, explanation.fee, explanation.payout), (2_039_280, 101_964, 1_937_316));
+        assert_eq!(explanation.fee_bps, 500);
+        assert_eq!(explanation.network_fee, 5000);
+    }
+
+    #[test]
+    fn reads_payout_destination_before_token_accounts() {
+        let destination = "Dest111111111111111111111111111111111111111";
+        let mut tx = sample_tx(Value::Null, vec![], 0);
+        let message = &mut tx["transaction"]["message"];
+        message["accountKeys"].as_array_mut().unwrap().push(json!(destination));
+        message["instructions"][0]["accounts"] = json!([0, 1, 2, 3, 6, 7, 4]);
+        message["instructions"][0]["data"] = json!(bs58::encode(
+            JanitorInstruction::BatchClean {
+                num_accounts: 1,
+                has_destination: true,
+                has_referrer: false,
+                has_user_stats: false,
+                has_governance_account: false,
+                relayer_fee: None,
+            }
+            .pack()
+        )
+        .into_string());
+
+        let explanation = explain("sig", &tx).unwrap();
+
+        assert_eq!(explanation.destination, destination);
+        assert_eq!(explanation.closed_accounts.len(), 1);
+        assert_eq!(explanation.closed_accounts[0].address, "Token11111111111111111111111111111111111111");
+    }
+
+    #[test]
+    fn skips_tier_accounts_and_reads_tier_rate() {
+        let mut tx = sample_tx(
+            Value::Null,
+            vec![
+                "Program log: Fee tier: 250 bps".to_string(),
+                "Program log: Rent collected: 2039280 lamports".to_string(),
+                "Program log: Fee: 50982 lamports, User payout: 1988298 lamports".to_string(),
+            ],
+            0,
+        );
+        let message = &mut tx["transaction"]["message"];
+        let keys = message["accountKeys"].as_array_mut().unwrap();
+        keys.push(json!("Stats111111111111111111111111111111111111111"));
+        keys.push(json!("Gov1111111111111111111111111111111111111111"));
+        message["instructions"][0]["accounts"] = json!([0, 1, 2, 3, 6, 7, 8, 4]);
+        message["instructions"][0]["data"] = json!(bs58::encode(
+            JanitorInstruction::BatchClean {
+                num_accounts: 1,
+                has_destination: false,
+                has_referrer: false,
+                has_user_stats: true,
+                has_governance_account: true,
+                relayer_fee: None,
+            }
+            .pack()
+        )
+        .into_string());
+
+        let explanation = explain("sig", &tx).unwrap();
+
+        assert_eq!(explanation.closed_accounts[0].address, "Token11111111111111111111111111111111111111");
+        assert_eq!(explanation.fee_bps, 250);
+        assert_eq!((explanation.fee, explanation.payout), (50_982, 1_988_298));
+    }
+
+    #[test]
+    fn skips_fee_payer_and_reads_relayer_fee() {
+        let mut tx = sample_tx(Value::Null, vec![], 0);
+        let message = &mut tx["transaction"]["message"];
+        let relayer = "Relayer111111111111111111111111111111111111";
+        message["accountKeys"].as_array_mut().unwrap().push(json!(relayer));
+        message["instructions"][0]["accounts"] = json!([0, 1, 2, 3, 6, 7, 4]);
+        message["instructions"][0]["data"] = json!(bs58::encode(
+            JanitorInstruction::BatchClean {
+                num_accounts: 1,
+                has_destination: false,
+                has_referrer: false,
+                has_user_stats: false,
+                has_governance_account: false,
+                relayer_fee: Some(5_000),
+            }
+            .pack()
+        )
+        .into_string());
+
+        let explanation = explain("sig", &tx).unwrap();
+
+        assert_eq!(explanation.closed_accounts[0].address, "Token11111111111111111111111111111111111111");
+        assert_eq!(explanation.relayer_fee, 5_000);
+        assert_eq!(explanation.payout, 1_937_316 - 5_000);
+    }
+
+    #[test]
+    fn maps_custom_codes_to_janitor_errors() {
+        let janitor = encode_pubkey(&PROGRAM_ID);
+        let logs = vec![format!("Program {} failed: custom program error: 0x1", janitor)];
+        let err = json!({ "InstructionError": [0, { "Custom": 1 }] });
+        let explanation = explain("sig", &sample_tx(err, logs, 2_039_280)).unwrap();
+
+        assert!(!explanation.success);
+        assert_eq!(
+            explanation.error.as_deref(),
+            Some("Instruction 0 failed: Missing required signer (JanitorError::MissingSigner)")
+        );
+        assert_eq!(explanation.closed_accounts[0].lamports, 0);
+        assert_eq!(explanation.rent_collected, 0);
+    }
+}
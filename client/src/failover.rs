@@ -0,0 +1,213 @@
+//! Ordered RPC endpoints with health checks and automatic failover.
+//!
+//! Endpoints are preferred in the order given. A health check probes each one
+//! with `getHealth` and `getSlot`; endpoints that fail it, or whose slot is more
+//! than [`MAX_SLOT_LAG`] behind the best one, are left out until the next check.
+//! Requests that fail at the transport level move on to the next usable endpoint.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use serde_json::{json, Value};
+
+use crate::error::ClientError;
+use crate::transport::RpcTransport;
+
+/// How far behind the highest reported slot an endpoint may be and still serve requests.
+pub const MAX_SLOT_LAG: u64 = 150;
+
+/// `getHealth` and `getSlot` in one round trip: the endpoint's slot, if it reports healthy.
+pub async fn probe_endpoint<T: RpcTransport>(transport: &T) -> Result<u64, ClientError> {
+    let requests = vec![("getHealth", json!([])), ("getSlot", json!([{ "commitment": "confirmed" }]))];
+    match transport.request_batch(requests).await?.as_slice() {
+        [health, _] if health != "ok" => {
+            Err(ClientError::InvalidResponse(format!("Endpoint reports unhealthy: {}", health)))
+        }
+        [_, slot] => slot.as_u64().ok_or_else(|| ClientError::InvalidResponse("Missing slot".to_string())),
+        _ => Err(ClientError::InvalidResponse("Incomplete health check".to_string())),
+    }
+}
+
+/// Indices of the endpoints worth using, best first: those that answered a probe and
+/// are at most `max_lag` slots behind the highest slot seen.
+pub fn usable_endpoints(slots: &[Option<u64>], max_lag: u64) -> Vec<usize> {
+    let Some(highest) = slots.iter().flatten().max().copied() else {
+        return Vec::new();
+    };
+    slots
+        .iter()
+        .enumerate()
+        .filter(|(_, slot)| slot.is_some_and(|slot| highest - slot <= max_lag))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// An [`RpcTransport`] over an ordered list of endpoints, shareable between threads when the
+/// endpoints are.
+pub struct Failover<T> {
+    endpoints: Vec<T>,
+    /// Endpoints to try, best first; the first one is active.
+    order: Mutex<Vec<usize>>,
+}
+
+impl<T: RpcTransport> Failover<T> {
+    pub fn new(endpoints: Vec<T>) -> Self {
+        let order = (0..endpoints.len()).collect();
+        Self { endpoints, order: Mutex::new(order) }
+    }
+
+    pub fn endpoints(&self) -> &[T] {
+        &self.endpoints
+    }
+
+    /// Index of the endpoint requests currently go to.
+    pub fn active(&self) -> Option<usize> {
+        self.order().first().copied()
+    }
+
+    /// Rank the endpoints from their probed slots (`None` for failed probes).
+    pub fn update(&self, slots: &[Option<u64>]) {
+        let usable = usable_endpoints(slots, MAX_SLOT_LAG);
+        // With nothing usable, keep trying every endpoint rather than none
+        *self.order() = match usable.is_empty() {
+            true => (0..self.endpoints.len()).collect(),
+            false => usable,
+        };
+    }
+
+    /// Probe every endpoint and rank them; returns the probed slots.
+    pub async fn check_health(&self) -> Vec<Option<u64>> {
+        let mut slots = Vec::with_capacity(self.endpoints.len());
+        for endpoint in &self.endpoints {
+            slots.push(probe_endpoint(endpoint).await.ok());
+        }
+        self.update(&slots);
+        slots
+    }
+
+    fn order(&self) -> MutexGuard<'_, Vec<usize>> {
+        // The ranking stays valid whatever a panicking holder was doing with it
+        self.order.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Move a failing endpoint behind the others.
+    fn demote(&self, index: usize) {
+        let mut order = self.order();
+        if let Some(position) = order.iter().position(|&i| i == index) {
+            let failed = order.remove(position);
+            order.push(failed);
+        }
+    }
+}
+
+/// Errors that say something about the endpoint rather than the request.
+fn is_endpoint_failure(error: &ClientError) -> bool {
+    matches!(error, ClientError::Rpc(e) if e.is_endpoint_failure())
+}
+
+impl<T: RpcTransport> RpcTransport for Failover<T> {
+    async fn request(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let order = self.order().clone();
+        let mut last_error = None;
+        for index in order {
+            match self.endpoints[index].request(method, params.clone()).await {
+                Err(e) if is_endpoint_failure(&e) => {
+                    self.demote(index);
+                    last_error = Some(e);
+                }
+                result => return result,
+            }
+        }
+        Err(last_error.unwrap_or(ClientError::NoEndpoint))
+    }
+
+    async fn request_batch(&self, requests: Vec<(&str, Value)>) -> Result<Vec<Value>, ClientError> {
+        let order = self.order().clone();
+        let mut last_error = None;
+        for index in order {
+            match self.endpoints[index].request_batch(requests.clone()).await {
+                Err(e) if is_endpoint_failure(&e) => {
+                    self.demote(index);
+                    last_error = Some(e);
+                }
+                result => return result,
+            }
+        }
+        Err(last_error.unwrap_or(ClientError::NoEndpoint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::error::RpcError;
+
+    /// An endpoint at a fixed slot, optionally failing every request.
+    struct FakeEndpoint {
+        slot: u64,
+        down: bool,
+        calls: Cell<u32>,
+    }
+
+    impl FakeEndpoint {
+        fn new(slot: u64, down: bool) -> Self {
+            Self { slot, down, calls: Cell::new(0) }
+        }
+    }
+
+    impl RpcTransport for FakeEndpoint {
+        async fn request(&self, method: &str, _params: Value) -> Result<Value, ClientError> {
+            self.calls.set(self.calls.get() + 1);
+            if self.down {
+                return Err(RpcError::Http(503).into());
+            }
+            match method {
+                "getHealth" => Ok(json!("ok")),
+                "getSlot" => Ok(json!(self.slot)),
+                _ => {
+                    let message = "Method not found".to_string();
+                    Err(RpcError::JsonRpc { code: -32601, message, data: None }.into())
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn refuses_lagging_and_failed_endpoints() {
+        let slots = [Some(1_000), None, Some(1_200), Some(1_100), Some(1_050)];
+        assert_eq!(usable_endpoints(&slots, 150), vec![2, 3, 4]);
+        assert_eq!(usable_endpoints(&slots, 1_000), vec![0, 2, 3, 4]);
+        assert!(usable_endpoints(&[None, None], 150).is_empty());
+    }
+
+    #[test]
+    fn fails_over_to_the_next_usable_endpoint() {
+        let failover = Failover::new(vec![
+            FakeEndpoint::new(800, false),
+            FakeEndpoint::new(1_000, true),
+            FakeEndpoint::new(1_000, false),
+        ]);
+        assert_eq!(block_on(failover.check_health()), vec![Some(800), None, Some(1_000)]);
+        assert_eq!(failover.active(), Some(2));
+
+        // The first endpoint is down: the request moves on and the second becomes active
+        let failover = Failover::new(vec![FakeEndpoint::new(1_000, true), FakeEndpoint::new(1_000, false)]);
+        assert_eq!(block_on(failover.request("getSlot", json!([]))), Ok(json!(1_000)));
+        assert_eq!(failover.active(), Some(1));
+        assert_eq!(failover.endpoints()[0].calls.get(), 1);
+
+        // Request errors come from the node, not the connection: no failover
+        let result = block_on(failover.request("getNothing", json!([])));
+        assert!(matches!(result, Err(ClientError::Rpc(RpcError::JsonRpc { code: -32601, .. }))));
+        assert_eq!(failover.endpoints()[0].calls.get(), 1);
+
+        // Nor do failures after the request went through
+        assert!(!is_endpoint_failure(&ClientError::ConfirmTimeout("sig".to_string())));
+        assert!(!is_endpoint_failure(&ClientError::Serialize("too large".to_string())));
+        let empty = Failover::<FakeEndpoint>::new(vec![]);
+        assert_eq!(block_on(empty.request("getSlot", json!([]))), Err(ClientError::NoEndpoint));
+    }
+}
@@ -0,0 +1,113 @@
+use serde::Serialize;
+use zera_shared::config::ProgramConfig;
+use zera_shared::BPS_DENOMINATOR;
+
+use crate::scanner::TokenAccountInfo;
+
+/// Expected outcome of cleaning a set of accounts, in lamports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FeePreview {
+    /// Fee rate the preview was computed at, after any fee tier.
+    pub fee_bps: u16,
+    pub total_rent: u64,
+    /// Total protocol fee, including `referral_fee`.
+    pub fee: u64,
+    /// Part of `fee` paid to the referrer.
+    pub referral_fee: u64,
+    /// Wrapped SOL returned fee-free, already included in `payout`.
+    pub unwrapped: u64,
+    /// Reimbursed to a relayer paying the network fees, already taken out of `payout`.
+    pub relayer_fee: u64,
+    pub payout: u64,
+}
+
+impl FeePreview {
+    pub fn total_rent_sol(&self) -> f64 {
+        lamports_to_sol(self.total_rent)
+    }
+
+    pub fn fee_sol(&self) -> f64 {
+        lamports_to_sol(self.fee)
+    }
+
+    pub fn treasury_fee_sol(&self) -> f64 {
+        lamports_to_sol(self.fee - self.referral_fee)
+    }
+
+    pub fn referral_fee_sol(&self) -> f64 {
+        lamports_to_sol(self.referral_fee)
+    }
+
+    pub fn unwrapped_sol(&self) -> f64 {
+        lamports_to_sol(self.unwrapped)
+    }
+
+    pub fn relayer_fee_sol(&self) -> f64 {
+        lamports_to_sol(self.relayer_fee)
+    }
+
+    pub fn payout_sol(&self) -> f64 {
+        lamports_to_sol(self.payout)
+    }
+
+    /// Reimburse a relayer `fee_per_tx` lamports for each of `transactions`.
+    pub fn with_relayer_fee(self, fee_per_tx: u64, transactions: usize) -> Self {
+        let relayer_fee = fee_per_tx.saturating_mul(transactions as u64);
+        Self { relayer_fee, payout: self.payout.saturating_sub(relayer_fee), ..self }
+    }
+}
+
+/// Split collected rent into `(fee, payout)` at `fee_bps` using the program's integer math.
+pub fn split_rent(rent_collected: u64, fee_bps: u16) -> (u64, u64) {
+    let fee = (rent_collected as u128 * fee_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    (fee, rent_collected - fee)
+}
+
+/// Preview fees for `accounts`, rounding per transaction like the program does.
+///
+/// `fee_bps` is the rate the user pays (see [`crate::tiers::FeeStatus::fee_bps`]) and
+/// `referral_bps` the config's referral share, or 0 when no referrer is passed.
+pub fn preview(
+    accounts: &[TokenAccountInfo],
+    max_per_tx: usize,
+    fee_bps: u16,
+    referral_bps: u16,
+) -> FeePreview {
+    let config = ProgramConfig { referral_bps, ..Default::default() };
+    accounts.chunks(max_per_tx.max(1)).fold(
+        FeePreview { fee_bps, ..Default::default() },
+        |acc, chunk| {
+            let rent: u64 = chunk.iter().map(TokenAccountInfo::rent_lamports).sum();
+            let unwrapped: u64 = chunk.iter().map(TokenAccountInfo::wrapped_lamports).sum();
+            let (fee, payout) = split_rent(rent, fee_bps);
+            let (_, referral_fee) = config.split_fee(fee);
+            FeePreview {
+                fee_bps,
+                total_rent: acc.total_rent + rent,
+                fee: acc.fee + fee,
+                referral_fee: acc.referral_fee + referral_fee,
+                unwrapped: acc.unwrapped + unwrapped,
+                payout: acc.payout + payout + unwrapped,
+                ..acc
+            }
+        },
+    )
+}
+
+pub fn lamports_to_sol(lamports: u64) -> f64 {
+    lamports as f64 / 1_000_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relayer_fee_saturates_instead_of_overflowing() {
+        let preview = FeePreview { payout: 2_000_000, ..Default::default() };
+        assert_eq!(preview.with_relayer_fee(10_000, 3).payout, 1_970_000);
+
+        let preview = preview.with_relayer_fee(u64::MAX / 2, 3);
+        assert_eq!((preview.relayer_fee, preview.payout), (u64::MAX, 0));
+    }
+}
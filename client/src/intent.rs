@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use zera_shared::instruction::{self, Instruction};
+use zera_shared::intent::{CleanIntent, MAX_INTENT_MINTS};
+use zera_shared::FEE_BPS;
+
+use crate::crank::{scan_crankable, DelegatedAccount};
+use crate::error::ClientError;
+use crate::fees::{split_rent, FeePreview};
+use crate::pda::{find_config_pda, find_janitor_pda, find_vault_pda};
+use crate::pubkey::{decode_pubkey, encode_pubkey};
+use crate::transport::RpcTransport;
+
+/// Maximum token accounts closed by one `IntentClean`; the Ed25519
+/// instruction carrying the intent takes most of the transaction.
+pub const MAX_INTENT_ACCOUNTS_PER_TX: usize = 8;
+
+/// A [`CleanIntent`] and the user's signature over its message, as handed
+/// from the signing wallet to a relayer (JSON, base58 keys).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedIntent {
+    pub user: String,
+    pub destination: String,
+    pub allowed_mints: Vec<String>,
+    /// Unix timestamp after which the program rejects the intent.
+    pub expires_at: i64,
+    /// Ed25519 signature over [`CleanIntent::message`] (base58)
+    pub signature: String,
+}
+
+impl SignedIntent {
+    pub fn new(intent: &CleanIntent, signature: &[u8; 64]) -> Self {
+        Self {
+            user: encode_pubkey(&intent.user),
+            destination: encode_pubkey(&intent.destination),
+            allowed_mints: intent.allowed_mints.iter().map(encode_pubkey).collect(),
+            expires_at: intent.expires_at,
+            signature: bs58::encode(signature).into_string(),
+        }
+    }
+
+    /// Decode back into the intent and its raw signature.
+    pub fn decode(&self) -> Result<(CleanIntent, [u8; 64]), ClientError> {
+        if self.allowed_mints.len() > MAX_INTENT_MINTS {
+            return Err(ClientError::InvalidIntent(format!("more than {} mints", MAX_INTENT_MINTS)));
+        }
+        let intent = CleanIntent {
+            user: decode_pubkey(&self.user)?,
+            destination: decode_pubkey(&self.destination)?,
+            allowed_mints: self
+                .allowed_mints
+                .iter()
+                .map(|m| decode_pubkey(m))
+                .collect::<Result<_, _>>()?,
+            expires_at: self.expires_at,
+        };
+        let signature = bs58::decode(&self.signature)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| ClientError::InvalidIntent("signature must be 64 bytes".to_string()))?;
+        Ok((intent, signature))
+    }
+}
+
+/// One `IntentClean` transaction: the Ed25519 verification then the clean.
+#[derive(Clone, Debug)]
+pub struct IntentBatch {
+    pub instructions: Vec<Instruction>,
+    pub accounts: Vec<DelegatedAccount>,
+}
+
+/// Empty delegated accounts of the intent's user with an allowed mint.
+pub async fn scan_intent_accounts<T: RpcTransport>(
+    transport: &T,
+    intent: &SignedIntent,
+) -> Result<Vec<DelegatedAccount>, ClientError> {
+    Ok(scan_crankable(transport, Some(&intent.user))
+        .await?
+        .into_iter()
+        .filter(|acc| intent.allowed_mints.contains(&acc.mint))
+        .collect())
+}
+
+/// Split `accounts` into `IntentClean` transactions submitted by `submitter`.
+pub fn build_intent_batches(
+    submitter: &str,
+    signed: &SignedIntent,
+    accounts: &[DelegatedAccount],
+    max_per_tx: usize,
+) -> Result<Vec<IntentBatch>, ClientError> {
+    let submitter = decode_pubkey(submitter)?;
+    let (intent, signature) = signed.decode()?;
+    let (vault, _) = find_vault_pda();
+    let (config, _) = find_config_pda();
+    let (janitor, _) = find_janitor_pda();
+
+    accounts
+        .chunks(max_per_tx.clamp(1, MAX_INTENT_ACCOUNTS_PER_TX))
+        .map(|chunk| {
+            let keys = chunk
+                .iter()
+                .map(|acc| decode_pubkey(&acc.address))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(IntentBatch {
+                instructions: vec![
+                    instruction::verify_intent(&intent, &signature),
+                    instruction::intent_clean(submitter, vault, config, janitor, &intent, &keys)?,
+                ],
+                accounts: chunk.to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// Preview submitting `batches`; the standard fee applies to each transaction's rent.
+pub fn preview(batches: &[IntentBatch]) -> FeePreview {
+    batches.iter().fold(FeePreview { fee_bps: FEE_BPS as u16, ..Default::default() }, |acc, batch| {
+        let rent: u64 = batch.accounts.iter().map(|acc| acc.lamports).sum();
+        let (fee, payout) = split_rent(rent, FEE_BPS as u16);
+        FeePreview {
+            total_rent: acc.total_rent + rent,
+            fee: acc.fee + fee,
+            payout: acc.payout + payout,
+            ..acc
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zera_shared::intent::parse_ed25519_instruction_data;
+    use zera_shared::{ED25519_PROGRAM_ID, PROGRAM_ID};
+
+    fn intent() -> CleanIntent {
+        CleanIntent {
+            user: [1u8; 32],
+            destination: [2u8; 32],
+            allowed_mints: vec![[3u8; 32]],
+            expires_at: 1_800_000_000,
+        }
+    }
+
+    #[test]
+    fn signed_intent_round_trips_through_json() {
+        let signed = SignedIntent::new(&intent(), &[9u8; 64]);
+        let json = serde_json::to_string(&signed).unwrap();
+        let decoded: SignedIntent = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.decode().unwrap(), (intent(), [9u8; 64]));
+    }
+
+    #[test]
+    fn rejects_short_signature() {
+        let signed = SignedIntent {
+            signature: bs58::encode([9u8; 32]).into_string(),
+            ..SignedIntent::new(&intent(), &[9u8; 64])
+        };
+        assert!(matches!(signed.decode(), Err(ClientError::InvalidIntent(_))));
+    }
+
+    #[test]
+    fn batches_verify_before_cleaning() {
+        let signed = SignedIntent::new(&intent(), &[9u8; 64]);
+        let account = DelegatedAccount {
+            address: encode_pubkey(&[5u8; 32]),
+            owner: signed.user.clone(),
+            mint: encode_pubkey(&[3u8; 32]),
+            amount: 0,
+            lamports: 2_039_280,
+        };
+        let accounts = vec![account; MAX_INTENT_ACCOUNTS_PER_TX + 1];
+        let batches = build_intent_batches(&encode_pubkey(&[4u8; 32]), &signed, &accounts, 100).unwrap();
+
+        assert_eq!(batches.len(), 2);
+        let [verify, clean] = &batches[1].instructions[..] else {
+            panic!("expected two instructions")
+        };
+        assert_eq!(verify.program_id, ED25519_PROGRAM_ID);
+        let (signer, message) = parse_ed25519_instruction_data(&verify.data).unwrap();
+        assert_eq!(signer, [1u8; 32]);
+        assert_eq!(CleanIntent::from_message(message), Some(intent()));
+        assert_eq!(clean.program_id, PROGRAM_ID);
+        assert_eq!(clean.accounts[1].pubkey, [2u8; 32]);
+        assert_eq!(clean.accounts.len(), 8 + 1);
+
+        let preview = preview(&batches);
+        assert_eq!(preview.total_rent, 9 * 2_039_280);
+        assert_eq!(preview.fee, 8 * 2_039_280 / 20 + 2_039_280 / 20);
+        assert_eq!(preview.payout, preview.total_rent - preview.fee);
+    }
+}
@@ -0,0 +1,148 @@
+//! JSON-RPC 2.0 envelopes and retry policy shared by the HTTP transports.
+//!
+//! Transports only differ in how they move bytes and how they sleep; building
+//! requests, matching batch responses to their ids and deciding when to back
+//! off lives here so the browser and native clients behave the same.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde_json::{json, Value};
+
+use crate::error::RpcError;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A request id unique within this process.
+pub fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A single request envelope with a fresh id.
+pub fn request_body(method: &str, params: Value) -> (u64, Value) {
+    let id = next_id();
+    (id, json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+}
+
+/// A batch of request envelopes, with their ids in request order.
+pub fn batch_body(requests: Vec<(&str, Value)>) -> (Vec<u64>, Value) {
+    let (ids, bodies): (Vec<u64>, Vec<Value>) =
+        requests.into_iter().map(|(method, params)| request_body(method, params)).unzip();
+    (ids, Value::Array(bodies))
+}
+
+/// The `result` of a response envelope, or its `error` as [`RpcError::JsonRpc`].
+pub fn parse_response(response: Value) -> Result<Value, RpcError> {
+    let Value::Object(mut envelope) = response else {
+        return Err(RpcError::Decode("Response is not an object".to_string()));
+    };
+    if let Some(error) = envelope.remove("error") {
+        return Err(RpcError::JsonRpc {
+            code: error["code"].as_i64().unwrap_or_default(),
+            message: error["message"].as_str().unwrap_or("Unknown error").to_string(),
+            data: error.get("data").cloned(),
+        });
+    }
+    envelope.remove("result").ok_or_else(|| RpcError::Decode("No result in response".to_string()))
+}
+
+/// Results of a batch response in the order of `ids`; nodes may answer out of order.
+///
+/// Fails with the first error in request order.
+pub fn parse_batch_response(ids: &[u64], response: Value) -> Result<Vec<Value>, RpcError> {
+    let Value::Array(responses) = response else {
+        return Err(RpcError::Decode("Batch response is not an array".to_string()));
+    };
+    let mut by_id: Vec<(u64, Value)> = responses
+        .into_iter()
+        .map(|r| (r["id"].as_u64().unwrap_or_default(), r))
+        .collect();
+    ids.iter()
+        .map(|id| {
+            let index = by_id
+                .iter()
+                .position(|(response_id, _)| response_id == id)
+                .ok_or_else(|| RpcError::Decode(format!("No response for request {}", id)))?;
+            parse_response(by_id.swap_remove(index).1)
+        })
+        .collect()
+}
+
+/// Exponential backoff for retryable errors (HTTP 429 and 5xx).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Backoff {
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    pub base_delay_ms: u32,
+    pub max_delay_ms: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self { max_retries: 4, base_delay_ms: 250, max_delay_ms: 4_000 }
+    }
+}
+
+impl Backoff {
+    /// How long to wait before retry number `attempt` (0-based) after `error`, or `None`
+    /// to give up.
+    pub fn delay_ms(&self, error: &RpcError, attempt: u32) -> Option<u32> {
+        if !error.is_retryable() || attempt >= self.max_retries {
+            return None;
+        }
+        let delay = self.base_delay_ms.saturating_mul(1 << attempt.min(16));
+        Some(delay.min(self.max_delay_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_batch_responses_by_id() {
+        let (ids, body) = batch_body(vec![("getSlot", json!([])), ("getEpochInfo", json!([]))]);
+        assert_eq!(body[1]["id"], ids[1]);
+        assert_eq!(body[0]["method"], "getSlot");
+
+        let response = json!([
+            { "jsonrpc": "2.0", "id": ids[1], "result": { "epoch": 7 } },
+            { "jsonrpc": "2.0", "id": ids[0], "result": 42 },
+        ]);
+        assert_eq!(parse_batch_response(&ids, response).unwrap(), vec![json!(42), json!({ "epoch": 7 })]);
+
+        let partial = json!([{ "jsonrpc": "2.0", "id": ids[0], "result": 42 }]);
+        assert!(matches!(parse_batch_response(&ids, partial), Err(RpcError::Decode(_))));
+    }
+
+    #[test]
+    fn surfaces_json_rpc_errors() {
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": { "code": -32602, "message": "Invalid params", "data": { "logs": [] } }
+        });
+        assert_eq!(
+            parse_response(response),
+            Err(RpcError::JsonRpc {
+                code: -32602,
+                message: "Invalid params".to_string(),
+                data: Some(json!({ "logs": [] })),
+            })
+        );
+        assert!(matches!(parse_response(json!({ "id": 1 })), Err(RpcError::Decode(_))));
+    }
+
+    #[test]
+    fn backs_off_exponentially_on_rate_limits_and_server_errors() {
+        let backoff = Backoff::default();
+        assert_eq!(backoff.delay_ms(&RpcError::Http(429), 0), Some(250));
+        assert_eq!(backoff.delay_ms(&RpcError::Http(503), 2), Some(1_000));
+        assert_eq!(backoff.delay_ms(&RpcError::Http(502), 3), Some(2_000));
+        assert_eq!(backoff.delay_ms(&RpcError::Http(429), 4), None);
+        assert_eq!(backoff.delay_ms(&RpcError::Http(404), 0), None);
+        assert_eq!(backoff.delay_ms(&RpcError::Timeout(10_000), 0), None);
+
+        let capped = Backoff { max_retries: 10, ..backoff };
+        assert_eq!(capped.delay_ms(&RpcError::Http(500), 9), Some(4_000));
+    }
+}
@@ -0,0 +1,34 @@
+//! Native client SDK for the Zera Janitor program.
+//!
+//! Everything here is independent of the browser: bring an [`RpcTransport`]
+//! implementation (HTTP, a test double, a metered proxy…) and the SDK handles
+//! scanning, instruction building, PDA derivation, fee previews and event
+//! decoding exactly the way the web app does.
+
+pub mod batch;
+pub mod buffer;
+pub mod config;
+pub mod consolidate;
+pub mod crank;
+pub mod error;
+pub mod events;
+pub mod explain;
+pub mod failover;
+pub mod fees;
+pub mod intent;
+pub mod jsonrpc;
+pub mod nft;
+pub mod pda;
+pub mod program_error;
+pub mod pubkey;
+pub mod pubsub;
+pub mod reclaim;
+pub mod rpc;
+pub mod scanner;
+pub mod stats;
+pub mod tiers;
+pub mod transport;
+
+pub use error::{ClientError, RpcError};
+pub use scanner::TokenAccountInfo;
+pub use transport::RpcTransport;
@@ -0,0 +1,155 @@
+use serde::Serialize;
+use zera_shared::instruction::{self, Instruction};
+use zera_shared::token::Mint;
+use zera_shared::FEE_BPS;
+
+use crate::error::ClientError;
+use crate::fees::split_rent;
+use crate::pda::{find_config_pda, find_master_edition_pda, find_metadata_pda};
+use crate::pubkey::{decode_pubkey, encode_pubkey};
+use crate::rpc::{get_multiple_accounts, get_token_accounts_by_owner};
+use crate::scanner::{decode_token_accounts, TokenAccountInfo};
+use crate::transport::RpcTransport;
+
+/// A token account of a Metaplex NFT: decimals 0, supply 1 and a metadata PDA.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct NftAccount {
+    pub account: TokenAccountInfo,
+    /// Metadata PDA (base58)
+    pub metadata: String,
+    pub metadata_lamports: u64,
+    /// Master edition PDA (base58), `None` for NFTs without one
+    pub master_edition: Option<String>,
+    pub edition_lamports: u64,
+}
+
+impl NftAccount {
+    /// Holds the NFT, so it can be burned; empty accounts are left to `BatchClean`.
+    pub fn is_burnable(&self) -> bool {
+        self.account.amount == 1 && self.master_edition.is_some()
+    }
+
+    /// Lamports reclaimed by burning: token account, metadata and master edition rent.
+    pub fn burn_rent(&self) -> u64 {
+        self.account.lamports + self.metadata_lamports + self.edition_lamports
+    }
+
+    /// `(fee, payout)` of burning, at the standard fee.
+    pub fn burn_fee_split(&self) -> (u64, u64) {
+        split_rent(self.burn_rent(), FEE_BPS as u16)
+    }
+}
+
+/// Whether `account` could hold an NFT: a balance of at most one, owner-closeable and not frozen.
+pub fn is_nft_shaped(account: &TokenAccountInfo) -> bool {
+    account.amount <= 1 && !account.is_native && account.close_authority.is_none() && !account.frozen
+}
+
+/// Recognise the NFTs among `owner`'s token accounts.
+///
+/// Candidates' mints are fetched first; only those with supply 1 and decimals 0 get their
+/// metadata and master edition looked up.
+pub async fn scan_nfts<T: RpcTransport>(transport: &T, owner: &str) -> Result<Vec<NftAccount>, ClientError> {
+    let accounts = decode_token_accounts(get_token_accounts_by_owner(transport, owner).await?)?;
+    let candidates: Vec<TokenAccountInfo> = accounts.into_iter().filter(is_nft_shaped).collect();
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mints: Vec<String> = candidates.iter().map(|c| c.mint.clone()).collect();
+    let fetched = get_multiple_accounts(transport, &mints).await?;
+    let mut nfts = Vec::new();
+    for (candidate, mint) in candidates.into_iter().zip(fetched) {
+        // Closed Token-2022 mints leave their token accounts behind
+        let Some((_, data)) = mint else { continue };
+        let mint = match Mint::unpack(&data) {
+            Ok(mint) => mint,
+            Err(e) => return Err(ClientError::InvalidAccount(candidate.mint, e)),
+        };
+        if mint.is_nft() {
+            nfts.push(candidate);
+        }
+    }
+    if nfts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Metadata and master edition of every NFT in one round of lookups
+    let mut keys = Vec::with_capacity(nfts.len() * 2);
+    for nft in &nfts {
+        let mint = decode_pubkey(&nft.mint)?;
+        keys.push(encode_pubkey(&find_metadata_pda(&mint)));
+        keys.push(encode_pubkey(&find_master_edition_pda(&mint)));
+    }
+    let accounts = get_multiple_accounts(transport, &keys).await?;
+
+    Ok(nfts
+        .into_iter()
+        .zip(accounts.chunks(2).zip(keys.chunks(2)))
+        .filter_map(|(account, (fetched, keys))| {
+            let (metadata_lamports, _) = fetched[0].as_ref()?;
+            let edition = fetched[1].as_ref().map(|(lamports, _)| (keys[1].clone(), *lamports));
+            Some(NftAccount {
+                account,
+                metadata: keys[0].clone(),
+                metadata_lamports: *metadata_lamports,
+                master_edition: edition.as_ref().map(|(key, _)| key.clone()),
+                edition_lamports: edition.map_or(0, |(_, lamports)| lamports),
+            })
+        })
+        .collect())
+}
+
+/// Build the `BurnNft` instruction for one of `owner`'s burnable NFTs.
+pub fn build_burn_instruction(owner: &str, nft: &NftAccount) -> Result<Instruction, ClientError> {
+    let master_edition = nft
+        .master_edition
+        .as_deref()
+        .ok_or_else(|| ClientError::InvalidResponse("NFT has no master edition".to_string()))?;
+    Ok(instruction::burn_nft(
+        decode_pubkey(owner)?,
+        find_config_pda().0,
+        decode_pubkey(&nft.account.mint)?,
+        decode_pubkey(&nft.account.address)?,
+        decode_pubkey(&nft.metadata)?,
+        decode_pubkey(master_edition)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nft(amount: u64) -> NftAccount {
+        let mint = [3u8; 32];
+        NftAccount {
+            account: TokenAccountInfo {
+                address: encode_pubkey(&[2u8; 32]),
+                mint: encode_pubkey(&mint),
+                amount,
+                lamports: 2_039_280,
+                is_native: false,
+                close_authority: None,
+                frozen: false,
+            },
+            metadata: encode_pubkey(&find_metadata_pda(&mint)),
+            metadata_lamports: 5_616_720,
+            master_edition: Some(encode_pubkey(&find_master_edition_pda(&mint))),
+            edition_lamports: 2_853_600,
+        }
+    }
+
+    #[test]
+    fn burn_reclaims_all_three_accounts() {
+        let held = nft(1);
+        assert!(held.is_burnable());
+        assert!(!nft(0).is_burnable());
+        assert_eq!(held.burn_rent(), 2_039_280 + 5_616_720 + 2_853_600);
+        assert_eq!(held.burn_fee_split(), split_rent(held.burn_rent(), FEE_BPS as u16));
+
+        let ix = build_burn_instruction(&encode_pubkey(&[1u8; 32]), &held).unwrap();
+        assert_eq!(ix.accounts[3].pubkey, find_metadata_pda(&[3u8; 32]));
+        assert_eq!(ix.accounts[5].pubkey, [2u8; 32]);
+        assert_eq!(ix.accounts[6].pubkey, find_master_edition_pda(&[3u8; 32]));
+    }
+}
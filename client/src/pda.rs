@@ -0,0 +1,165 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use sha2::{Digest, Sha256};
+use zera_shared::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, BPF_LOADER_UPGRADEABLE_ID, CONFIG_SEED, EDITION_SEED, JANITOR_SEED,
+    METADATA_PROGRAM_ID, METADATA_SEED, PROGRAM_ID, SPL_TOKEN_PROGRAM_ID, USER_STATS_SEED, VAULT_SEED,
+};
+
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+/// Derive a program address from `seeds` and a bump, as `Pubkey::create_program_address` does.
+///
+/// Returns `None` if the resulting point lies on the ed25519 curve.
+pub fn create_program_address(seeds: &[&[u8]], program_id: &[u8; 32]) -> Option<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    for seed in seeds {
+        hasher.update(seed);
+    }
+    hasher.update(program_id);
+    hasher.update(PDA_MARKER);
+    let hash: [u8; 32] = hasher.finalize().into();
+
+    match CompressedEdwardsY(hash).decompress() {
+        Some(_) => None,
+        None => Some(hash),
+    }
+}
+
+/// Find the canonical program address and bump seed, as `Pubkey::find_program_address` does.
+pub fn find_program_address(seeds: &[&[u8]], program_id: &[u8; 32]) -> Option<([u8; 32], u8)> {
+    (0..=u8::MAX).rev().find_map(|bump| {
+        let bump_seed = [bump];
+        let mut with_bump = seeds.to_vec();
+        with_bump.push(&bump_seed);
+        create_program_address(&with_bump, program_id).map(|addr| (addr, bump))
+    })
+}
+
+/// Derive the janitor vault PDA and its bump seed.
+pub fn find_vault_pda() -> ([u8; 32], u8) {
+    find_program_address(&[VAULT_SEED], &PROGRAM_ID).expect("Vault PDA must exist")
+}
+
+/// Derive the janitor config PDA and its bump seed.
+pub fn find_config_pda() -> ([u8; 32], u8) {
+    find_program_address(&[CONFIG_SEED], &PROGRAM_ID).expect("Config PDA must exist")
+}
+
+/// Derive the janitor PDA that delegated token accounts name as close authority.
+pub fn find_janitor_pda() -> ([u8; 32], u8) {
+    find_program_address(&[JANITOR_SEED], &PROGRAM_ID).expect("Janitor PDA must exist")
+}
+
+/// Derive `user`'s stats PDA and its bump seed.
+pub fn find_user_stats_pda(user: &[u8; 32]) -> ([u8; 32], u8) {
+    find_program_address(&[USER_STATS_SEED, user], &PROGRAM_ID).expect("User stats PDA must exist")
+}
+
+/// Derive the janitor's `ProgramData` account under the upgradeable loader.
+pub fn find_program_data_address() -> [u8; 32] {
+    find_program_address(&[&PROGRAM_ID], &BPF_LOADER_UPGRADEABLE_ID)
+        .expect("Program data address must exist")
+        .0
+}
+
+/// Derive `owner`'s associated token account for `mint` under SPL Token.
+pub fn find_associated_token_address(owner: &[u8; 32], mint: &[u8; 32]) -> [u8; 32] {
+    find_program_address(&[owner, &SPL_TOKEN_PROGRAM_ID, mint], &ASSOCIATED_TOKEN_PROGRAM_ID)
+        .expect("Associated token address must exist")
+        .0
+}
+
+/// Derive the Metaplex metadata PDA of `mint`.
+pub fn find_metadata_pda(mint: &[u8; 32]) -> [u8; 32] {
+    find_program_address(&[METADATA_SEED, &METADATA_PROGRAM_ID, mint], &METADATA_PROGRAM_ID)
+        .expect("Metadata PDA must exist")
+        .0
+}
+
+/// Derive the Metaplex master edition PDA of `mint`.
+pub fn find_master_edition_pda(mint: &[u8; 32]) -> [u8; 32] {
+    find_program_address(&[METADATA_SEED, &METADATA_PROGRAM_ID, mint, EDITION_SEED], &METADATA_PROGRAM_ID)
+        .expect("Master edition PDA must exist")
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::pubkey::Pubkey;
+
+    #[test]
+    fn vault_pda_matches_solana_program() {
+        let (expected, expected_bump) =
+            Pubkey::find_program_address(&[VAULT_SEED], &Pubkey::new_from_array(PROGRAM_ID));
+        assert_eq!(find_vault_pda(), (expected.to_bytes(), expected_bump));
+    }
+
+    #[test]
+    fn config_pda_matches_solana_program() {
+        let (expected, expected_bump) =
+            Pubkey::find_program_address(&[CONFIG_SEED], &Pubkey::new_from_array(PROGRAM_ID));
+        assert_eq!(find_config_pda(), (expected.to_bytes(), expected_bump));
+    }
+
+    #[test]
+    fn janitor_pda_matches_solana_program() {
+        let (expected, expected_bump) =
+            Pubkey::find_program_address(&[JANITOR_SEED], &Pubkey::new_from_array(PROGRAM_ID));
+        assert_eq!(find_janitor_pda(), (expected.to_bytes(), expected_bump));
+    }
+
+    #[test]
+    fn user_stats_pda_matches_solana_program() {
+        let user = Pubkey::new_unique();
+        let (expected, expected_bump) = Pubkey::find_program_address(
+            &[USER_STATS_SEED, user.as_ref()],
+            &Pubkey::new_from_array(PROGRAM_ID),
+        );
+        assert_eq!(find_user_stats_pda(&user.to_bytes()), (expected.to_bytes(), expected_bump));
+    }
+
+    #[test]
+    fn program_data_address_matches_solana_program() {
+        let program_id = Pubkey::new_from_array(PROGRAM_ID);
+        let (expected, _) = Pubkey::find_program_address(
+            &[program_id.as_ref()],
+            &solana_program::bpf_loader_upgradeable::id(),
+        );
+        assert_eq!(find_program_data_address(), expected.to_bytes());
+    }
+
+    #[test]
+    fn associated_token_address_matches_solana_program() {
+        let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let token_program = Pubkey::new_from_array(SPL_TOKEN_PROGRAM_ID);
+        let (expected, _) = Pubkey::find_program_address(
+            &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &Pubkey::new_from_array(ASSOCIATED_TOKEN_PROGRAM_ID),
+        );
+        assert_eq!(find_associated_token_address(&owner.to_bytes(), &mint.to_bytes()), expected.to_bytes());
+    }
+
+    #[test]
+    fn metaplex_pdas_match_solana_program() {
+        let mint = Pubkey::new_unique();
+        let metadata_program = Pubkey::new_from_array(METADATA_PROGRAM_ID);
+        let seeds = [METADATA_SEED, metadata_program.as_ref(), mint.as_ref()];
+        let (metadata, _) = Pubkey::find_program_address(&seeds, &metadata_program);
+        let edition_seeds = [&seeds[..], &[EDITION_SEED]].concat();
+        let (edition, _) = Pubkey::find_program_address(&edition_seeds, &metadata_program);
+        assert_eq!(find_metadata_pda(&mint.to_bytes()), metadata.to_bytes());
+        assert_eq!(find_master_edition_pda(&mint.to_bytes()), edition.to_bytes());
+    }
+
+    #[test]
+    fn multi_seed_pda_matches_solana_program() {
+        let program_id = Pubkey::new_unique();
+        let seeds: &[&[u8]] = &[b"zera", &[1, 2, 3], &[0xff; 32]];
+        let (expected, bump) = Pubkey::find_program_address(seeds, &program_id);
+        assert_eq!(
+            find_program_address(seeds, &program_id.to_bytes()),
+            Some((expected.to_bytes(), bump))
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use core::fmt;
+
+use serde_json::Value;
+use zera_shared::error::JanitorError;
+use zera_shared::{PROGRAM_ID, SPL_TOKEN_PROGRAM_ID};
+
+use crate::pubkey::encode_pubkey;
+
+pub const SPL_TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// SPL Token program error codes (shared by Token and Token-2022).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TokenError {
+    NotRentExempt,
+    InsufficientFunds,
+    InvalidMint,
+    MintMismatch,
+    OwnerMismatch,
+    FixedSupply,
+    AlreadyInUse,
+    InvalidNumberOfProvidedSigners,
+    InvalidNumberOfRequiredSigners,
+    UninitializedState,
+    NativeNotSupported,
+    NonNativeHasBalance,
+    InvalidInstruction,
+    InvalidState,
+    Overflow,
+    AuthorityTypeNotSupported,
+    MintCannotFreeze,
+    AccountFrozen,
+    MintDecimalsMismatch,
+    NonNativeNotSupported,
+}
+
+impl TokenError {
+    pub const ALL: &'static [TokenError] = &[
+        TokenError::NotRentExempt,
+        TokenError::InsufficientFunds,
+        TokenError::InvalidMint,
+        TokenError::MintMismatch,
+        TokenError::OwnerMismatch,
+        TokenError::FixedSupply,
+        TokenError::AlreadyInUse,
+        TokenError::InvalidNumberOfProvidedSigners,
+        TokenError::InvalidNumberOfRequiredSigners,
+        TokenError::UninitializedState,
+        TokenError::NativeNotSupported,
+        TokenError::NonNativeHasBalance,
+        TokenError::InvalidInstruction,
+        TokenError::InvalidState,
+        TokenError::Overflow,
+        TokenError::AuthorityTypeNotSupported,
+        TokenError::MintCannotFreeze,
+        TokenError::AccountFrozen,
+        TokenError::MintDecimalsMismatch,
+        TokenError::NonNativeNotSupported,
+    ];
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.get(code as usize).copied()
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            TokenError::NotRentExempt => "Lamport balance below rent-exempt threshold",
+            TokenError::InsufficientFunds => "Insufficient funds",
+            TokenError::InvalidMint => "Invalid mint",
+            TokenError::MintMismatch => "Account not associated with this mint",
+            TokenError::OwnerMismatch => "Token account owner does not match",
+            TokenError::FixedSupply => "Fixed supply",
+            TokenError::AlreadyInUse => "Account already in use",
+            TokenError::InvalidNumberOfProvidedSigners => "Invalid number of provided signers",
+            TokenError::InvalidNumberOfRequiredSigners => "Invalid number of required signers",
+            TokenError::UninitializedState => "Token account is not initialized",
+            TokenError::NativeNotSupported => "Instruction does not support native tokens",
+            TokenError::NonNativeHasBalance => "Token account still holds tokens and cannot be closed",
+            TokenError::InvalidInstruction => "Invalid instruction",
+            TokenError::InvalidState => "State is invalid for requested operation",
+            TokenError::Overflow => "Operation overflowed",
+            TokenError::AuthorityTypeNotSupported => "Account does not support specified authority type",
+            TokenError::MintCannotFreeze => "This token mint cannot freeze accounts",
+            TokenError::AccountFrozen => "Token account is frozen",
+            TokenError::MintDecimalsMismatch => "Decimals do not match the mint",
+            TokenError::NonNativeNotSupported => "Instruction does not support non-native tokens",
+        }
+    }
+}
+
+/// Why a janitor transaction did not land, decoded for display.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TxError {
+    Janitor(JanitorError),
+    Token(TokenError),
+    Custom { program: Option<String>, code: u32 },
+    WalletRejected,
+    BlockhashExpired,
+    Other(String),
+}
+
+impl TxError {
+    /// Decode `Custom(code)` raised by `program` (base58 id, if known).
+    pub fn from_custom(program: Option<&str>, code: u32) -> Self {
+        let janitor = encode_pubkey(&PROGRAM_ID);
+        let token = encode_pubkey(&SPL_TOKEN_PROGRAM_ID);
+        let decoded = match program {
+            Some(id) if id == janitor => JanitorError::from_code(code).map(TxError::Janitor),
+            Some(id) if id == token || id == SPL_TOKEN_2022_PROGRAM => {
+                TokenError::from_code(code).map(TxError::Token)
+            }
+            _ => None,
+        };
+        decoded.unwrap_or_else(|| TxError::Custom {
+            program: program.map(|p| p.to_string()),
+            code,
+        })
+    }
+}
+
+impl fmt::Display for TxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxError::Janitor(err) => write!(f, "{}", err),
+            TxError::Token(err) => write!(f, "Token program: {}", err.message()),
+            TxError::Custom { program: Some(p), code } => {
+                write!(f, "Program {} failed with custom error {}", p, code)
+            }
+            TxError::Custom { program: None, code } => write!(f, "Custom program error {}", code),
+            TxError::WalletRejected => f.write_str("Transaction was rejected in the wallet"),
+            TxError::BlockhashExpired => {
+                f.write_str("Transaction expired before it was confirmed, please retry")
+            }
+            TxError::Other(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// Program that failed first, i.e. the innermost `Program <id> failed:` log line.
+pub fn failing_program(logs: &[String]) -> Option<&str> {
+    logs.iter().find_map(|line| {
+        let rest = line.strip_prefix("Program ")?;
+        let (id, tail) = rest.split_once(' ')?;
+        tail.starts_with("failed:").then_some(id)
+    })
+}
+
+/// Explain a `Custom(code)` raised by `program`.
+pub fn describe_custom_error(program: Option<&str>, code: u32) -> String {
+    match TxError::from_custom(program, code) {
+        TxError::Janitor(err) => format!("{} (JanitorError::{:?})", err, err),
+        TxError::Token(err) => format!("{} (TokenError::{:?})", err.message(), err),
+        other => other.to_string(),
+    }
+}
+
+/// Human-readable explanation of a transaction's `meta.err`.
+pub fn describe_transaction_error(err: &Value, logs: &[String]) -> String {
+    if let Some([index, detail]) = err["InstructionError"].as_array().map(Vec::as_slice) {
+        let detail = match detail["Custom"].as_u64() {
+            Some(code) => describe_custom_error(failing_program(logs), code as u32),
+            None => detail
+                .as_str()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| detail.to_string()),
+        };
+        return format!("Instruction {} failed: {}", index, detail);
+    }
+    err.as_str()
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| err.to_string())
+}
+
+/// Wallet error code for "user rejected the request" (EIP-1193 / Solana wallet standard).
+const WALLET_REJECTED_CODE: i64 = 4001;
+
+/// Classify an error raised while signing or sending a transaction.
+///
+/// `message` is the error text, `wallet_code` the wallet adapter's numeric
+/// code if any, and `logs` the simulation logs attached to send errors.
+pub fn classify_send_error(message: &str, wallet_code: Option<i64>, logs: &[String]) -> TxError {
+    let lower = message.to_lowercase();
+
+    if wallet_code == Some(WALLET_REJECTED_CODE)
+        || lower.contains("user rejected")
+        || lower.contains("rejected the request")
+    {
+        return TxError::WalletRejected;
+    }
+
+    if lower.contains("blockhash not found")
+        || lower.contains("block height exceeded")
+        || lower.contains("blockhash expired")
+    {
+        return TxError::BlockhashExpired;
+    }
+
+    if let Some(code) = parse_custom_code(&lower) {
+        return TxError::from_custom(failing_program(logs), code);
+    }
+
+    TxError::Other(message.to_string())
+}
+
+/// Extract `n` from "custom program error: 0x<n>".
+fn parse_custom_code(message: &str) -> Option<u32> {
+    let (_, rest) = message.split_once("custom program error: 0x")?;
+    let hex: String = rest.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_token_errors_from_simulation_logs() {
+        let logs = vec![
+            format!("Program {} invoke [1]", encode_pubkey(&PROGRAM_ID)),
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]".to_string(),
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: custom program error: 0xb"
+                .to_string(),
+            format!("Program {} failed: custom program error: 0xb", encode_pubkey(&PROGRAM_ID)),
+        ];
+        let msg = "Transaction simulation failed: Error processing Instruction 0: custom program error: 0xb";
+
+        assert_eq!(
+            classify_send_error(msg, None, &logs),
+            TxError::Token(TokenError::NonNativeHasBalance)
+        );
+    }
+
+    #[test]
+    fn classifies_janitor_wallet_and_blockhash_errors() {
+        let janitor = encode_pubkey(&PROGRAM_ID);
+        let logs = vec![format!("Program {} failed: custom program error: 0x0", janitor)];
+
+        assert_eq!(
+            classify_send_error("custom program error: 0x0", None, &logs),
+            TxError::Janitor(JanitorError::InvalidVaultPda)
+        );
+        assert_eq!(
+            classify_send_error("User rejected the request.", Some(4001), &[]),
+            TxError::WalletRejected
+        );
+        assert_eq!(
+            classify_send_error("Blockhash not found", None, &[]),
+            TxError::BlockhashExpired
+        );
+    }
+}
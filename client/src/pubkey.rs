@@ -0,0 +1,15 @@
+use crate::error::ClientError;
+
+/// Decode a base58 pubkey into raw bytes.
+pub fn decode_pubkey(pubkey: &str) -> Result<[u8; 32], ClientError> {
+    bs58::decode(pubkey)
+        .into_vec()
+        .map_err(|e| ClientError::InvalidPubkey(format!("{}: {}", pubkey, e)))?
+        .try_into()
+        .map_err(|_| ClientError::InvalidPubkey(format!("{}: expected 32 bytes", pubkey)))
+}
+
+/// Encode raw pubkey bytes as base58.
+pub fn encode_pubkey(pubkey: &[u8; 32]) -> String {
+    bs58::encode(pubkey).into_string()
+}
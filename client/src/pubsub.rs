@@ -0,0 +1,134 @@
+//! Solana PubSub (WebSocket) subscriptions.
+//!
+//! Sockets are too platform-specific to share, so this module only builds the
+//! subscribe requests and parses what the node pushes back; the browser's live
+//! view and a native watcher read notifications the same way.
+
+use serde_json::{json, Value};
+
+use crate::error::{ClientError, RpcError};
+use crate::jsonrpc::{parse_response, request_body};
+use crate::rpc::{decode_account, KeyedAccount};
+
+/// PubSub URL of the node serving JSON-RPC at `http_url`: `ws`/`wss`, and for explicit
+/// non-default ports the one after the RPC port, as `solana-test-validator` serves it.
+pub fn ws_url(http_url: &str) -> String {
+    let (scheme, rest) = match http_url.split_once("://") {
+        Some(("https", rest)) => ("wss", rest),
+        Some((_, rest)) => ("ws", rest),
+        None => ("ws", http_url),
+    };
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let authority = match authority.rsplit_once(':').map(|(host, port)| (host, port.parse::<u16>())) {
+        Some((host, Ok(port))) if port != 80 && port != 443 => format!("{}:{}", host, port + 1),
+        _ => authority.to_string(),
+    };
+    format!("{}://{}{}", scheme, authority, path)
+}
+
+/// `signatureSubscribe`: one notification once the transaction is confirmed.
+pub fn signature_subscribe(signature: &str) -> (u64, Value) {
+    request_body("signatureSubscribe", json!([signature, { "commitment": "confirmed" }]))
+}
+
+/// `programSubscribe` on accounts owned by `program_id` matching `filters` (`base64`).
+pub fn program_subscribe(program_id: &str, filters: Value) -> (u64, Value) {
+    let config = json!({ "encoding": "base64", "filters": filters, "commitment": "confirmed" });
+    request_body("programSubscribe", json!([program_id, config]))
+}
+
+/// `accountSubscribe` on `address` (`base64`); unlike program subscriptions it also reports
+/// the account being closed.
+pub fn account_subscribe(address: &str) -> (u64, Value) {
+    request_body("accountSubscribe", json!([address, { "encoding": "base64", "commitment": "confirmed" }]))
+}
+
+/// Cancel a subscription made with `{kind}Subscribe`, e.g. `account`.
+pub fn unsubscribe(kind: &str, subscription: u64) -> (u64, Value) {
+    request_body(&format!("{}Unsubscribe", kind), json!([subscription]))
+}
+
+/// A message pushed by the node.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PubsubMessage {
+    /// Reply to the request `id`: the subscription id for subscribe requests.
+    Reply { id: u64, result: Result<Value, RpcError> },
+    /// The transaction was confirmed; `err` is set if it failed.
+    Signature { subscription: u64, err: Option<Value> },
+    /// An account owned by the subscribed program changed.
+    Program { subscription: u64, account: KeyedAccount },
+    /// The subscribed account changed; closed accounts have no lamports and no data.
+    Account { subscription: u64, lamports: u64, data: Vec<u8> },
+}
+
+/// Parse one WebSocket text frame.
+pub fn parse_message(text: &str) -> Result<PubsubMessage, ClientError> {
+    let message: Value = serde_json::from_str(text).map_err(|e| RpcError::Decode(e.to_string()))?;
+    if let Some(id) = message["id"].as_u64() {
+        return Ok(PubsubMessage::Reply { id, result: parse_response(message) });
+    }
+
+    let invalid = |what: &str| ClientError::InvalidResponse(what.to_string());
+    let params = &message["params"];
+    let subscription = params["subscription"].as_u64().ok_or_else(|| invalid("Missing subscription"))?;
+    let value = &params["result"]["value"];
+    match message["method"].as_str() {
+        Some("signatureNotification") => {
+            let err = Some(value["err"].clone()).filter(|err| !err.is_null());
+            Ok(PubsubMessage::Signature { subscription, err })
+        }
+        Some("programNotification") => {
+            let address = value["pubkey"].as_str().ok_or_else(|| invalid("Missing account pubkey"))?;
+            let (lamports, data) = decode_account(&value["account"])?;
+            Ok(PubsubMessage::Program { subscription, account: (address.to_string(), lamports, data) })
+        }
+        Some("accountNotification") => {
+            let (lamports, data) = decode_account(value)?;
+            Ok(PubsubMessage::Account { subscription, lamports, data })
+        }
+        method => Err(ClientError::InvalidResponse(format!("Unexpected notification {:?}", method))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_websocket_url() {
+        assert_eq!(ws_url("http://127.0.0.1:8899"), "ws://127.0.0.1:8900");
+        assert_eq!(ws_url("https://api.mainnet-beta.solana.com"), "wss://api.mainnet-beta.solana.com");
+        assert_eq!(ws_url("https://rpc.example.com:443/key/abc"), "wss://rpc.example.com:443/key/abc");
+    }
+
+    #[test]
+    fn parses_replies_and_notifications() {
+        let (id, body) = account_subscribe("acc");
+        assert_eq!(body["method"], "accountSubscribe");
+        let reply = parse_message(&format!(r#"{{"jsonrpc":"2.0","result":23784,"id":{}}}"#, id)).unwrap();
+        assert_eq!(reply, PubsubMessage::Reply { id, result: Ok(json!(23784)) });
+
+        let notification = |method: &str, subscription: u64, value: Value| {
+            let result = json!({ "context": { "slot": 5 }, "value": value });
+            let params = json!({ "subscription": subscription, "result": result });
+            json!({ "jsonrpc": "2.0", "method": method, "params": params }).to_string()
+        };
+
+        let system = "11111111111111111111111111111111";
+        let closed = json!({ "lamports": 0, "data": ["", "base64"], "owner": system });
+        let closed = parse_message(&notification("accountNotification", 23784, closed)).unwrap();
+        assert_eq!(closed, PubsubMessage::Account { subscription: 23784, lamports: 0, data: vec![] });
+
+        let created = json!({
+            "pubkey": "acc",
+            "account": { "lamports": 2_039_280, "data": ["AQID", "base64"] }
+        });
+        let created = parse_message(&notification("programNotification", 7, created)).unwrap();
+        let account = ("acc".to_string(), 2_039_280, vec![1, 2, 3]);
+        assert_eq!(created, PubsubMessage::Program { subscription: 7, account });
+
+        let err = json!({ "InstructionError": [0, { "Custom": 1 }] });
+        let failed = parse_message(&notification("signatureNotification", 8, json!({ "err": err }))).unwrap();
+        assert_eq!(failed, PubsubMessage::Signature { subscription: 8, err: Some(err) });
+    }
+}
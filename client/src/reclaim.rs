@@ -0,0 +1,481 @@
+//! Reclaimable accounts that aren't SPL token accounts.
+//!
+//! Each [`ReclaimableSource`] knows how to find one kind of account still
+//! holding rent for a wallet and how to get the lamports back out of it. New
+//! kinds plug in by implementing the trait, adding a [`ReclaimKind`] variant and
+//! listing the source in [`scan_reclaimable`] and [`build_reclaim_instruction`].
+
+use serde::Serialize;
+use serde_json::json;
+use zera_shared::instruction::{AccountMeta, Instruction};
+use zera_shared::SYSTEM_PROGRAM_ID;
+
+use crate::error::ClientError;
+use crate::pubkey::{decode_pubkey, encode_pubkey};
+use crate::rpc::{get_account_data, get_epoch, get_program_accounts_sliced, memcmp};
+use crate::transport::RpcTransport;
+
+pub const STAKE_PROGRAM: &str = "Stake11111111111111111111111111111111111111";
+const SYSVAR_CLOCK: &str = "SysvarC1ock11111111111111111111111111111111";
+const SYSVAR_STAKE_HISTORY: &str = "SysvarStakeHistory1111111111111111111111111";
+const SYSVAR_RECENT_BLOCKHASHES: &str = "SysvarRecentB1ockHashes11111111111111111111";
+const SYSVAR_RENT: &str = "SysvarRent111111111111111111111111111111111";
+
+/// Nonce account layout: `u32` version, `u32` state, then the authority.
+const NONCE_ACCOUNT_LEN: u64 = 80;
+const NONCE_INITIALIZED: [u8; 8] = [1, 0, 0, 0, 1, 0, 0, 0];
+const NONCE_AUTHORITY_OFFSET: u64 = 8;
+/// `SystemInstruction::WithdrawNonceAccount` variant tag.
+const WITHDRAW_NONCE_TAG: u32 = 5;
+
+/// Stake account layout: `u32` state, `Meta` (reserve, staker, withdrawer, lockup), then the delegation.
+const STAKE_ACCOUNT_LEN: u64 = 200;
+const STAKE_INITIALIZED: u32 = 1;
+const STAKE_DELEGATED: u32 = 2;
+const STAKE_WITHDRAWER_OFFSET: u64 = 44;
+const STAKE_LOCKUP_TIMESTAMP_OFFSET: usize = 76;
+const STAKE_LOCKUP_EPOCH_OFFSET: usize = 84;
+const STAKE_AMOUNT_OFFSET: usize = 156;
+const STAKE_ACTIVATION_EPOCH_OFFSET: usize = 164;
+const STAKE_DEACTIVATION_EPOCH_OFFSET: usize = 172;
+/// Cluster cooldown rate per epoch, the slower of the two the runtime has used.
+const COOLDOWN_RATE_PERCENT: u128 = 9;
+/// `StakeInstruction::Withdraw` variant tag.
+const WITHDRAW_STAKE_TAG: u32 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReclaimKind {
+    Nonce,
+    Stake,
+}
+
+impl ReclaimKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReclaimKind::Nonce => "Nonce",
+            ReclaimKind::Stake => "Stake",
+        }
+    }
+}
+
+/// An account whose full balance can be withdrawn back to the wallet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReclaimableAccount {
+    /// Account address (base58)
+    pub address: String,
+    pub kind: ReclaimKind,
+    pub lamports: u64,
+    /// Authority allowed to withdraw (base58)
+    pub authority: String,
+    /// Stake still deactivating: listed, but not withdrawable in full until the cooldown ends
+    pub cooling_down: bool,
+}
+
+impl ReclaimableAccount {
+    pub fn sol(&self) -> f64 {
+        crate::fees::lamports_to_sol(self.lamports)
+    }
+}
+
+/// One kind of reclaimable account: how to find it and how to empty it.
+#[allow(async_fn_in_trait)]
+pub trait ReclaimableSource {
+    const KIND: ReclaimKind;
+
+    /// Accounts of this kind `authority` can withdraw in full right now, or once their
+    /// `cooling_down` ends.
+    async fn scan<T: RpcTransport>(
+        &self,
+        transport: &T,
+        authority: &str,
+    ) -> Result<Vec<ReclaimableAccount>, ClientError>;
+
+    /// Instruction withdrawing the whole balance of `account` to `recipient`.
+    fn reclaim_instruction(
+        &self,
+        account: &ReclaimableAccount,
+        recipient: &str,
+    ) -> Result<Instruction, ClientError>;
+}
+
+/// Initialized durable nonce accounts.
+pub struct NonceSource;
+
+impl ReclaimableSource for NonceSource {
+    const KIND: ReclaimKind = ReclaimKind::Nonce;
+
+    async fn scan<T: RpcTransport>(
+        &self,
+        transport: &T,
+        authority: &str,
+    ) -> Result<Vec<ReclaimableAccount>, ClientError> {
+        let filters = json!([
+            { "dataSize": NONCE_ACCOUNT_LEN },
+            memcmp(0, &NONCE_INITIALIZED),
+            memcmp(NONCE_AUTHORITY_OFFSET, &decode_pubkey(authority)?),
+        ]);
+        let system = encode_pubkey(&SYSTEM_PROGRAM_ID);
+        let accounts = get_program_accounts_sliced(transport, &system, filters, 0).await?;
+        Ok(accounts
+            .into_iter()
+            .map(|(address, lamports, _)| ReclaimableAccount {
+                address,
+                kind: Self::KIND,
+                lamports,
+                authority: authority.to_string(),
+                cooling_down: false,
+            })
+            .collect())
+    }
+
+    fn reclaim_instruction(
+        &self,
+        account: &ReclaimableAccount,
+        recipient: &str,
+    ) -> Result<Instruction, ClientError> {
+        let mut data = WITHDRAW_NONCE_TAG.to_le_bytes().to_vec();
+        data.extend_from_slice(&account.lamports.to_le_bytes());
+        Ok(Instruction {
+            program_id: SYSTEM_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(decode_pubkey(&account.address)?, false),
+                AccountMeta::new(decode_pubkey(recipient)?, false),
+                AccountMeta::new_readonly(decode_pubkey(SYSVAR_RECENT_BLOCKHASHES)?, false),
+                AccountMeta::new_readonly(decode_pubkey(SYSVAR_RENT)?, false),
+                AccountMeta::new_readonly(decode_pubkey(&account.authority)?, true),
+            ],
+            data,
+        })
+    }
+}
+
+/// Undelegated or deactivated stake accounts without a lockup in force.
+pub struct StakeSource;
+
+impl ReclaimableSource for StakeSource {
+    const KIND: ReclaimKind = ReclaimKind::Stake;
+
+    async fn scan<T: RpcTransport>(
+        &self,
+        transport: &T,
+        authority: &str,
+    ) -> Result<Vec<ReclaimableAccount>, ClientError> {
+        let filters = json!([
+            { "dataSize": STAKE_ACCOUNT_LEN },
+            memcmp(STAKE_WITHDRAWER_OFFSET, &decode_pubkey(authority)?),
+        ]);
+        let length = STAKE_ACCOUNT_LEN as usize;
+        let accounts = get_program_accounts_sliced(transport, STAKE_PROGRAM, filters, length).await?;
+        let epoch = get_epoch(transport).await?;
+        let history = get_account_data(transport, SYSVAR_STAKE_HISTORY)
+            .await?
+            .ok_or_else(|| ClientError::InvalidResponse("Missing stake history".to_string()))?;
+        let history = StakeHistory::decode(&history)?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, lamports, data)| {
+                let cooling_down = match stake_withdrawal(&data, epoch, &history) {
+                    StakeWithdrawal::Ready => false,
+                    StakeWithdrawal::CoolingDown => true,
+                    StakeWithdrawal::Unavailable => return None,
+                };
+                Some(ReclaimableAccount {
+                    address,
+                    kind: Self::KIND,
+                    lamports,
+                    authority: authority.to_string(),
+                    cooling_down,
+                })
+            })
+            .collect())
+    }
+
+    fn reclaim_instruction(
+        &self,
+        account: &ReclaimableAccount,
+        recipient: &str,
+    ) -> Result<Instruction, ClientError> {
+        let mut data = WITHDRAW_STAKE_TAG.to_le_bytes().to_vec();
+        data.extend_from_slice(&account.lamports.to_le_bytes());
+        Ok(Instruction {
+            program_id: decode_pubkey(STAKE_PROGRAM)?,
+            accounts: vec![
+                AccountMeta::new(decode_pubkey(&account.address)?, false),
+                AccountMeta::new(decode_pubkey(recipient)?, false),
+                AccountMeta::new_readonly(decode_pubkey(SYSVAR_CLOCK)?, false),
+                AccountMeta::new_readonly(decode_pubkey(SYSVAR_STAKE_HISTORY)?, false),
+                AccountMeta::new_readonly(decode_pubkey(&account.authority)?, true),
+            ],
+            data,
+        })
+    }
+}
+
+/// Cluster-wide stake of one epoch in the `StakeHistory` sysvar.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StakeHistoryEntry {
+    pub effective: u64,
+    pub activating: u64,
+    pub deactivating: u64,
+}
+
+/// The `StakeHistory` sysvar, newest epoch first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StakeHistory(pub Vec<(u64, StakeHistoryEntry)>);
+
+impl StakeHistory {
+    /// Decode the sysvar's bincode `Vec<(Epoch, StakeHistoryEntry)>`.
+    pub fn decode(data: &[u8]) -> Result<Self, ClientError> {
+        let invalid = || ClientError::InvalidResponse("Malformed stake history".to_string());
+        let read_u64 = |offset: usize| {
+            data.get(offset..offset + 8).and_then(|b| b.try_into().ok()).map(u64::from_le_bytes)
+        };
+        let len = read_u64(0).ok_or_else(invalid)?;
+        (0..len as usize)
+            .map(|i| {
+                let at = 8 + i * 32;
+                let entry = StakeHistoryEntry {
+                    effective: read_u64(at + 8).ok_or_else(invalid)?,
+                    activating: read_u64(at + 16).ok_or_else(invalid)?,
+                    deactivating: read_u64(at + 24).ok_or_else(invalid)?,
+                };
+                Ok((read_u64(at).ok_or_else(invalid)?, entry))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    pub fn get(&self, epoch: u64) -> Option<&StakeHistoryEntry> {
+        self.0.iter().find(|(e, _)| *e == epoch).map(|(_, entry)| entry)
+    }
+}
+
+/// Whether a stake account's full balance can be withdrawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StakeWithdrawal {
+    /// Never delegated, or no stake left effective
+    Ready,
+    /// Deactivated, but part of the stake is still effective
+    CoolingDown,
+    /// Delegated, locked up or not a stake account
+    Unavailable,
+}
+
+/// Where a raw stake account stands at `epoch`.
+///
+/// Lockups by unix timestamp are treated as in force, since the client has no clock.
+pub fn stake_withdrawal(data: &[u8], epoch: u64, history: &StakeHistory) -> StakeWithdrawal {
+    let read_u64 = |offset: usize| {
+        data.get(offset..offset + 8).and_then(|b| b.try_into().ok()).map(u64::from_le_bytes)
+    };
+    let Some(state) = data.get(..4).and_then(|b| b.try_into().ok()).map(u32::from_le_bytes) else {
+        return StakeWithdrawal::Unavailable;
+    };
+    let unlocked = read_u64(STAKE_LOCKUP_TIMESTAMP_OFFSET) == Some(0)
+        && read_u64(STAKE_LOCKUP_EPOCH_OFFSET).is_some_and(|lockup| lockup <= epoch);
+    if !unlocked {
+        return StakeWithdrawal::Unavailable;
+    }
+    match state {
+        STAKE_INITIALIZED => StakeWithdrawal::Ready,
+        STAKE_DELEGATED => {
+            let (Some(stake), Some(activation), Some(deactivation)) = (
+                read_u64(STAKE_AMOUNT_OFFSET),
+                read_u64(STAKE_ACTIVATION_EPOCH_OFFSET),
+                read_u64(STAKE_DEACTIVATION_EPOCH_OFFSET),
+            ) else {
+                return StakeWithdrawal::Unavailable;
+            };
+            if deactivation == u64::MAX {
+                StakeWithdrawal::Unavailable
+            } else if activation == deactivation
+                || remaining_effective_stake(stake, deactivation, epoch, history) == 0
+            {
+                StakeWithdrawal::Ready
+            } else {
+                StakeWithdrawal::CoolingDown
+            }
+        }
+        _ => StakeWithdrawal::Unavailable,
+    }
+}
+
+/// Upper bound on the stake still effective at `epoch` of a delegation of `stake`
+/// deactivated at `deactivation`, following the stake program's cooldown.
+///
+/// Assumes the whole stake was effective when deactivated and the slower cooldown
+/// rate, so it never reports zero early.
+fn remaining_effective_stake(stake: u64, deactivation: u64, epoch: u64, history: &StakeHistory) -> u64 {
+    if epoch <= deactivation {
+        return stake;
+    }
+    // Out of the history's reach: the stake program treats the stake as fully deactivated
+    let Some(mut cluster) = history.get(deactivation) else {
+        return 0;
+    };
+    let mut effective = stake;
+    let mut current = deactivation;
+    loop {
+        current += 1;
+        if cluster.deactivating == 0 {
+            break;
+        }
+        let newly_inactive = u128::from(effective) * u128::from(cluster.effective) * COOLDOWN_RATE_PERCENT
+            / (100 * u128::from(cluster.deactivating));
+        effective = effective.saturating_sub((newly_inactive as u64).max(1));
+        if effective == 0 || current >= epoch {
+            break;
+        }
+        match history.get(current) {
+            Some(next) => cluster = next,
+            None => break,
+        }
+    }
+    effective
+}
+
+/// Run every source for `authority`.
+pub async fn scan_reclaimable<T: RpcTransport>(
+    transport: &T,
+    authority: &str,
+) -> Result<Vec<ReclaimableAccount>, ClientError> {
+    let mut accounts = NonceSource.scan(transport, authority).await?;
+    accounts.extend(StakeSource.scan(transport, authority).await?);
+    Ok(accounts)
+}
+
+/// Withdraw instruction for `account`, from the source that found it.
+pub fn build_reclaim_instruction(
+    account: &ReclaimableAccount,
+    recipient: &str,
+) -> Result<Instruction, ClientError> {
+    match account.kind {
+        ReclaimKind::Nonce => NonceSource.reclaim_instruction(account, recipient),
+        ReclaimKind::Stake => StakeSource.reclaim_instruction(account, recipient),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::pubkey::Pubkey;
+
+    fn account(kind: ReclaimKind) -> ReclaimableAccount {
+        ReclaimableAccount {
+            address: encode_pubkey(&[2u8; 32]),
+            kind,
+            lamports: 1_447_680,
+            authority: encode_pubkey(&[7u8; 32]),
+            cooling_down: false,
+        }
+    }
+
+    type Flattened = (Pubkey, Vec<(Pubkey, bool, bool)>, Vec<u8>);
+
+    fn to_sdk(ix: &Instruction) -> Flattened {
+        let metas = ix
+            .accounts
+            .iter()
+            .map(|m| (Pubkey::new_from_array(m.pubkey), m.is_signer, m.is_writable))
+            .collect();
+        (Pubkey::new_from_array(ix.program_id), metas, ix.data.clone())
+    }
+
+    fn from_sdk(ix: solana_program::instruction::Instruction) -> Flattened {
+        let metas = ix.accounts.iter().map(|m| (m.pubkey, m.is_signer, m.is_writable)).collect();
+        (ix.program_id, metas, ix.data)
+    }
+
+    #[test]
+    fn nonce_withdraw_matches_system_program() {
+        let nonce = account(ReclaimKind::Nonce);
+        let ix = build_reclaim_instruction(&nonce, &encode_pubkey(&[7u8; 32])).unwrap();
+        let key = |b: u8| Pubkey::new_from_array([b; 32]);
+        let expected = solana_program::system_instruction::withdraw_nonce_account(
+            &key(2),
+            &key(7),
+            &key(7),
+            nonce.lamports,
+        );
+        assert_eq!(to_sdk(&ix), from_sdk(expected));
+    }
+
+    #[test]
+    fn stake_withdraw_matches_stake_program() {
+        let stake = account(ReclaimKind::Stake);
+        let ix = build_reclaim_instruction(&stake, &encode_pubkey(&[7u8; 32])).unwrap();
+        let key = |b: u8| Pubkey::new_from_array([b; 32]);
+        let expected =
+            solana_program::stake::instruction::withdraw(&key(2), &key(7), &key(7), stake.lamports, None);
+        assert_eq!(to_sdk(&ix), from_sdk(expected));
+    }
+
+    fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn delegated(stake: u64, activation: u64, deactivation: u64) -> Vec<u8> {
+        let mut data = vec![0u8; STAKE_ACCOUNT_LEN as usize];
+        data[..4].copy_from_slice(&STAKE_DELEGATED.to_le_bytes());
+        write_u64(&mut data, STAKE_AMOUNT_OFFSET, stake);
+        write_u64(&mut data, STAKE_ACTIVATION_EPOCH_OFFSET, activation);
+        write_u64(&mut data, STAKE_DEACTIVATION_EPOCH_OFFSET, deactivation);
+        data
+    }
+
+    fn cluster_history(epochs: std::ops::RangeInclusive<u64>, entry: StakeHistoryEntry) -> StakeHistory {
+        StakeHistory(epochs.rev().map(|epoch| (epoch, entry)).collect())
+    }
+
+    #[test]
+    fn stake_history_decodes_the_sysvar_layout() {
+        let mut data = 2u64.to_le_bytes().to_vec();
+        for (epoch, effective) in [(9u64, 100u64), (8, 90)] {
+            for value in [epoch, effective, 5, 7] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        let history = StakeHistory::decode(&data).unwrap();
+        let entry = StakeHistoryEntry { effective: 90, activating: 5, deactivating: 7 };
+        assert_eq!(history.get(8), Some(&entry));
+        assert_eq!(history.get(7), None);
+        assert!(StakeHistory::decode(&data[..40]).is_err());
+    }
+
+    #[test]
+    fn only_inactive_unlocked_stake_is_withdrawable() {
+        let history = StakeHistory::default();
+        let mut data = vec![0u8; STAKE_ACCOUNT_LEN as usize];
+        data[..4].copy_from_slice(&STAKE_INITIALIZED.to_le_bytes());
+        assert_eq!(stake_withdrawal(&data, 500, &history), StakeWithdrawal::Ready);
+
+        let active = delegated(1_000, 10, u64::MAX);
+        assert_eq!(stake_withdrawal(&active, 500, &history), StakeWithdrawal::Unavailable);
+        // Deactivating this epoch, and deactivated before the history reaches back
+        assert_eq!(stake_withdrawal(&delegated(1_000, 10, 500), 500, &history), StakeWithdrawal::CoolingDown);
+        assert_eq!(stake_withdrawal(&delegated(1_000, 10, 499), 500, &history), StakeWithdrawal::Ready);
+        // Deactivated in the epoch it was delegated: never effective
+        assert_eq!(stake_withdrawal(&delegated(1_000, 500, 500), 500, &history), StakeWithdrawal::Ready);
+
+        data[STAKE_LOCKUP_EPOCH_OFFSET..STAKE_LOCKUP_EPOCH_OFFSET + 8].copy_from_slice(&600u64.to_le_bytes());
+        assert_eq!(stake_withdrawal(&data, 500, &history), StakeWithdrawal::Unavailable);
+        assert_eq!(stake_withdrawal(&data[..40], 700, &history), StakeWithdrawal::Unavailable);
+    }
+
+    #[test]
+    fn stake_cools_down_over_the_epochs_the_history_records() {
+        // Cluster deactivating as much as is effective: 9% of the stake goes inactive per epoch
+        let cluster = StakeHistoryEntry { effective: 1_000_000, activating: 0, deactivating: 1_000_000 };
+        let stake = delegated(1_000_000, 10, 499);
+
+        let history = cluster_history(499..=500, cluster);
+        assert_eq!(remaining_effective_stake(1_000_000, 499, 500, &history), 910_000);
+        assert_eq!(remaining_effective_stake(1_000_000, 499, 501, &history), 828_100);
+        assert_eq!(stake_withdrawal(&stake, 501, &history), StakeWithdrawal::CoolingDown);
+
+        let history = cluster_history(499..=700, cluster);
+        assert_eq!(stake_withdrawal(&stake, 510, &history), StakeWithdrawal::CoolingDown);
+        assert_eq!(stake_withdrawal(&stake, 700, &history), StakeWithdrawal::Ready);
+    }
+}
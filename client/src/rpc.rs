@@ -0,0 +1,311 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+
+use crate::error::ClientError;
+use crate::transport::RpcTransport;
+
+pub const SPL_TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+/// Address (base58), lamports and raw data of an account.
+pub type KeyedAccount = (String, u64, Vec<u8>);
+
+/// SPL token accounts of `owner` (`base64`).
+pub async fn get_token_accounts_by_owner<T: RpcTransport>(
+    transport: &T,
+    owner: &str,
+) -> Result<Vec<KeyedAccount>, ClientError> {
+    let params = json!([
+        owner,
+        { "programId": SPL_TOKEN_PROGRAM },
+        { "encoding": "base64", "commitment": "confirmed" }
+    ]);
+    let result = transport.request("getTokenAccountsByOwner", params).await?;
+    decode_keyed_accounts(&result["value"])
+}
+
+/// Token accounts of `owner` holding `mint` (`base64`).
+pub async fn get_token_accounts_by_mint<T: RpcTransport>(
+    transport: &T,
+    owner: &str,
+    mint: &str,
+) -> Result<Vec<KeyedAccount>, ClientError> {
+    let params = json!([owner, { "mint": mint }, { "encoding": "base64", "commitment": "confirmed" }]);
+    let result = transport.request("getTokenAccountsByOwner", params).await?;
+    decode_keyed_accounts(&result["value"])
+}
+
+/// Accounts owned by `program_id` matching `filters` (`base64`).
+pub async fn get_program_accounts<T: RpcTransport>(
+    transport: &T,
+    program_id: &str,
+    filters: Value,
+) -> Result<Vec<KeyedAccount>, ClientError> {
+    let params = json!([program_id, { "encoding": "base64", "filters": filters, "commitment": "confirmed" }]);
+    let result = transport.request("getProgramAccounts", params).await?;
+    decode_keyed_accounts(&result)
+}
+
+/// Like [`get_program_accounts`], but only the first `length` data bytes of each account are
+/// downloaded.
+pub async fn get_program_accounts_sliced<T: RpcTransport>(
+    transport: &T,
+    program_id: &str,
+    filters: Value,
+    length: usize,
+) -> Result<Vec<KeyedAccount>, ClientError> {
+    let params = json!([
+        program_id,
+        {
+            "encoding": "base64",
+            "filters": filters,
+            "dataSlice": { "offset": 0, "length": length },
+            "commitment": "confirmed"
+        }
+    ]);
+    let result = transport.request("getProgramAccounts", params).await?;
+    decode_keyed_accounts(&result)
+}
+
+/// Decode a `[{ pubkey, account }]` list of `base64` accounts, failing on any malformed entry.
+pub fn decode_keyed_accounts(accounts: &Value) -> Result<Vec<KeyedAccount>, ClientError> {
+    let invalid = |what: &str| ClientError::InvalidResponse(what.to_string());
+    accounts
+        .as_array()
+        .ok_or_else(|| invalid("Missing account list"))?
+        .iter()
+        .map(|acc| {
+            let address = acc["pubkey"].as_str().ok_or_else(|| invalid("Missing account pubkey"))?;
+            let (lamports, data) = decode_account(&acc["account"])?;
+            Ok((address.to_string(), lamports, data))
+        })
+        .collect()
+}
+
+/// Lamports and raw data of a `base64`-encoded account object.
+pub fn decode_account(account: &Value) -> Result<(u64, Vec<u8>), ClientError> {
+    let invalid = |what: &str| ClientError::InvalidResponse(what.to_string());
+    let lamports = account["lamports"].as_u64().ok_or_else(|| invalid("Missing account lamports"))?;
+    let encoded = match (account["data"][0].as_str(), account["data"][1].as_str()) {
+        (Some(data), Some("base64")) => data,
+        _ => return Err(invalid("Account data is not base64")),
+    };
+    let data = STANDARD
+        .decode(encoded)
+        .map_err(|e| ClientError::InvalidResponse(format!("Invalid account data: {}", e)))?;
+    Ok((lamports, data))
+}
+
+/// `getProgramAccounts` filter matching `bytes` at `offset`.
+pub fn memcmp(offset: u64, bytes: &[u8]) -> Value {
+    json!({ "memcmp": { "offset": offset, "bytes": bs58::encode(bytes).into_string() } })
+}
+
+pub async fn get_latest_blockhash<T: RpcTransport>(transport: &T) -> Result<String, ClientError> {
+    let result = transport.request("getLatestBlockhash", json!([])).await?;
+    result["value"]["blockhash"]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| ClientError::InvalidResponse("Missing blockhash".to_string()))
+}
+
+/// Current epoch of the cluster.
+pub async fn get_epoch<T: RpcTransport>(transport: &T) -> Result<u64, ClientError> {
+    let result = transport.request("getEpochInfo", json!([])).await?;
+    result["epoch"]
+        .as_u64()
+        .ok_or_else(|| ClientError::InvalidResponse("Missing epoch".to_string()))
+}
+
+pub async fn get_balance<T: RpcTransport>(transport: &T, pubkey: &str) -> Result<u64, ClientError> {
+    let result = transport.request("getBalance", json!([pubkey])).await?;
+    result["value"]
+        .as_u64()
+        .ok_or_else(|| ClientError::InvalidResponse("Invalid balance".to_string()))
+}
+
+/// Raw data of an account, or `None` if it doesn't exist.
+pub async fn get_account_data<T: RpcTransport>(
+    transport: &T,
+    pubkey: &str,
+) -> Result<Option<Vec<u8>>, ClientError> {
+    let params = json!([pubkey, { "encoding": "base64", "commitment": "confirmed" }]);
+    let result = transport.request("getAccountInfo", params).await?;
+    let value = &result["value"];
+    if value.is_null() {
+        return Ok(None);
+    }
+    let data = value["data"][0]
+        .as_str()
+        .ok_or_else(|| ClientError::InvalidResponse("Missing account data".to_string()))?;
+    STANDARD
+        .decode(data)
+        .map(Some)
+        .map_err(|e| ClientError::InvalidResponse(format!("Invalid account data: {}", e)))
+}
+
+/// Lamports and raw data of each of `pubkeys`, `None` for accounts that don't exist.
+pub async fn get_multiple_accounts<T: RpcTransport>(
+    transport: &T,
+    pubkeys: &[String],
+) -> Result<Vec<Option<(u64, Vec<u8>)>>, ClientError> {
+    // The RPC accepts at most 100 keys per call; every chunk goes out in one batch
+    let requests = pubkeys
+        .chunks(100)
+        .map(|chunk| {
+            let params = json!([chunk, { "encoding": "base64", "commitment": "confirmed" }]);
+            ("getMultipleAccounts", params)
+        })
+        .collect();
+    let mut accounts = Vec::with_capacity(pubkeys.len());
+    for result in transport.request_batch(requests).await? {
+        let values = result["value"]
+            .as_array()
+            .ok_or_else(|| ClientError::InvalidResponse("Missing accounts".to_string()))?;
+        for value in values {
+            match value.is_null() {
+                true => accounts.push(None),
+                false => accounts.push(Some(decode_account(value)?)),
+            }
+        }
+    }
+    Ok(accounts)
+}
+
+/// Fetch a confirmed transaction with its meta (logs, balances, error).
+pub async fn get_transaction<T: RpcTransport>(
+    transport: &T,
+    signature: &str,
+) -> Result<Value, ClientError> {
+    let params = json!([
+        signature,
+        { "encoding": "json", "maxSupportedTransactionVersion": 0, "commitment": "confirmed" }
+    ]);
+    transport.request("getTransaction", params).await
+}
+
+/// Several transactions in one batched round trip, `null` for unknown signatures.
+pub async fn get_transactions<T: RpcTransport>(
+    transport: &T,
+    signatures: &[String],
+) -> Result<Vec<Value>, ClientError> {
+    let requests = signatures
+        .iter()
+        .map(|signature| {
+            let params = json!([
+                signature,
+                { "encoding": "json", "maxSupportedTransactionVersion": 0, "commitment": "confirmed" }
+            ]);
+            ("getTransaction", params)
+        })
+        .collect();
+    transport.request_batch(requests).await
+}
+
+/// Submit a base64-encoded, fully signed transaction and return its signature.
+pub async fn send_transaction<T: RpcTransport>(
+    transport: &T,
+    tx_base64: &str,
+) -> Result<String, ClientError> {
+    let params = json!([tx_base64, { "encoding": "base64", "preflightCommitment": "confirmed" }]);
+    transport
+        .request("sendTransaction", params)
+        .await?
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| ClientError::InvalidResponse("Missing signature".to_string()))
+}
+
+/// Look up the status of a single signature; `Null` until the cluster has seen it.
+pub async fn get_signature_status<T: RpcTransport>(
+    transport: &T,
+    signature: &str,
+) -> Result<Value, ClientError> {
+    let statuses = get_signature_statuses(transport, &[signature.to_string()]).await?;
+    Ok(statuses.into_iter().next().unwrap_or(Value::Null))
+}
+
+/// Statuses of `signatures` in order, `null` for unknown ones.
+pub async fn get_signature_statuses<T: RpcTransport>(
+    transport: &T,
+    signatures: &[String],
+) -> Result<Vec<Value>, ClientError> {
+    // The RPC accepts at most 256 signatures per call; every chunk goes out in one batch
+    let requests = signatures
+        .chunks(256)
+        .map(|chunk| ("getSignatureStatuses", json!([chunk, { "searchTransactionHistory": false }])))
+        .collect();
+    let mut statuses = Vec::with_capacity(signatures.len());
+    for mut result in transport.request_batch(requests).await? {
+        match result["value"].take() {
+            Value::Array(values) => statuses.extend(values),
+            _ => return Err(ClientError::InvalidResponse("Missing statuses".to_string())),
+        }
+    }
+    Ok(statuses)
+}
+
+/// Most recent signatures involving `address`, newest first.
+pub async fn get_signatures_for_address<T: RpcTransport>(
+    transport: &T,
+    address: &str,
+    limit: usize,
+) -> Result<Vec<String>, ClientError> {
+    let params = json!([address, { "limit": limit, "commitment": "confirmed" }]);
+    let result = transport.request("getSignaturesForAddress", params).await?;
+    let invalid = |what: &str| ClientError::InvalidResponse(what.to_string());
+    result
+        .as_array()
+        .ok_or_else(|| invalid("Missing signatures"))?
+        .iter()
+        .filter(|e| e["err"].is_null())
+        .map(|e| e["signature"].as_str().map(|s| s.to_string()).ok_or_else(|| invalid("Missing signature")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    /// Answers every request with the same result.
+    struct Canned(Value);
+
+    impl RpcTransport for Canned {
+        async fn request(&self, _method: &str, _params: Value) -> Result<Value, ClientError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_accounts() {
+        let keys = ["a".to_string(), "b".to_string()];
+        let account = json!({ "lamports": 5, "data": ["AQI=", "base64"] });
+        let valid = Canned(json!({ "value": [account, null] }));
+        assert_eq!(block_on(get_multiple_accounts(&valid, &keys)), Ok(vec![Some((5, vec![1, 2])), None]));
+
+        for account in [
+            json!({ "data": ["AQI=", "base64"] }),
+            json!({ "lamports": 5, "data": ["AQI=", "base58"] }),
+            json!({ "lamports": 5, "data": ["not base64!", "base64"] }),
+        ] {
+            let malformed = Canned(json!({ "value": [account, null] }));
+            let result = block_on(get_multiple_accounts(&malformed, &keys));
+            assert!(matches!(result, Err(ClientError::InvalidResponse(_))), "{:?}", result);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_signature_lists() {
+        let listed = Canned(json!([
+            { "signature": "ok", "err": null },
+            { "signature": "failed", "err": { "InstructionError": [0, { "Custom": 1 }] } }
+        ]));
+        assert_eq!(block_on(get_signatures_for_address(&listed, "addr", 10)), Ok(vec!["ok".to_string()]));
+
+        for result in [json!({ "value": [] }), json!([{ "err": null }])] {
+            let signatures = block_on(get_signatures_for_address(&Canned(result), "addr", 10));
+            assert!(matches!(signatures, Err(ClientError::InvalidResponse(_))), "{:?}", signatures);
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use zera_shared::token::{AccountState, DecodeError, TokenAccount, ACCOUNT_LEN};
+
+use crate::error::ClientError;
+use crate::pubkey::{decode_pubkey, encode_pubkey};
+use crate::rpc::{
+    get_program_accounts_sliced, get_token_accounts_by_owner, memcmp, KeyedAccount, SPL_TOKEN_PROGRAM,
+};
+use crate::transport::RpcTransport;
+
+/// Dust threshold: accounts with balance at or below this are considered closeable.
+pub const DUST_THRESHOLD: u64 = 0;
+
+pub const NATIVE_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// SPL token account layout: mint, owner, amount, delegate, then the one-byte state.
+const OWNER_OFFSET: u64 = 32;
+const STATE_OFFSET: usize = 108;
+const CLOSE_AUTHORITY_OFFSET: u64 = 129;
+/// Bytes fetched per account by the sliced scan: everything up to and including the state.
+const SLICE_LEN: usize = STATE_OFFSET + 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TokenAccountInfo {
+    /// Token account address (base58)
+    pub address: String,
+    /// Mint address (base58)
+    pub mint: String,
+    /// Token balance (raw u64 as string from RPC)
+    pub amount: u64,
+    /// Lamports held by the account: rent, plus the wrapped balance for native SOL
+    pub lamports: u64,
+    /// Wrapped SOL (native mint) account, closeable with a balance since closing unwraps it
+    #[serde(default)]
+    pub is_native: bool,
+    /// Close authority other than the owner (base58), e.g. the janitor PDA after `Authorize`
+    #[serde(default)]
+    pub close_authority: Option<String>,
+    /// Frozen by the mint's freeze authority, which blocks closing
+    #[serde(default)]
+    pub frozen: bool,
+}
+
+impl TokenAccountInfo {
+    /// Wrapped SOL principal returned fee-free on close, in lamports; 0 for other mints.
+    pub fn wrapped_lamports(&self) -> u64 {
+        if self.is_native {
+            self.amount
+        } else {
+            0
+        }
+    }
+
+    /// Rent released by closing this account, the part the protocol fee applies to.
+    pub fn rent_lamports(&self) -> u64 {
+        self.lamports.saturating_sub(self.wrapped_lamports())
+    }
+
+    /// Estimated rent reclaimable from closing this account, in SOL.
+    pub fn rent_sol(&self) -> f64 {
+        self.rent_lamports() as f64 / 1_000_000_000.0
+    }
+
+    /// Wrapped SOL balance unwrapped by closing this account.
+    pub fn wrapped_sol(&self) -> f64 {
+        self.wrapped_lamports() as f64 / 1_000_000_000.0
+    }
+
+    /// Truncated mint for display.
+    pub fn mint_short(&self) -> String {
+        if self.mint.len() > 8 {
+            format!("{}...{}", &self.mint[..4], &self.mint[self.mint.len() - 4..])
+        } else {
+            self.mint.clone()
+        }
+    }
+
+    /// Empty (or wrapped SOL) and still closeable by its owner; delegated accounts are left
+    /// to `CrankClean`.
+    // `DUST_THRESHOLD` is a tunable; while it is 0 this is an equality check
+    #[allow(clippy::absurd_extreme_comparisons)]
+    pub fn is_closeable(&self) -> bool {
+        (self.amount <= DUST_THRESHOLD || self.is_native) && self.close_authority.is_none() && !self.frozen
+    }
+}
+
+/// Account info of a decoded token account. A close authority equal to the owner counts as none.
+pub fn token_account_info(address: String, lamports: u64, account: &TokenAccount) -> TokenAccountInfo {
+    TokenAccountInfo {
+        address,
+        mint: encode_pubkey(&account.mint),
+        amount: account.amount,
+        lamports,
+        is_native: account.is_native.is_some(),
+        close_authority: account
+            .close_authority
+            .filter(|authority| *authority != account.owner)
+            .map(|authority| encode_pubkey(&authority)),
+        frozen: account.state == AccountState::Frozen,
+    }
+}
+
+/// Decode raw token accounts, failing on the first one that doesn't match the layout.
+pub fn decode_token_accounts(accounts: Vec<KeyedAccount>) -> Result<Vec<TokenAccountInfo>, ClientError> {
+    accounts
+        .into_iter()
+        .map(|(address, lamports, data)| {
+            let account = unpack_initialized(&address, &data)?;
+            Ok(token_account_info(address, lamports, &account))
+        })
+        .collect()
+}
+
+/// Decode a token account the RPC returned for an owner, which can't be uninitialized.
+fn unpack_initialized(address: &str, data: &[u8]) -> Result<TokenAccount, ClientError> {
+    match TokenAccount::unpack(data) {
+        Ok(account) if account.state == AccountState::Uninitialized => {
+            Err(ClientError::InvalidAccount(address.to_string(), DecodeError::InvalidState(0)))
+        }
+        Ok(account) => Ok(account),
+        Err(e) => Err(ClientError::InvalidAccount(address.to_string(), e)),
+    }
+}
+
+/// Fetch every SPL token account owned by `owner` and keep the closeable ones.
+pub async fn scan_closeable<T: RpcTransport>(
+    transport: &T,
+    owner: &str,
+) -> Result<Vec<TokenAccountInfo>, ClientError> {
+    let accounts = get_token_accounts_by_owner(transport, owner).await?;
+    Ok(decode_token_accounts(accounts)?
+        .into_iter()
+        .filter(TokenAccountInfo::is_closeable)
+        .collect())
+}
+
+/// `getProgramAccounts` filters for `owner`'s token accounts.
+pub fn owner_filters(owner: &[u8; 32]) -> Value {
+    json!([{ "dataSize": ACCOUNT_LEN }, memcmp(OWNER_OFFSET, owner)])
+}
+
+/// [`owner_filters`] narrowed to the accounts `owner` can close itself: those without a close
+/// authority, and those whose close authority is `owner`. The node can only match one of the two
+/// per query.
+fn owner_closeable_filters(owner: &[u8; 32]) -> [Value; 2] {
+    let with_close_authority = |option: &[u8]| {
+        let close_authority = memcmp(CLOSE_AUTHORITY_OFFSET, option);
+        json!([{ "dataSize": ACCOUNT_LEN }, memcmp(OWNER_OFFSET, owner), close_authority])
+    };
+    let mut owner_authority = vec![1, 0, 0, 0];
+    owner_authority.extend_from_slice(owner);
+    [with_close_authority(&[0, 0, 0, 0]), with_close_authority(&owner_authority)]
+}
+
+/// Decode the leading [`SLICE_LEN`] bytes of a token account.
+///
+/// The slice ends before `is_native` and the close authority: native accounts are recognised
+/// by their mint, whose `amount` is the wrapped balance, and the scan only asks the node for
+/// accounts without a close authority other than the owner.
+pub fn decode_token_account_slice(
+    address: String,
+    lamports: u64,
+    data: &[u8],
+) -> Result<TokenAccountInfo, ClientError> {
+    if data.len() != SLICE_LEN {
+        let e = DecodeError::TooShort { expected: SLICE_LEN, actual: data.len() };
+        return Err(ClientError::InvalidAccount(address, e));
+    }
+    let mut padded = [0u8; ACCOUNT_LEN];
+    padded[..SLICE_LEN].copy_from_slice(data);
+    let account = unpack_initialized(&address, &padded)?;
+    let mut info = token_account_info(address, lamports, &account);
+    info.is_native = info.mint == NATIVE_MINT;
+    Ok(info)
+}
+
+/// Like [`scan_closeable`], but filtered on the RPC node with `getProgramAccounts` and only the
+/// first [`SLICE_LEN`] bytes of each account downloaded.
+pub async fn scan_closeable_sliced<T: RpcTransport>(
+    transport: &T,
+    owner: &str,
+) -> Result<Vec<TokenAccountInfo>, ClientError> {
+    let mut accounts = Vec::new();
+    for filters in owner_closeable_filters(&decode_pubkey(owner)?) {
+        accounts.extend(get_program_accounts_sliced(transport, SPL_TOKEN_PROGRAM, filters, SLICE_LEN).await?);
+    }
+    let accounts = accounts
+        .into_iter()
+        .map(|(address, lamports, data)| decode_token_account_slice(address, lamports, &data))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(accounts.into_iter().filter(TokenAccountInfo::is_closeable).collect())
+}
+
+/// How a live update changed the list of closeable accounts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountChange {
+    Added,
+    Updated,
+    /// Removed from this index; later accounts moved down by one.
+    Removed(usize),
+    Unchanged,
+}
+
+/// Apply a change of `address` seen on chain to the closeable `accounts` of a scan.
+///
+/// Accounts that were closed (no lamports, no data) or stopped being closeable leave the list;
+/// newly closeable ones are appended so the indices of the others stay put.
+pub fn apply_account_update(
+    accounts: &mut Vec<TokenAccountInfo>,
+    address: &str,
+    lamports: u64,
+    data: &[u8],
+) -> AccountChange {
+    let updated = match TokenAccount::unpack(data) {
+        Ok(account) if account.state != AccountState::Uninitialized => {
+            Some(token_account_info(address.to_string(), lamports, &account))
+                .filter(TokenAccountInfo::is_closeable)
+        }
+        _ => None,
+    };
+    match (accounts.iter().position(|a| a.address == address), updated) {
+        (Some(index), Some(info)) if accounts[index] == info => AccountChange::Unchanged,
+        (Some(index), Some(info)) => {
+            accounts[index] = info;
+            AccountChange::Updated
+        }
+        (Some(index), None) => {
+            accounts.remove(index);
+            AccountChange::Removed(index)
+        }
+        (None, Some(info)) => {
+            accounts.push(info);
+            AccountChange::Added
+        }
+        (None, None) => AccountChange::Unchanged,
+    }
+}
+
+/// Group `accounts` by mint (base58), keeping each group in scan order.
+pub fn group_by_mint(accounts: &[TokenAccountInfo]) -> BTreeMap<String, Vec<TokenAccountInfo>> {
+    let mut groups: BTreeMap<String, Vec<TokenAccountInfo>> = BTreeMap::new();
+    for account in accounts {
+        groups.entry(account.mint.clone()).or_default().push(account.clone());
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use futures::executor::block_on;
+
+    use super::*;
+
+    fn slice(mint: &[u8; 32], amount: u64, state: u8) -> Vec<u8> {
+        let mut data = vec![0u8; SLICE_LEN];
+        data[..32].copy_from_slice(mint);
+        data[32..64].copy_from_slice(&[9u8; 32]);
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data[STATE_OFFSET] = state;
+        data
+    }
+
+    #[test]
+    fn decodes_sliced_token_accounts() {
+        let decode = |data: &[u8]| decode_token_account_slice("acc".to_string(), 2_039_280, data);
+        let account = decode(&slice(&[3u8; 32], 0, 1)).unwrap();
+        assert_eq!(account.mint, encode_pubkey(&[3u8; 32]));
+        assert_eq!(account.amount, 0);
+        assert!(!account.is_native);
+        assert!(account.is_closeable());
+
+        assert!(!decode(&slice(&[3u8; 32], 5, 1)).unwrap().is_closeable());
+
+        // Frozen accounts can't be closed; anything else off-layout is an error, not an empty account
+        let frozen = decode(&slice(&[3u8; 32], 0, 2)).unwrap();
+        assert!(frozen.frozen && !frozen.is_closeable());
+        let invalid = |e| Err(ClientError::InvalidAccount("acc".to_string(), e));
+        assert_eq!(decode(&slice(&[3u8; 32], 0, 0)), invalid(DecodeError::InvalidState(0)));
+        assert_eq!(decode(&slice(&[3u8; 32], 0, 7)), invalid(DecodeError::InvalidState(7)));
+        assert_eq!(decode(&[0u8; 64]), invalid(DecodeError::TooShort { expected: SLICE_LEN, actual: 64 }));
+    }
+
+    #[test]
+    fn closes_balances_at_or_below_dust_threshold() {
+        let account = |amount| TokenAccountInfo {
+            address: "acc".to_string(),
+            mint: encode_pubkey(&[3u8; 32]),
+            amount,
+            lamports: 2_039_280,
+            is_native: false,
+            close_authority: None,
+            frozen: false,
+        };
+        assert!(account(DUST_THRESHOLD).is_closeable());
+        assert!(!account(DUST_THRESHOLD + 1).is_closeable());
+    }
+
+    #[test]
+    fn recognises_wrapped_sol_by_mint() {
+        let native_mint = decode_pubkey(NATIVE_MINT).unwrap();
+        let data = slice(&native_mint, 1_000_000, 1);
+        let account = decode_token_account_slice("acc".to_string(), 3_039_280, &data).unwrap();
+        assert!(account.is_native && account.is_closeable());
+        assert_eq!(account.wrapped_lamports(), 1_000_000);
+        assert_eq!(account.rent_lamports(), 2_039_280);
+    }
+
+    #[test]
+    fn decodes_full_accounts_with_hard_errors() {
+        let mut data = vec![0u8; ACCOUNT_LEN];
+        data[..SLICE_LEN].copy_from_slice(&slice(&[3u8; 32], 0, 1));
+        // Close authority set to the owner itself
+        data[129] = 1;
+        data[133..165].copy_from_slice(&[9u8; 32]);
+        let accounts = decode_token_accounts(vec![("acc".to_string(), 2_039_280, data.clone())]).unwrap();
+        assert_eq!(accounts[0].close_authority, None);
+        assert!(accounts[0].is_closeable());
+
+        data[129] = 5;
+        let err = decode_token_accounts(vec![("bad".to_string(), 2_039_280, data)]).unwrap_err();
+        assert_eq!(err, ClientError::InvalidAccount("bad".to_string(), DecodeError::InvalidOption(5)));
+    }
+
+    #[test]
+    fn applies_live_updates() {
+        let full = |amount: u64| {
+            let mut data = vec![0u8; ACCOUNT_LEN];
+            data[..SLICE_LEN].copy_from_slice(&slice(&[3u8; 32], amount, 1));
+            data
+        };
+        let mut accounts = Vec::new();
+        assert_eq!(apply_account_update(&mut accounts, "a", 2_039_280, &full(0)), AccountChange::Added);
+        assert_eq!(apply_account_update(&mut accounts, "b", 2_039_280, &full(0)), AccountChange::Added);
+        assert_eq!(apply_account_update(&mut accounts, "b", 2_039_280, &full(0)), AccountChange::Unchanged);
+        assert_eq!(apply_account_update(&mut accounts, "b", 3_000_000, &full(0)), AccountChange::Updated);
+        assert_eq!(accounts[1].lamports, 3_000_000);
+
+        // Receiving tokens makes an account uncloseable; closing it leaves no data behind
+        assert_eq!(apply_account_update(&mut accounts, "a", 2_039_280, &full(7)), AccountChange::Removed(0));
+        assert_eq!(apply_account_update(&mut accounts, "b", 0, &[]), AccountChange::Removed(0));
+        assert!(accounts.is_empty());
+        assert_eq!(apply_account_update(&mut accounts, "c", 2_039_280, &full(7)), AccountChange::Unchanged);
+    }
+
+    #[test]
+    fn filters_on_owner() {
+        let filters = owner_filters(&[9u8; 32]);
+        assert_eq!(filters[0]["dataSize"], 165);
+        assert_eq!(filters[1]["memcmp"]["offset"], 32);
+        assert_eq!(filters[1]["memcmp"]["bytes"], bs58::encode([9u8; 32]).into_string());
+        assert!(filters.get(2).is_none());
+    }
+
+    /// Serves `getTokenAccountsByOwner` and `getProgramAccounts` over a fixed set of token accounts.
+    struct FakeNode(Vec<KeyedAccount>);
+
+    impl RpcTransport for FakeNode {
+        async fn request(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+            let matches = |data: &[u8], filter: &Value| match (&filter["dataSize"], &filter["memcmp"]) {
+                (Value::Number(size), _) => size.as_u64() == Some(data.len() as u64),
+                (_, memcmp) => {
+                    let offset = memcmp["offset"].as_u64().unwrap() as usize;
+                    let bytes = bs58::decode(memcmp["bytes"].as_str().unwrap()).into_vec().unwrap();
+                    data.get(offset..offset + bytes.len()) == Some(&bytes[..])
+                }
+            };
+            let config = &params[1];
+            let length = config["dataSlice"]["length"].as_u64().map_or(ACCOUNT_LEN, |l| l as usize);
+            let filters = config["filters"].as_array().cloned().unwrap_or_default();
+            let accounts: Vec<Value> = self
+                .0
+                .iter()
+                .filter(|(_, _, data)| filters.iter().all(|filter| matches(data, filter)))
+                .map(|(address, lamports, data)| {
+                    let data = STANDARD.encode(&data[..length]);
+                    let account = json!({ "lamports": lamports, "data": [data, "base64"] });
+                    json!({ "pubkey": address, "account": account })
+                })
+                .collect();
+            match method {
+                "getTokenAccountsByOwner" => Ok(json!({ "value": accounts })),
+                _ => Ok(json!(accounts)),
+            }
+        }
+    }
+
+    #[test]
+    fn sliced_scan_matches_full_scan() {
+        let owner = [9u8; 32];
+        let account = |amount: u64, close_authority: Option<[u8; 32]>| {
+            let mut data = vec![0u8; ACCOUNT_LEN];
+            data[..SLICE_LEN].copy_from_slice(&slice(&[3u8; 32], amount, 1));
+            if let Some(authority) = close_authority {
+                data[129] = 1;
+                data[133..165].copy_from_slice(&authority);
+            }
+            data
+        };
+        let node = FakeNode(vec![
+            ("plain".to_string(), 2_039_280, account(0, None)),
+            ("owner-authority".to_string(), 2_039_280, account(0, Some(owner))),
+            ("janitor-authority".to_string(), 2_039_280, account(0, Some([4u8; 32]))),
+            ("funded".to_string(), 2_039_280, account(5, Some(owner))),
+        ]);
+
+        let owner = encode_pubkey(&owner);
+        let full = block_on(scan_closeable(&node, &owner)).unwrap();
+        let sliced = block_on(scan_closeable_sliced(&node, &owner)).unwrap();
+        let addresses: Vec<&str> = full.iter().map(|a| a.address.as_str()).collect();
+        assert_eq!(addresses, ["plain", "owner-authority"]);
+        assert_eq!(sliced, full);
+    }
+}
@@ -0,0 +1,103 @@
+use serde::Serialize;
+use serde_json::Value;
+use zera_shared::event::JanitorEvent;
+use zera_shared::PROGRAM_ID;
+
+use crate::error::ClientError;
+use crate::events::parse_events;
+use crate::pubkey::encode_pubkey;
+use crate::rpc::{get_signatures_for_address, get_transactions};
+use crate::transport::RpcTransport;
+
+/// Aggregated janitor activity over a window of transactions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CleanStats {
+    pub transactions: u64,
+    pub accounts_closed: u64,
+    pub rent_collected: u64,
+    /// Wrapped SOL returned fee-free from native-mint accounts, included in `payouts`.
+    pub unwrapped: u64,
+    /// Protocol fees, including the referral share.
+    pub fees: u64,
+    pub referral_fees: u64,
+    /// Rewards paid to cranks for closing delegated accounts.
+    pub crank_rewards: u64,
+    pub payouts: u64,
+}
+
+impl CleanStats {
+    pub fn record(&mut self, event: &JanitorEvent) {
+        match event {
+            JanitorEvent::BatchClean {
+                accounts_closed,
+                rent_collected,
+                unwrapped,
+                fee,
+                referral_fee,
+                payout,
+                ..
+            } => {
+                self.transactions += 1;
+                self.accounts_closed += *accounts_closed as u64;
+                self.rent_collected += rent_collected;
+                self.unwrapped += unwrapped;
+                self.fees += fee;
+                self.referral_fees += referral_fee;
+                self.payouts += payout;
+            }
+            JanitorEvent::CrankClean {
+                accounts_closed,
+                rent_collected,
+                fee,
+                crank_reward,
+                payout,
+                ..
+            } => {
+                self.transactions += 1;
+                self.accounts_closed += *accounts_closed as u64;
+                self.rent_collected += rent_collected;
+                self.fees += fee;
+                self.crank_rewards += crank_reward;
+                self.payouts += payout;
+            }
+            JanitorEvent::Consolidate { accounts_closed, rent_collected, fee, payout, .. } => {
+                self.transactions += 1;
+                self.accounts_closed += *accounts_closed as u64;
+                self.rent_collected += rent_collected;
+                self.fees += fee;
+                self.payouts += payout;
+            }
+            JanitorEvent::VaultSwept { .. } => {}
+        }
+    }
+}
+
+/// Log messages of a `getTransaction` result.
+pub fn transaction_logs(tx: &Value) -> Vec<String> {
+    tx["meta"]["logMessages"]
+        .as_array()
+        .map(|logs| {
+            logs.iter()
+                .filter_map(|l| l.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Aggregate the events of the last `limit` successful janitor transactions.
+pub async fn recent_stats<T: RpcTransport>(
+    transport: &T,
+    limit: usize,
+) -> Result<CleanStats, ClientError> {
+    let program_id = encode_pubkey(&PROGRAM_ID);
+    let mut stats = CleanStats::default();
+
+    let signatures = get_signatures_for_address(transport, &program_id, limit).await?;
+    for tx in get_transactions(transport, &signatures).await? {
+        for event in parse_events(&transaction_logs(&tx)) {
+            stats.record(&event);
+        }
+    }
+
+    Ok(stats)
+}
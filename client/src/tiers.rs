@@ -0,0 +1,130 @@
+use zera_shared::config::{FeeTier, ProgramConfig, UserStats};
+use zera_shared::instruction::BatchCleanOptions;
+
+use crate::config::fetch_config;
+use crate::error::ClientError;
+use crate::pda::find_user_stats_pda;
+use crate::pubkey::{decode_pubkey, encode_pubkey};
+use crate::rpc::{get_account_data, get_token_accounts_by_mint};
+use crate::scanner::decode_token_accounts;
+use crate::transport::RpcTransport;
+
+/// Everything that decides which fee tier a user's next `BatchClean` pays.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeeStatus {
+    pub config: ProgramConfig,
+    /// The user's stats PDA and its contents, if it has been initialized.
+    pub user_stats: Option<([u8; 32], UserStats)>,
+    /// The user's largest governance token account and its balance.
+    pub governance_account: Option<([u8; 32], u64)>,
+}
+
+impl FeeStatus {
+    pub fn closed_accounts(&self) -> u64 {
+        self.user_stats.map_or(0, |(_, stats)| stats.closed_accounts)
+    }
+
+    pub fn governance_holding(&self) -> u64 {
+        self.governance_account.map_or(0, |(_, amount)| amount)
+    }
+
+    /// The tier the program will apply, with its index in the config.
+    pub fn tier(&self) -> Option<(usize, FeeTier)> {
+        self.config.fee_tier(self.closed_accounts(), self.governance_holding())
+    }
+
+    /// Fee rate the program will charge, in basis points.
+    pub fn fee_bps(&self) -> u16 {
+        self.config.fee_bps(self.closed_accounts(), self.governance_holding())
+    }
+
+    /// Fill in the stats and governance accounts the program needs to see.
+    pub fn apply(&self, options: &mut BatchCleanOptions) {
+        options.user_stats = self.user_stats.map(|(address, _)| address);
+        options.governance_account = self.governance_account.map(|(address, _)| address);
+    }
+}
+
+/// Fetch the config, `user`'s stats PDA and their governance holding.
+pub async fn fetch_fee_status<T: RpcTransport>(
+    transport: &T,
+    user: &[u8; 32],
+) -> Result<FeeStatus, ClientError> {
+    let config = fetch_config(transport).await?;
+    let user_stats = fetch_user_stats(transport, user).await?;
+    let governance_account = match config.has_governance_mint() {
+        true => find_governance_account(transport, user, &config.governance_mint).await?,
+        false => None,
+    };
+    Ok(FeeStatus { config, user_stats, governance_account })
+}
+
+/// `user`'s stats PDA and its contents, or `None` if it hasn't been initialized.
+pub async fn fetch_user_stats<T: RpcTransport>(
+    transport: &T,
+    user: &[u8; 32],
+) -> Result<Option<([u8; 32], UserStats)>, ClientError> {
+    let address = find_user_stats_pda(user).0;
+    match get_account_data(transport, &encode_pubkey(&address)).await? {
+        None => Ok(None),
+        Some(data) => UserStats::unpack(&data)
+            .map(|stats| Some((address, stats)))
+            .ok_or_else(|| ClientError::InvalidResponse("Invalid user stats account".to_string())),
+    }
+}
+
+/// The `owner`'s token account with the largest balance of `mint`, if any holds some.
+pub async fn find_governance_account<T: RpcTransport>(
+    transport: &T,
+    owner: &[u8; 32],
+    mint: &[u8; 32],
+) -> Result<Option<([u8; 32], u64)>, ClientError> {
+    let accounts = get_token_accounts_by_mint(transport, &encode_pubkey(owner), &encode_pubkey(mint)).await?;
+    decode_token_accounts(accounts)?
+        .into_iter()
+        .filter(|account| account.amount > 0)
+        .max_by_key(|account| account.amount)
+        .map(|account| Ok((decode_pubkey(&account.address)?, account.amount)))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zera_shared::FEE_BPS;
+
+    fn tiered_config() -> ProgramConfig {
+        let mut config = ProgramConfig { governance_mint: [9; 32], ..Default::default() };
+        config.fee_tiers[0] = FeeTier { min_closed: 100, min_holding: 0, fee_bps: 300 };
+        config.fee_tiers[1] = FeeTier { min_closed: 0, min_holding: 1_000, fee_bps: 250 };
+        config
+    }
+
+    #[test]
+    fn standard_fee_without_accounts() {
+        let status = FeeStatus { config: tiered_config(), ..Default::default() };
+        assert_eq!(status.tier(), None);
+        assert_eq!(status.fee_bps(), FEE_BPS as u16);
+
+        let mut options = BatchCleanOptions::default();
+        status.apply(&mut options);
+        assert_eq!(options, BatchCleanOptions::default());
+    }
+
+    #[test]
+    fn picks_tier_and_passes_accounts() {
+        let status = FeeStatus {
+            config: tiered_config(),
+            user_stats: Some(([1; 32], UserStats { closed_accounts: 150 })),
+            governance_account: Some(([2; 32], 1_000)),
+        };
+        assert_eq!(status.tier().map(|(index, _)| index), Some(1));
+        assert_eq!(status.fee_bps(), 250);
+
+        let mut options = BatchCleanOptions { destination: Some([3; 32]), ..Default::default() };
+        status.apply(&mut options);
+        assert_eq!(options.destination, Some([3; 32]));
+        assert_eq!(options.user_stats, Some([1; 32]));
+        assert_eq!(options.governance_account, Some([2; 32]));
+    }
+}
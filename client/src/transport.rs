@@ -0,0 +1,34 @@
+use serde_json::Value;
+
+use crate::error::ClientError;
+
+/// Pluggable JSON-RPC transport.
+///
+/// Implementations only move bytes: they send `method`/`params` to a Solana
+/// RPC node and hand back the `result` member of the response. Browser
+/// transports are not `Send`, so the returned futures aren't either.
+/// [`crate::jsonrpc`] has the envelope and backoff helpers HTTP transports share.
+#[allow(async_fn_in_trait)]
+pub trait RpcTransport {
+    async fn request(&self, method: &str, params: Value) -> Result<Value, ClientError>;
+
+    /// Send `requests` as one JSON-RPC batch and return their results in order,
+    /// failing if any of them fails. The default sends them one at a time.
+    async fn request_batch(&self, requests: Vec<(&str, Value)>) -> Result<Vec<Value>, ClientError> {
+        let mut results = Vec::with_capacity(requests.len());
+        for (method, params) in requests {
+            results.push(self.request(method, params).await?);
+        }
+        Ok(results)
+    }
+}
+
+impl<T: RpcTransport + ?Sized> RpcTransport for &T {
+    async fn request(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+        (**self).request(method, params).await
+    }
+
+    async fn request_batch(&self, requests: Vec<(&str, Value)>) -> Result<Vec<Value>, ClientError> {
+        (**self).request_batch(requests).await
+    }
+}
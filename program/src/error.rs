@@ -14,6 +14,93 @@ pub enum JanitorError {
 
     #[error("Arithmetic overflow")]
     Overflow,
+
+    #[error("Token account count does not match num_accounts")]
+    AccountCountMismatch,
+
+    #[error("Token account passed more than once")]
+    DuplicateAccount,
+
+    #[error("Account must be writable")]
+    AccountNotWritable,
+
+    #[error("Token account is not owned by the SPL Token program")]
+    InvalidTokenAccount,
+
+    #[error("Token account owner is not the signer")]
+    TokenOwnerMismatch,
+
+    #[error("Token program is not SPL Token")]
+    InvalidTokenProgram,
+
+    #[error("Treasury account does not match the protocol treasury")]
+    InvalidTreasury,
+
+    #[error("Vault is not initialized or below rent-exemption")]
+    VaultNotInitialized,
+
+    #[error("Vault is already initialized")]
+    VaultAlreadyInitialized,
+
+    #[error("Signer is not the program admin")]
+    Unauthorized,
+
+    #[error("Payout destination cannot be the vault or a closed account")]
+    InvalidPayoutDestination,
+
+    #[error("Invalid config PDA derivation")]
+    InvalidConfigPda,
+
+    #[error("Referrer cannot be the user, payout destination or a protocol account")]
+    InvalidReferrer,
+
+    #[error("Referral share exceeds 100% of the fee")]
+    InvalidReferralBps,
+
+    #[error("User stats account is not the signer's initialized stats PDA")]
+    InvalidUserStats,
+
+    #[error("User stats account already exists")]
+    UserStatsAlreadyInitialized,
+
+    #[error("Governance token account is not the signer's account for the governance mint")]
+    InvalidGovernanceAccount,
+
+    #[error("Fee tier charges more than the standard fee")]
+    InvalidFeeTier,
+
+    #[error("Program is paused")]
+    ProgramPaused,
+
+    #[error("Invalid janitor PDA")]
+    InvalidJanitorPda,
+
+    #[error("Token account close authority is not the janitor")]
+    NotDelegated,
+
+    #[error("Missing or malformed signed intent")]
+    InvalidIntent,
+
+    #[error("Signed intent has expired")]
+    IntentExpired,
+
+    #[error("Token mint is not allowed by the signed intent")]
+    MintNotAllowed,
+
+    #[error("Relayer reimbursement exceeds the cap or the payout")]
+    RelayerFeeTooHigh,
+
+    #[error("Target is not the owner's associated token account")]
+    NotAssociatedAccount,
+
+    #[error("Token account mint does not match the target account")]
+    MintMismatch,
+
+    #[error("Accounts are not the signer's NFT with its metadata and master edition")]
+    InvalidNft,
+
+    #[error("Metadata program is not Metaplex Token Metadata")]
+    InvalidMetadataProgram,
 }
 
 impl From<JanitorError> for ProgramError {
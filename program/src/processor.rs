@@ -2,14 +2,37 @@ use borsh::BorshDeserialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    log::sol_log_data,
     msg,
-    program::invoke_signed,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
     pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::{
+        self,
+        instructions::{load_current_index_checked, load_instruction_at_checked},
+        Sysvar,
+    },
 };
+use solana_program::clock::Clock;
+use spl_token::instruction::AuthorityType;
+use spl_token::state::Account as TokenAccount;
+use zera_shared::event::JanitorEvent;
+use zera_shared::intent::{parse_ed25519_instruction_data, CleanIntent};
 
 use crate::error::JanitorError;
 use crate::instruction::JanitorInstruction;
-use crate::state::{find_vault_pda, BPS_DENOMINATOR, FEE_BPS, VAULT_SEED};
+use crate::state::{
+    find_associated_token_address, find_config_pda, find_janitor_pda, find_master_edition_pda,
+    find_metadata_pda, find_program_data_address, find_user_stats_pda, find_vault_pda, load_config,
+    load_user_stats, upgrade_authority, FeeTier, ProgramConfig, UserStats, BPS_DENOMINATOR,
+    CONFIG_SEED, CRANK_REWARD_BPS, ED25519_PROGRAM_ID, FEE_BPS, JANITOR_SEED, MAX_FEE_TIERS,
+    MAX_RELAYER_FEE, METADATA_PROGRAM_ID, METAPLEX_BURN_NFT, TREASURY, USER_STATS_SEED, VAULT_SEED,
+};
 
 pub fn process(
     program_id: &Pubkey,
@@ -17,19 +40,68 @@ pub fn process(
     instruction_data: &[u8],
 ) -> ProgramResult {
     let instruction = JanitorInstruction::try_from_slice(instruction_data)
-        .map_err(|_| solana_program::program_error::ProgramError::InvalidInstructionData)?;
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
 
     match instruction {
-        JanitorInstruction::BatchClean { num_accounts } => {
-            process_batch_clean(program_id, accounts, num_accounts)
+        JanitorInstruction::BatchClean {
+            num_accounts,
+            has_destination,
+            has_referrer,
+            has_user_stats,
+            has_governance_account,
+            relayer_fee,
+        } => {
+            let optional = OptionalAccounts {
+                destination: has_destination,
+                referrer: has_referrer,
+                user_stats: has_user_stats,
+                governance_account: has_governance_account,
+                relayer_fee,
+            };
+            process_batch_clean(program_id, accounts, num_accounts, optional)
+        }
+        JanitorInstruction::InitializeVault => process_initialize_vault(program_id, accounts),
+        JanitorInstruction::SweepVault => process_sweep_vault(program_id, accounts),
+        JanitorInstruction::SetConfig { referral_bps } => {
+            process_set_config(program_id, accounts, referral_bps)
+        }
+        JanitorInstruction::SetFeeTiers { governance_mint, fee_tiers } => {
+            process_set_fee_tiers(program_id, accounts, governance_mint, fee_tiers)
+        }
+        JanitorInstruction::InitUserStats => process_init_user_stats(program_id, accounts),
+        JanitorInstruction::SetPaused { paused } => process_set_paused(program_id, accounts, paused),
+        JanitorInstruction::Authorize { num_accounts } => {
+            process_authorize(program_id, accounts, num_accounts)
+        }
+        JanitorInstruction::Revoke { num_accounts } => process_revoke(program_id, accounts, num_accounts),
+        JanitorInstruction::CrankClean { num_accounts } => {
+            process_crank_clean(program_id, accounts, num_accounts)
+        }
+        JanitorInstruction::IntentClean { num_accounts } => {
+            process_intent_clean(program_id, accounts, num_accounts)
+        }
+        JanitorInstruction::Consolidate { num_accounts } => {
+            process_consolidate(program_id, accounts, num_accounts)
         }
+        JanitorInstruction::BurnNft => process_burn_nft(program_id, accounts),
     }
 }
 
+/// Which optional `BatchClean` header accounts are present, in account order.
+struct OptionalAccounts {
+    destination: bool,
+    referrer: bool,
+    user_stats: bool,
+    governance_account: bool,
+    /// Lamports reimbursed to a relayer fee payer account, if one follows.
+    relayer_fee: Option<u64>,
+}
+
 fn process_batch_clean(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     num_accounts: u8,
+    optional: OptionalAccounts,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
@@ -37,27 +109,111 @@ fn process_batch_clean(
     let vault = next_account_info(accounts_iter)?;
     let treasury = next_account_info(accounts_iter)?;
     let token_program = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+    let destination = next_optional_account(accounts_iter, optional.destination)?.unwrap_or(user);
+    let referrer = next_optional_account(accounts_iter, optional.referrer)?;
+    let user_stats = next_optional_account(accounts_iter, optional.user_stats)?;
+    let governance_account = next_optional_account(accounts_iter, optional.governance_account)?;
+    let fee_payer = next_optional_account(accounts_iter, optional.relayer_fee.is_some())?;
+    let relayer_fee = optional.relayer_fee.unwrap_or(0);
+
+    let config = load_config(program_id, config)?;
+    if config.paused {
+        return Err(JanitorError::ProgramPaused.into());
+    }
 
     // 1. Validate user is signer
     if !user.is_signer {
         return Err(JanitorError::MissingSigner.into());
     }
 
-    // 2. Validate vault PDA
+    // 2. Validate vault PDA, treasury and token program
     let (expected_vault, vault_bump) = find_vault_pda(program_id);
     if vault.key != &expected_vault {
         return Err(JanitorError::InvalidVaultPda.into());
     }
+    if treasury.key != &TREASURY {
+        return Err(JanitorError::InvalidTreasury.into());
+    }
+    if token_program.key != &spl_token::id() {
+        return Err(JanitorError::InvalidTokenProgram.into());
+    }
+    if [user, vault, treasury, destination].iter().any(|a| !a.is_writable) {
+        return Err(JanitorError::AccountNotWritable.into());
+    }
+    if destination.key == vault.key {
+        return Err(JanitorError::InvalidPayoutDestination.into());
+    }
+    if let Some(referrer) = referrer {
+        if !referrer.is_writable {
+            return Err(JanitorError::AccountNotWritable.into());
+        }
+        if [user, destination, vault, treasury].iter().any(|a| a.key == referrer.key) {
+            return Err(JanitorError::InvalidReferrer.into());
+        }
+    }
+    if let Some(fee_payer) = fee_payer {
+        if !fee_payer.is_signer {
+            return Err(JanitorError::MissingSigner.into());
+        }
+        if !fee_payer.is_writable {
+            return Err(JanitorError::AccountNotWritable.into());
+        }
+        if relayer_fee > MAX_RELAYER_FEE {
+            return Err(JanitorError::RelayerFeeTooHigh.into());
+        }
+    }
+
+    // Fee tier inputs: lifetime volume and governance token holdings
+    let mut stats = match user_stats {
+        Some(user_stats) => {
+            if !user_stats.is_writable {
+                return Err(JanitorError::AccountNotWritable.into());
+            }
+            Some(load_user_stats(program_id, user.key, user_stats)?)
+        }
+        None => None,
+    };
+    let holding = match governance_account {
+        Some(account) => governance_holding(&config, account, user)?,
+        None => 0,
+    };
+    let closed_before = stats.map_or(0, |s| s.closed_accounts);
+    let fee_bps = config.fee_bps(closed_before, holding);
+    let vault_minimum = Rent::get()?.minimum_balance(vault.data_len());
+    if vault.owner != program_id || vault.lamports() < vault_minimum {
+        return Err(JanitorError::VaultNotInitialized.into());
+    }
 
-    // 3. Record vault lamports before closing accounts
+    // 3. Validate the token account list before touching anything
+    let token_accounts = accounts_iter.as_slice();
+    if token_accounts.len() != num_accounts as usize {
+        return Err(JanitorError::AccountCountMismatch.into());
+    }
+    let mut unwrapped: u64 = 0;
+    for (i, token_account) in token_accounts.iter().enumerate() {
+        validate_token_account(token_account, user)?;
+        unwrapped = unwrapped
+            .checked_add(wrapped_lamports(token_account)?)
+            .ok_or(JanitorError::Overflow)?;
+        if token_accounts[..i].iter().any(|prev| prev.key == token_account.key) {
+            return Err(JanitorError::DuplicateAccount.into());
+        }
+        if token_account.key == destination.key {
+            return Err(JanitorError::InvalidPayoutDestination.into());
+        }
+        if referrer.is_some_and(|r| r.key == token_account.key) {
+            return Err(JanitorError::InvalidReferrer.into());
+        }
+    }
+
+    // 4. Record vault lamports before closing accounts
     let lamports_before = vault.lamports();
 
-    // 4. CPI close each token account — rent goes to vault
+    // 5. CPI close each token account — rent goes to vault
     let vault_seeds: &[&[u8]] = &[VAULT_SEED, &[vault_bump]];
 
-    for _ in 0..num_accounts {
-        let token_account = next_account_info(accounts_iter)?;
-
+    for token_account in token_accounts {
         // Build CloseAccount CPI: authority=user, destination=vault
         let close_ix = spl_token::instruction::close_account(
             token_program.key,
@@ -79,33 +235,1050 @@ fn process_batch_clean(
         )?;
     }
 
-    // 5. Calculate rent collected
+    // 6. Calculate rent collected; unwrapped SOL is the user's principal, not rent
     let lamports_after = vault.lamports();
-    let rent_collected = lamports_after
+    let collected = lamports_after
         .checked_sub(lamports_before)
         .ok_or(JanitorError::Overflow)?;
+    let rent_collected = collected.checked_sub(unwrapped).ok_or(JanitorError::Overflow)?;
 
     msg!("Rent collected: {} lamports", rent_collected);
+    if unwrapped > 0 {
+        msg!("Unwrapped: {} lamports", unwrapped);
+    }
 
-    // 6. Split: fee to treasury, remainder to user
+    // 7. Split: fee to treasury, remainder to the payout destination
+    if fee_bps as u64 != FEE_BPS {
+        msg!("Fee tier: {} bps", fee_bps);
+    }
     let fee = rent_collected
-        .checked_mul(FEE_BPS)
+        .checked_mul(fee_bps as u64)
         .ok_or(JanitorError::Overflow)?
         .checked_div(BPS_DENOMINATOR)
         .ok_or(JanitorError::Overflow)?;
 
+    // Unwrapped SOL is returned fee-free; the relayer is reimbursed out of the
+    // user's share, never the fee
     let user_payout = rent_collected
         .checked_sub(fee)
-        .ok_or(JanitorError::Overflow)?;
+        .ok_or(JanitorError::Overflow)?
+        .checked_add(unwrapped)
+        .ok_or(JanitorError::Overflow)?
+        .checked_sub(relayer_fee)
+        .ok_or(JanitorError::RelayerFeeTooHigh)?;
 
     msg!("Fee: {} lamports, User payout: {} lamports", fee, user_payout);
+    if relayer_fee > 0 {
+        msg!("Relayer fee: {} lamports", relayer_fee);
+    }
 
-    // 7. Direct lamport transfer (vault is program-owned PDA)
-    **vault.try_borrow_mut_lamports()? -= fee + user_payout;
-    **treasury.try_borrow_mut_lamports()? += fee;
-    **user.try_borrow_mut_lamports()? += user_payout;
+    // Referrer takes its configured share out of the fee
+    let (treasury_fee, referral_fee) = match referrer {
+        Some(_) => config.split_fee(fee),
+        None => (fee, 0),
+    };
+    if referral_fee > 0 {
+        msg!("Referral fee: {} lamports", referral_fee);
+    }
+
+    // 8. Direct lamport transfer (vault is program-owned PDA); only the
+    //    lamports collected above are paid out, so the vault stays rent-exempt
+    let vault_remaining = vault
+        .lamports()
+        .checked_sub(collected)
+        .ok_or(JanitorError::Overflow)?;
+    if vault_remaining < vault_minimum {
+        return Err(JanitorError::VaultNotInitialized.into());
+    }
+    **vault.try_borrow_mut_lamports()? = vault_remaining;
+    **treasury.try_borrow_mut_lamports()? += treasury_fee;
+    if let Some(referrer) = referrer {
+        **referrer.try_borrow_mut_lamports()? += referral_fee;
+    }
+    if let Some(fee_payer) = fee_payer {
+        **fee_payer.try_borrow_mut_lamports()? += relayer_fee;
+    }
+    **destination.try_borrow_mut_lamports()? += user_payout;
+
+    if let (Some(user_stats), Some(stats)) = (user_stats, stats.as_mut()) {
+        stats.closed_accounts = stats
+            .closed_accounts
+            .checked_add(num_accounts as u64)
+            .ok_or(JanitorError::Overflow)?;
+        user_stats.try_borrow_mut_data()?[..UserStats::LEN].copy_from_slice(&stats.pack());
+    }
 
     msg!("Batch clean complete: {} accounts closed", num_accounts);
 
+    // 9. Emit structured event for off-chain indexers and clients
+    let event = JanitorEvent::BatchClean {
+        user: user.key.to_bytes(),
+        destination: destination.key.to_bytes(),
+        referrer: referrer.map(|r| r.key.to_bytes()),
+        accounts_closed: num_accounts,
+        rent_collected,
+        unwrapped,
+        fee_bps,
+        fee,
+        referral_fee,
+        relayer_fee,
+        payout: user_payout,
+    };
+    sol_log_data(&[&event.pack()]);
+
+    Ok(())
+}
+
+fn process_initialize_vault(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let payer = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let system = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+
+    assert_not_paused(program_id, config)?;
+    if !payer.is_signer {
+        return Err(JanitorError::MissingSigner.into());
+    }
+    let (expected_vault, vault_bump) = find_vault_pda(program_id);
+    if vault.key != &expected_vault {
+        return Err(JanitorError::InvalidVaultPda.into());
+    }
+    if vault.owner == program_id {
+        return Err(JanitorError::VaultAlreadyInitialized.into());
+    }
+    if system.key != &system_program::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Top up rather than create_account, so lamports sent to the PDA
+    // before initialization don't block it.
+    let minimum = Rent::get()?.minimum_balance(0);
+    let shortfall = minimum.saturating_sub(vault.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, vault.key, shortfall),
+            &[payer.clone(), vault.clone(), system.clone()],
+        )?;
+    }
+
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, &[vault_bump]];
+    invoke_signed(
+        &system_instruction::assign(vault.key, program_id),
+        &[vault.clone(), system.clone()],
+        &[vault_seeds],
+    )?;
+
+    msg!("Vault initialized with {} lamports", vault.lamports());
+    Ok(())
+}
+
+fn process_sweep_vault(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let program_data = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+
+    assert_admin(program_id, admin, program_data)?;
+    if vault.key != &find_vault_pda(program_id).0 {
+        return Err(JanitorError::InvalidVaultPda.into());
+    }
+    if vault.owner != program_id {
+        return Err(JanitorError::VaultNotInitialized.into());
+    }
+    if treasury.key != &TREASURY {
+        return Err(JanitorError::InvalidTreasury.into());
+    }
+
+    let minimum = Rent::get()?.minimum_balance(vault.data_len());
+    let amount = vault.lamports().saturating_sub(minimum);
+
+    **vault.try_borrow_mut_lamports()? -= amount;
+    **treasury.try_borrow_mut_lamports()? += amount;
+
+    msg!("Swept {} lamports to treasury", amount);
+    sol_log_data(&[&JanitorEvent::VaultSwept { amount }.pack()]);
+
+    Ok(())
+}
+
+fn process_set_config(program_id: &Pubkey, accounts: &[AccountInfo], referral_bps: u16) -> ProgramResult {
+    if referral_bps as u64 > BPS_DENOMINATOR {
+        return Err(JanitorError::InvalidReferralBps.into());
+    }
+    update_config(program_id, accounts, |state| state.referral_bps = referral_bps)?;
+
+    msg!("Config updated: referral {} bps", referral_bps);
+    Ok(())
+}
+
+fn process_set_fee_tiers(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    governance_mint: [u8; 32],
+    fee_tiers: [FeeTier; MAX_FEE_TIERS],
+) -> ProgramResult {
+    if fee_tiers.iter().any(|tier| tier.fee_bps as u64 > FEE_BPS) {
+        return Err(JanitorError::InvalidFeeTier.into());
+    }
+    update_config(program_id, accounts, |state| {
+        state.governance_mint = governance_mint;
+        state.fee_tiers = fee_tiers;
+    })?;
+
+    msg!("Config updated: fee tiers replaced");
+    Ok(())
+}
+
+fn process_set_paused(program_id: &Pubkey, accounts: &[AccountInfo], paused: bool) -> ProgramResult {
+    update_config(program_id, accounts, |state| state.paused = paused)?;
+
+    msg!("Config updated: paused = {}", paused);
+    Ok(())
+}
+
+fn process_init_user_stats(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let user_stats = next_account_info(accounts_iter)?;
+    let system = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+
+    assert_not_paused(program_id, config)?;
+    if !user.is_signer {
+        return Err(JanitorError::MissingSigner.into());
+    }
+    let (expected_stats, stats_bump) = find_user_stats_pda(program_id, user.key);
+    if user_stats.key != &expected_stats {
+        return Err(JanitorError::InvalidUserStats.into());
+    }
+    if user_stats.owner == program_id {
+        return Err(JanitorError::UserStatsAlreadyInitialized.into());
+    }
+
+    let stats_seeds: &[&[u8]] = &[USER_STATS_SEED, user.key.as_ref(), &[stats_bump]];
+    create_pda_account(program_id, user, user_stats, system, UserStats::LEN, stats_seeds)?;
+    user_stats.try_borrow_mut_data()?.copy_from_slice(&UserStats::default().pack());
+
+    msg!("User stats initialized for {}", user.key);
+    Ok(())
+}
+
+fn process_authorize(program_id: &Pubkey, accounts: &[AccountInfo], num_accounts: u8) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let janitor = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+
+    assert_not_paused(program_id, config)?;
+    if !owner.is_signer {
+        return Err(JanitorError::MissingSigner.into());
+    }
+    if janitor.key != &find_janitor_pda(program_id).0 {
+        return Err(JanitorError::InvalidJanitorPda.into());
+    }
+    if token_program.key != &spl_token::id() {
+        return Err(JanitorError::InvalidTokenProgram.into());
+    }
+    let token_accounts = accounts_iter.as_slice();
+    if token_accounts.len() != num_accounts as usize {
+        return Err(JanitorError::AccountCountMismatch.into());
+    }
+
+    for token_account in token_accounts {
+        validate_token_account(token_account, owner)?;
+        let set_ix = spl_token::instruction::set_authority(
+            token_program.key,
+            token_account.key,
+            Some(janitor.key),
+            AuthorityType::CloseAccount,
+            owner.key,
+            &[],
+        )?;
+        invoke(&set_ix, &[token_account.clone(), owner.clone(), token_program.clone()])?;
+    }
+
+    msg!("Authorized {} accounts for auto-clean", num_accounts);
+    Ok(())
+}
+
+/// Hand close authority back to the owner. Deliberately not blocked by the
+/// pause flag, so owners can always take their accounts back.
+fn process_revoke(program_id: &Pubkey, accounts: &[AccountInfo], num_accounts: u8) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let janitor = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(JanitorError::MissingSigner.into());
+    }
+    let (expected_janitor, janitor_bump) = find_janitor_pda(program_id);
+    if janitor.key != &expected_janitor {
+        return Err(JanitorError::InvalidJanitorPda.into());
+    }
+    if token_program.key != &spl_token::id() {
+        return Err(JanitorError::InvalidTokenProgram.into());
+    }
+    let token_accounts = accounts_iter.as_slice();
+    if token_accounts.len() != num_accounts as usize {
+        return Err(JanitorError::AccountCountMismatch.into());
+    }
+
+    let janitor_seeds: &[&[u8]] = &[JANITOR_SEED, &[janitor_bump]];
+    for token_account in token_accounts {
+        validate_token_account(token_account, owner)?;
+        validate_delegated_account(token_account, janitor)?;
+        let set_ix = spl_token::instruction::set_authority(
+            token_program.key,
+            token_account.key,
+            None,
+            AuthorityType::CloseAccount,
+            janitor.key,
+            &[],
+        )?;
+        invoke_signed(
+            &set_ix,
+            &[token_account.clone(), janitor.clone(), token_program.clone()],
+            &[janitor_seeds],
+        )?;
+    }
+
+    msg!("Revoked auto-clean for {} accounts", num_accounts);
+    Ok(())
+}
+
+fn process_crank_clean(program_id: &Pubkey, accounts: &[AccountInfo], num_accounts: u8) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let cranker = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+    let janitor = next_account_info(accounts_iter)?;
+
+    assert_not_paused(program_id, config)?;
+    if !cranker.is_signer {
+        return Err(JanitorError::MissingSigner.into());
+    }
+    if vault.key != &find_vault_pda(program_id).0 {
+        return Err(JanitorError::InvalidVaultPda.into());
+    }
+    if treasury.key != &TREASURY {
+        return Err(JanitorError::InvalidTreasury.into());
+    }
+    if token_program.key != &spl_token::id() {
+        return Err(JanitorError::InvalidTokenProgram.into());
+    }
+    let (expected_janitor, janitor_bump) = find_janitor_pda(program_id);
+    if janitor.key != &expected_janitor {
+        return Err(JanitorError::InvalidJanitorPda.into());
+    }
+    if [cranker, vault, treasury].iter().any(|a| !a.is_writable) {
+        return Err(JanitorError::AccountNotWritable.into());
+    }
+    let vault_minimum = Rent::get()?.minimum_balance(vault.data_len());
+    if vault.owner != program_id || vault.lamports() < vault_minimum {
+        return Err(JanitorError::VaultNotInitialized.into());
+    }
+
+    // Validate every (token account, owner) pair before closing anything
+    let pairs = accounts_iter.as_slice();
+    if pairs.len() != num_accounts as usize * 2 {
+        return Err(JanitorError::AccountCountMismatch.into());
+    }
+    for (i, pair) in pairs.chunks(2).enumerate() {
+        let (token_account, owner) = (&pair[0], &pair[1]);
+        validate_token_account(token_account, owner)?;
+        validate_delegated_account(token_account, janitor)?;
+        if token_balance(token_account)? != 0 {
+            return Err(JanitorError::NonZeroBalance.into());
+        }
+        if !owner.is_writable {
+            return Err(JanitorError::AccountNotWritable.into());
+        }
+        if owner.key == vault.key {
+            return Err(JanitorError::InvalidPayoutDestination.into());
+        }
+        if pairs[..i * 2].iter().step_by(2).any(|prev| prev.key == token_account.key) {
+            return Err(JanitorError::DuplicateAccount.into());
+        }
+    }
+
+    // Close each account into the vault, recording the rent it released
+    let janitor_seeds: &[&[u8]] = &[JANITOR_SEED, &[janitor_bump]];
+    let mut rents = Vec::with_capacity(num_accounts as usize);
+
+    for token_account in pairs.iter().step_by(2) {
+        let lamports_before = vault.lamports();
+        let close_ix = spl_token::instruction::close_account(
+            token_program.key,
+            token_account.key,
+            vault.key,
+            janitor.key,
+            &[],
+        )?;
+        invoke_signed(
+            &close_ix,
+            &[token_account.clone(), vault.clone(), janitor.clone(), token_program.clone()],
+            &[janitor_seeds],
+        )?;
+
+        rents.push(
+            vault
+                .lamports()
+                .checked_sub(lamports_before)
+                .ok_or(JanitorError::Overflow)?,
+        );
+    }
+
+    // Pay each owner their share once all CPIs are done
+    let mut rent_collected: u64 = 0;
+    let mut fee: u64 = 0;
+    let mut crank_reward: u64 = 0;
+    let mut payout: u64 = 0;
+
+    for (owner, rent) in pairs.iter().skip(1).step_by(2).zip(rents) {
+        let account_fee = bps_of(rent, FEE_BPS)?;
+        let account_reward = bps_of(rent, CRANK_REWARD_BPS)?;
+        let owner_payout = rent
+            .checked_sub(account_fee + account_reward)
+            .ok_or(JanitorError::Overflow)?;
+
+        **vault.try_borrow_mut_lamports()? -= owner_payout;
+        **owner.try_borrow_mut_lamports()? += owner_payout;
+
+        rent_collected = rent_collected.checked_add(rent).ok_or(JanitorError::Overflow)?;
+        fee += account_fee;
+        crank_reward += account_reward;
+        payout += owner_payout;
+    }
+
+    msg!("Rent collected: {} lamports", rent_collected);
+    msg!(
+        "Fee: {} lamports, Crank reward: {} lamports, Owner payout: {} lamports",
+        fee,
+        crank_reward,
+        payout
+    );
+
+    let vault_remaining = vault
+        .lamports()
+        .checked_sub(fee + crank_reward)
+        .ok_or(JanitorError::Overflow)?;
+    if vault_remaining < vault_minimum {
+        return Err(JanitorError::VaultNotInitialized.into());
+    }
+    **vault.try_borrow_mut_lamports()? = vault_remaining;
+    **treasury.try_borrow_mut_lamports()? += fee;
+    **cranker.try_borrow_mut_lamports()? += crank_reward;
+
+    msg!("Crank clean complete: {} accounts closed", num_accounts);
+
+    let event = JanitorEvent::CrankClean {
+        cranker: cranker.key.to_bytes(),
+        accounts_closed: num_accounts,
+        rent_collected,
+        fee,
+        crank_reward,
+        payout,
+    };
+    sol_log_data(&[&event.pack()]);
+
+    Ok(())
+}
+
+/// Close delegated accounts on behalf of a user who signed a `CleanIntent`.
+///
+/// The intent is read back from the Ed25519 precompile instruction right
+/// before this one, so the runtime has already checked the signature. An
+/// intent may be submitted more than once until it expires; it can only ever
+/// close empty, delegated accounts and pay their rent to its destination.
+fn process_intent_clean(program_id: &Pubkey, accounts: &[AccountInfo], num_accounts: u8) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let submitter = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+    let janitor = next_account_info(accounts_iter)?;
+    let instructions = next_account_info(accounts_iter)?;
+
+    assert_not_paused(program_id, config)?;
+    if !submitter.is_signer {
+        return Err(JanitorError::MissingSigner.into());
+    }
+    let intent = load_signed_intent(instructions)?;
+    if Clock::get()?.unix_timestamp > intent.expires_at {
+        return Err(JanitorError::IntentExpired.into());
+    }
+    if destination.key.to_bytes() != intent.destination {
+        return Err(JanitorError::InvalidPayoutDestination.into());
+    }
+
+    let (expected_vault, _) = find_vault_pda(program_id);
+    if vault.key != &expected_vault {
+        return Err(JanitorError::InvalidVaultPda.into());
+    }
+    if treasury.key != &TREASURY {
+        return Err(JanitorError::InvalidTreasury.into());
+    }
+    if token_program.key != &spl_token::id() {
+        return Err(JanitorError::InvalidTokenProgram.into());
+    }
+    let (expected_janitor, janitor_bump) = find_janitor_pda(program_id);
+    if janitor.key != &expected_janitor {
+        return Err(JanitorError::InvalidJanitorPda.into());
+    }
+    if [destination, vault, treasury].iter().any(|a| !a.is_writable) {
+        return Err(JanitorError::AccountNotWritable.into());
+    }
+    if destination.key == vault.key {
+        return Err(JanitorError::InvalidPayoutDestination.into());
+    }
+    let vault_minimum = Rent::get()?.minimum_balance(vault.data_len());
+    if vault.owner != program_id || vault.lamports() < vault_minimum {
+        return Err(JanitorError::VaultNotInitialized.into());
+    }
+
+    // Validate every token account against the intent before closing anything
+    let user = Pubkey::new_from_array(intent.user);
+    let token_accounts = accounts_iter.as_slice();
+    if token_accounts.len() != num_accounts as usize {
+        return Err(JanitorError::AccountCountMismatch.into());
+    }
+    for (i, token_account) in token_accounts.iter().enumerate() {
+        if !token_account.is_writable {
+            return Err(JanitorError::AccountNotWritable.into());
+        }
+        validate_delegated_account(token_account, janitor)?;
+        let state = TokenAccount::unpack(&token_account.try_borrow_data()?)
+            .map_err(|_| JanitorError::InvalidTokenAccount)?;
+        if state.owner != user {
+            return Err(JanitorError::TokenOwnerMismatch.into());
+        }
+        if !intent.allows_mint(&state.mint.to_bytes()) {
+            return Err(JanitorError::MintNotAllowed.into());
+        }
+        if state.amount != 0 {
+            return Err(JanitorError::NonZeroBalance.into());
+        }
+        if token_accounts[..i].iter().any(|prev| prev.key == token_account.key) {
+            return Err(JanitorError::DuplicateAccount.into());
+        }
+        if token_account.key == destination.key {
+            return Err(JanitorError::InvalidPayoutDestination.into());
+        }
+    }
+
+    // Close each account into the vault with the janitor's authority
+    let lamports_before = vault.lamports();
+    let janitor_seeds: &[&[u8]] = &[JANITOR_SEED, &[janitor_bump]];
+
+    for token_account in token_accounts {
+        let close_ix = spl_token::instruction::close_account(
+            token_program.key,
+            token_account.key,
+            vault.key,
+            janitor.key,
+            &[],
+        )?;
+        invoke_signed(
+            &close_ix,
+            &[token_account.clone(), vault.clone(), janitor.clone(), token_program.clone()],
+            &[janitor_seeds],
+        )?;
+    }
+
+    let rent_collected = vault
+        .lamports()
+        .checked_sub(lamports_before)
+        .ok_or(JanitorError::Overflow)?;
+    let fee = bps_of(rent_collected, FEE_BPS)?;
+    let payout = rent_collected.checked_sub(fee).ok_or(JanitorError::Overflow)?;
+
+    msg!("Rent collected: {} lamports", rent_collected);
+    msg!("Fee: {} lamports, User payout: {} lamports", fee, payout);
+
+    let vault_remaining = vault
+        .lamports()
+        .checked_sub(rent_collected)
+        .ok_or(JanitorError::Overflow)?;
+    if vault_remaining < vault_minimum {
+        return Err(JanitorError::VaultNotInitialized.into());
+    }
+    **vault.try_borrow_mut_lamports()? = vault_remaining;
+    **treasury.try_borrow_mut_lamports()? += fee;
+    **destination.try_borrow_mut_lamports()? += payout;
+
+    msg!("Intent clean complete: {} accounts closed", num_accounts);
+
+    let event = JanitorEvent::BatchClean {
+        user: intent.user,
+        destination: intent.destination,
+        referrer: None,
+        accounts_closed: num_accounts,
+        rent_collected,
+        unwrapped: 0,
+        fee_bps: FEE_BPS as u16,
+        fee,
+        referral_fee: 0,
+        relayer_fee: 0,
+        payout,
+    };
+    sol_log_data(&[&event.pack()]);
+
+    Ok(())
+}
+
+fn process_consolidate(program_id: &Pubkey, accounts: &[AccountInfo], num_accounts: u8) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let target = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+
+    assert_not_paused(program_id, config)?;
+    if !user.is_signer {
+        return Err(JanitorError::MissingSigner.into());
+    }
+    let (expected_vault, _) = find_vault_pda(program_id);
+    if vault.key != &expected_vault {
+        return Err(JanitorError::InvalidVaultPda.into());
+    }
+    if treasury.key != &TREASURY {
+        return Err(JanitorError::InvalidTreasury.into());
+    }
+    if token_program.key != &spl_token::id() {
+        return Err(JanitorError::InvalidTokenProgram.into());
+    }
+    if [user, vault, treasury].iter().any(|a| !a.is_writable) {
+        return Err(JanitorError::AccountNotWritable.into());
+    }
+    let vault_minimum = Rent::get()?.minimum_balance(vault.data_len());
+    if vault.owner != program_id || vault.lamports() < vault_minimum {
+        return Err(JanitorError::VaultNotInitialized.into());
+    }
+
+    // The target must be the user's canonical ATA; balances only ever move into it
+    validate_token_account(target, user)?;
+    let mint = TokenAccount::unpack(&target.try_borrow_data()?)
+        .map_err(|_| JanitorError::InvalidTokenAccount)?
+        .mint;
+    if target.key != &find_associated_token_address(user.key, &mint) {
+        return Err(JanitorError::NotAssociatedAccount.into());
+    }
+
+    let auxiliaries = accounts_iter.as_slice();
+    if auxiliaries.len() != num_accounts as usize {
+        return Err(JanitorError::AccountCountMismatch.into());
+    }
+    let mut balances = Vec::with_capacity(auxiliaries.len());
+    for (i, aux) in auxiliaries.iter().enumerate() {
+        validate_token_account(aux, user)?;
+        let state = TokenAccount::unpack(&aux.try_borrow_data()?)
+            .map_err(|_| JanitorError::InvalidTokenAccount)?;
+        if state.mint != mint {
+            return Err(JanitorError::MintMismatch.into());
+        }
+        if aux.key == target.key || auxiliaries[..i].iter().any(|prev| prev.key == aux.key) {
+            return Err(JanitorError::DuplicateAccount.into());
+        }
+        balances.push(state.amount);
+    }
+
+    // Transfer each balance into the ATA, then close the emptied account into the vault
+    let lamports_before = vault.lamports();
+    for (aux, &amount) in auxiliaries.iter().zip(&balances) {
+        if amount > 0 {
+            let transfer_ix = spl_token::instruction::transfer(
+                token_program.key,
+                aux.key,
+                target.key,
+                user.key,
+                &[],
+                amount,
+            )?;
+            invoke(
+                &transfer_ix,
+                &[aux.clone(), target.clone(), user.clone(), token_program.clone()],
+            )?;
+        }
+        let close_ix =
+            spl_token::instruction::close_account(token_program.key, aux.key, vault.key, user.key, &[])?;
+        invoke(&close_ix, &[aux.clone(), vault.clone(), user.clone(), token_program.clone()])?;
+    }
+
+    let rent_collected = vault
+        .lamports()
+        .checked_sub(lamports_before)
+        .ok_or(JanitorError::Overflow)?;
+    let fee = bps_of(rent_collected, FEE_BPS)?;
+    let payout = rent_collected.checked_sub(fee).ok_or(JanitorError::Overflow)?;
+    let consolidated = balances
+        .iter()
+        .try_fold(0u64, |sum, &b| sum.checked_add(b))
+        .ok_or(JanitorError::Overflow)?;
+
+    msg!("Consolidated: {} tokens", consolidated);
+    msg!("Rent collected: {} lamports", rent_collected);
+    msg!("Fee: {} lamports, User payout: {} lamports", fee, payout);
+
+    let vault_remaining = vault
+        .lamports()
+        .checked_sub(rent_collected)
+        .ok_or(JanitorError::Overflow)?;
+    if vault_remaining < vault_minimum {
+        return Err(JanitorError::VaultNotInitialized.into());
+    }
+    **vault.try_borrow_mut_lamports()? = vault_remaining;
+    **treasury.try_borrow_mut_lamports()? += fee;
+    **user.try_borrow_mut_lamports()? += payout;
+
+    msg!("Consolidate complete: {} accounts closed", num_accounts);
+
+    let event = JanitorEvent::Consolidate {
+        user: user.key.to_bytes(),
+        mint: mint.to_bytes(),
+        target: target.key.to_bytes(),
+        accounts_closed: num_accounts,
+        amount: consolidated,
+        rent_collected,
+        fee,
+        payout,
+    };
+    sol_log_data(&[&event.pack()]);
+
+    Ok(())
+}
+
+fn process_burn_nft(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+    let metadata = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let master_edition = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let metadata_program = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    assert_not_paused(program_id, config)?;
+    if !user.is_signer {
+        return Err(JanitorError::MissingSigner.into());
+    }
+    if treasury.key != &TREASURY {
+        return Err(JanitorError::InvalidTreasury.into());
+    }
+    if token_program.key != &spl_token::id() {
+        return Err(JanitorError::InvalidTokenProgram.into());
+    }
+    if metadata_program.key != &METADATA_PROGRAM_ID {
+        return Err(JanitorError::InvalidMetadataProgram.into());
+    }
+    if [user, treasury, metadata, mint, master_edition].iter().any(|a| !a.is_writable) {
+        return Err(JanitorError::AccountNotWritable.into());
+    }
+
+    // The token account must hold the one token of this mint, with its Metaplex PDAs
+    validate_token_account(token_account, user)?;
+    let state = TokenAccount::unpack(&token_account.try_borrow_data()?)
+        .map_err(|_| JanitorError::InvalidTokenAccount)?;
+    if &state.mint != mint.key
+        || state.amount != 1
+        || metadata.key != &find_metadata_pda(mint.key)
+        || master_edition.key != &find_master_edition_pda(mint.key)
+    {
+        return Err(JanitorError::InvalidNft.into());
+    }
+
+    // Metaplex burns the token and pays the three accounts' rent to the owner
+    let lamports_before = user.lamports();
+    let burn_ix = Instruction {
+        program_id: METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*metadata.key, false),
+            AccountMeta::new(*user.key, true),
+            AccountMeta::new(*mint.key, false),
+            AccountMeta::new(*token_account.key, false),
+            AccountMeta::new(*master_edition.key, false),
+            AccountMeta::new_readonly(*token_program.key, false),
+        ],
+        data: vec![METAPLEX_BURN_NFT],
+    };
+    invoke(
+        &burn_ix,
+        &[
+            metadata.clone(),
+            user.clone(),
+            mint.clone(),
+            token_account.clone(),
+            master_edition.clone(),
+            token_program.clone(),
+            metadata_program.clone(),
+        ],
+    )?;
+
+    let rent_collected = user
+        .lamports()
+        .checked_sub(lamports_before)
+        .ok_or(JanitorError::Overflow)?;
+    let fee = bps_of(rent_collected, FEE_BPS)?;
+    let payout = rent_collected.checked_sub(fee).ok_or(JanitorError::Overflow)?;
+
+    msg!("Rent collected: {} lamports", rent_collected);
+    msg!("Fee: {} lamports, User payout: {} lamports", fee, payout);
+
+    // The rent landed in the user's wallet, so the fee is a system transfer they signed for
+    if fee > 0 {
+        invoke(
+            &system_instruction::transfer(user.key, treasury.key, fee),
+            &[user.clone(), treasury.clone(), system_program_account.clone()],
+        )?;
+    }
+
+    msg!("NFT burn complete: {}", mint.key);
+
+    let event = JanitorEvent::BatchClean {
+        user: user.key.to_bytes(),
+        destination: user.key.to_bytes(),
+        referrer: None,
+        accounts_closed: 3,
+        rent_collected,
+        unwrapped: 0,
+        fee_bps: FEE_BPS as u16,
+        fee,
+        referral_fee: 0,
+        relayer_fee: 0,
+        payout,
+    };
+    sol_log_data(&[&event.pack()]);
+
+    Ok(())
+}
+
+/// The intent verified by the Ed25519 precompile instruction preceding the
+/// current one, signed by the intent's own user.
+fn load_signed_intent(instructions: &AccountInfo) -> Result<CleanIntent, ProgramError> {
+    if instructions.key != &sysvar::instructions::id() {
+        return Err(JanitorError::InvalidIntent.into());
+    }
+    let current = load_current_index_checked(instructions)?;
+    let index = current.checked_sub(1).ok_or(JanitorError::InvalidIntent)?;
+    let verify_ix = load_instruction_at_checked(index as usize, instructions)?;
+    if verify_ix.program_id != ED25519_PROGRAM_ID {
+        return Err(JanitorError::InvalidIntent.into());
+    }
+
+    let (signer, message) =
+        parse_ed25519_instruction_data(&verify_ix.data).ok_or(JanitorError::InvalidIntent)?;
+    let intent = CleanIntent::from_message(message).ok_or(JanitorError::InvalidIntent)?;
+    if intent.user != signer {
+        return Err(JanitorError::InvalidIntent.into());
+    }
+    Ok(intent)
+}
+
+/// Run an admin-only config change, creating or growing the config PDA as needed.
+///
+/// Accounts: admin (signer, payer), program data, config PDA, system program.
+fn update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    update: impl FnOnce(&mut ProgramConfig),
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let program_data = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+    let system = next_account_info(accounts_iter)?;
+
+    assert_admin(program_id, admin, program_data)?;
+    let mut state = load_config(program_id, config)?;
+
+    if config.owner != program_id {
+        let (_, config_bump) = find_config_pda(program_id);
+        let config_seeds: &[&[u8]] = &[CONFIG_SEED, &[config_bump]];
+        create_pda_account(program_id, admin, config, system, ProgramConfig::LEN, config_seeds)?;
+    } else if config.data_len() < ProgramConfig::LEN {
+        // Config created by an older version: grow it into the new layout
+        let shortfall = Rent::get()?
+            .minimum_balance(ProgramConfig::LEN)
+            .saturating_sub(config.lamports());
+        if shortfall > 0 {
+            invoke(
+                &system_instruction::transfer(admin.key, config.key, shortfall),
+                &[admin.clone(), config.clone(), system.clone()],
+            )?;
+        }
+        config.realloc(ProgramConfig::LEN, true)?;
+    }
+
+    update(&mut state);
+    config.try_borrow_mut_data()?.copy_from_slice(&state.pack());
+    Ok(())
+}
+
+/// Allocate a rent-exempt, program-owned PDA of `space` bytes, funded by `payer`.
+///
+/// Works on PDAs that already hold lamports, which `create_account` rejects,
+/// so nobody can block creation by sending lamports to the address first.
+fn create_pda_account<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system: &AccountInfo<'a>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    if system.key != &system_program::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let minimum = Rent::get()?.minimum_balance(space);
+
+    if account.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(payer.key, account.key, minimum, space as u64, program_id),
+            &[payer.clone(), account.clone(), system.clone()],
+            &[seeds],
+        );
+    }
+
+    let shortfall = minimum.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, shortfall),
+            &[payer.clone(), account.clone(), system.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, program_id),
+        &[account.clone(), system.clone()],
+        &[seeds],
+    )
+}
+
+/// Require `admin` to sign and be the upgrade authority recorded in `program_data`.
+fn assert_admin(program_id: &Pubkey, admin: &AccountInfo, program_data: &AccountInfo) -> ProgramResult {
+    if !admin.is_signer {
+        return Err(JanitorError::MissingSigner.into());
+    }
+    if program_data.key != &find_program_data_address(program_id)
+        || upgrade_authority(program_data) != Some(*admin.key)
+    {
+        return Err(JanitorError::Unauthorized.into());
+    }
+    Ok(())
+}
+
+/// Fail with `ProgramPaused` while the admin has paused the program.
+fn assert_not_paused(program_id: &Pubkey, config: &AccountInfo) -> ProgramResult {
+    if load_config(program_id, config)?.paused {
+        return Err(JanitorError::ProgramPaused.into());
+    }
+    Ok(())
+}
+
+/// Next account if `present`, for optional header accounts.
+fn next_optional_account<'a, 'b>(
+    iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    present: bool,
+) -> Result<Option<&'a AccountInfo<'b>>, ProgramError> {
+    present.then(|| next_account_info(iter)).transpose()
+}
+
+/// Governance token balance proven by `account`, which must be `owner`'s
+/// SPL token account for the configured governance mint.
+fn governance_holding(
+    config: &ProgramConfig,
+    account: &AccountInfo,
+    owner: &AccountInfo,
+) -> Result<u64, ProgramError> {
+    if !config.has_governance_mint() || account.owner != &spl_token::id() {
+        return Err(JanitorError::InvalidGovernanceAccount.into());
+    }
+    let state = TokenAccount::unpack(&account.try_borrow_data()?)
+        .map_err(|_| JanitorError::InvalidGovernanceAccount)?;
+    if &state.owner != owner.key || state.mint.to_bytes() != config.governance_mint {
+        return Err(JanitorError::InvalidGovernanceAccount.into());
+    }
+    Ok(state.amount)
+}
+
+/// Check a token account's close authority has been delegated to the janitor PDA.
+///
+/// Balances are not checked here: owners may revoke non-empty accounts.
+fn validate_delegated_account(token_account: &AccountInfo, janitor: &AccountInfo) -> ProgramResult {
+    if token_account.owner != &spl_token::id() {
+        return Err(JanitorError::InvalidTokenAccount.into());
+    }
+    let state = TokenAccount::unpack(&token_account.try_borrow_data()?)
+        .map_err(|_| JanitorError::InvalidTokenAccount)?;
+    if state.close_authority != Some(*janitor.key).into() {
+        return Err(JanitorError::NotDelegated.into());
+    }
+    Ok(())
+}
+
+/// `bps` basis points of `lamports`, rounded down.
+fn bps_of(lamports: u64, bps: u64) -> Result<u64, ProgramError> {
+    Ok(lamports
+        .checked_mul(bps)
+        .ok_or(JanitorError::Overflow)?
+        / BPS_DENOMINATOR)
+}
+
+/// Token balance of an account already checked by `validate_token_account`.
+fn token_balance(token_account: &AccountInfo) -> Result<u64, ProgramError> {
+    let state = TokenAccount::unpack(&token_account.try_borrow_data()?)
+        .map_err(|_| JanitorError::InvalidTokenAccount)?;
+    Ok(state.amount)
+}
+
+/// Lamports a wrapped SOL account holds above its rent-exempt reserve; 0 for other mints.
+fn wrapped_lamports(token_account: &AccountInfo) -> Result<u64, ProgramError> {
+    let state = TokenAccount::unpack(&token_account.try_borrow_data()?)
+        .map_err(|_| JanitorError::InvalidTokenAccount)?;
+    Ok(match state.is_native {
+        COption::Some(reserve) => token_account.lamports().saturating_sub(reserve),
+        COption::None => 0,
+    })
+}
+
+/// Check a token account is writable, owned by SPL Token and belongs to `owner`.
+fn validate_token_account(token_account: &AccountInfo, owner: &AccountInfo) -> ProgramResult {
+    if !token_account.is_writable {
+        return Err(JanitorError::AccountNotWritable.into());
+    }
+    if token_account.owner != &spl_token::id() {
+        return Err(JanitorError::InvalidTokenAccount.into());
+    }
+    let state = TokenAccount::unpack(&token_account.try_borrow_data()?)
+        .map_err(|_| JanitorError::InvalidTokenAccount)?;
+    if &state.owner != owner.key {
+        return Err(JanitorError::TokenOwnerMismatch.into());
+    }
     Ok(())
 }
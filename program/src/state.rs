@@ -1,10 +1,123 @@
-use solana_program::pubkey::Pubkey;
+use solana_program::{
+    account_info::AccountInfo, bpf_loader_upgradeable, program_error::ProgramError, pubkey::Pubkey,
+};
+pub use zera_shared::config::{FeeTier, ProgramConfig, UserStats, MAX_FEE_TIERS};
+
+use crate::error::JanitorError;
 
 pub const VAULT_SEED: &[u8] = zera_shared::VAULT_SEED;
+pub const CONFIG_SEED: &[u8] = zera_shared::CONFIG_SEED;
+pub const USER_STATS_SEED: &[u8] = zera_shared::USER_STATS_SEED;
+pub const JANITOR_SEED: &[u8] = zera_shared::JANITOR_SEED;
 pub const FEE_BPS: u64 = zera_shared::FEE_BPS;
+pub const CRANK_REWARD_BPS: u64 = zera_shared::CRANK_REWARD_BPS;
+pub const MAX_RELAYER_FEE: u64 = zera_shared::MAX_RELAYER_FEE;
 pub const BPS_DENOMINATOR: u64 = zera_shared::BPS_DENOMINATOR;
+pub const TREASURY: Pubkey = Pubkey::new_from_array(zera_shared::TREASURY_PUBKEY);
+pub const ED25519_PROGRAM_ID: Pubkey = Pubkey::new_from_array(zera_shared::ED25519_PROGRAM_ID);
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::new_from_array(zera_shared::ASSOCIATED_TOKEN_PROGRAM_ID);
+pub const METADATA_PROGRAM_ID: Pubkey = Pubkey::new_from_array(zera_shared::METADATA_PROGRAM_ID);
+
+/// Metaplex `BurnNft` instruction discriminator.
+pub const METAPLEX_BURN_NFT: u8 = 29;
+
+/// `UpgradeableLoaderState::ProgramData` tag and field offsets (bincode layout).
+const PROGRAM_DATA_TAG: u32 = 3;
+const PROGRAM_DATA_AUTHORITY_OFFSET: usize = 12;
 
 /// Derive the vault PDA and its bump seed.
 pub fn find_vault_pda(program_id: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[VAULT_SEED], program_id)
 }
+
+/// Derive the config PDA and its bump seed.
+pub fn find_config_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+/// Derive the janitor PDA, the close authority users delegate to, and its bump seed.
+pub fn find_janitor_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[JANITOR_SEED], program_id)
+}
+
+/// Derive `owner`'s associated token account for `mint` under SPL Token.
+pub fn find_associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), spl_token::id().as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Derive the Metaplex metadata PDA of `mint`.
+pub fn find_metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[zera_shared::METADATA_SEED, METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Derive the Metaplex master edition PDA of `mint`.
+pub fn find_master_edition_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            zera_shared::METADATA_SEED,
+            METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+            zera_shared::EDITION_SEED,
+        ],
+        &METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Read the config PDA, falling back to defaults until the admin creates it.
+pub fn load_config(program_id: &Pubkey, config: &AccountInfo) -> Result<ProgramConfig, ProgramError> {
+    if config.key != &find_config_pda(program_id).0 {
+        return Err(JanitorError::InvalidConfigPda.into());
+    }
+    if config.owner != program_id {
+        return Ok(ProgramConfig::default());
+    }
+    ProgramConfig::unpack(&config.try_borrow_data()?).ok_or(ProgramError::InvalidAccountData)
+}
+
+/// Derive `user`'s stats PDA and its bump seed.
+pub fn find_user_stats_pda(program_id: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[USER_STATS_SEED, user.as_ref()], program_id)
+}
+
+/// Read `user`'s initialized stats PDA.
+pub fn load_user_stats(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_stats: &AccountInfo,
+) -> Result<UserStats, ProgramError> {
+    if user_stats.key != &find_user_stats_pda(program_id, user).0 || user_stats.owner != program_id {
+        return Err(JanitorError::InvalidUserStats.into());
+    }
+    UserStats::unpack(&user_stats.try_borrow_data()?).ok_or(ProgramError::InvalidAccountData)
+}
+
+/// Address of the program's `ProgramData` account under the upgradeable loader.
+pub fn find_program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+/// Read the upgrade authority out of a `ProgramData` account.
+///
+/// Returns `None` if the account isn't program data or the program is immutable.
+pub fn upgrade_authority(program_data: &AccountInfo) -> Option<Pubkey> {
+    if program_data.owner != &bpf_loader_upgradeable::id() {
+        return None;
+    }
+    let data = program_data.try_borrow_data().ok()?;
+    let tag = u32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+    let authority = data.get(PROGRAM_DATA_AUTHORITY_OFFSET..PROGRAM_DATA_AUTHORITY_OFFSET + 33)?;
+    if tag != PROGRAM_DATA_TAG || authority[0] != 1 {
+        return None;
+    }
+    Some(Pubkey::new_from_array(authority[1..].try_into().ok()?))
+}
@@ -0,0 +1,316 @@
+mod common;
+
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_program_test::tokio;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use zera_program::error::JanitorError;
+use zera_shared::instruction::{batch_clean, BatchCleanOptions, JanitorInstruction};
+use zera_shared::{BPS_DENOMINATOR, FEE_BPS};
+
+use common::*;
+
+fn batch_clean_ix(user: &Pubkey, vault: &Pubkey, token_accounts: &[Pubkey]) -> solana_sdk::instruction::Instruction {
+    let keys: Vec<[u8; 32]> = token_accounts.iter().map(|k| k.to_bytes()).collect();
+    let options = BatchCleanOptions::default();
+    to_sdk(batch_clean(user.to_bytes(), vault.to_bytes(), config().to_bytes(), &options, &keys))
+}
+
+#[tokio::test]
+async fn closes_empty_accounts_and_splits_rent() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let accounts: Vec<Pubkey> = (0..3)
+        .map(|_| add_token_account(&mut pt, &mint, &user.pubkey(), 0))
+        .collect();
+    let mut ctx = pt.start_with_context().await;
+
+    let vault_before = balance(&mut ctx, &vault()).await;
+    let treasury_before = balance(&mut ctx, &treasury()).await;
+
+    let ix = batch_clean_ix(&user.pubkey(), &vault(), &accounts);
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    let rent = 3 * Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let fee = rent * FEE_BPS / BPS_DENOMINATOR;
+    let payout = rent - fee;
+
+    for account in &accounts {
+        assert!(!account_exists(&mut ctx, account).await);
+    }
+    assert_eq!(balance(&mut ctx, &vault()).await, vault_before);
+    assert_eq!(balance(&mut ctx, &treasury()).await, treasury_before + fee);
+    assert_eq!(balance(&mut ctx, &user.pubkey()).await, USER_STARTING_LAMPORTS + payout);
+}
+
+#[tokio::test]
+async fn rejects_wrong_vault() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_ix(&user.pubkey(), &Pubkey::new_unique(), &[account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidVaultPda));
+}
+
+#[tokio::test]
+async fn rejects_unsigned_user() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = batch_clean_ix(&user.pubkey(), &vault(), &[account]);
+    ix.accounts[0].is_signer = false;
+    let err = instruction_error(send(&mut ctx, &[ix], &[]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::MissingSigner));
+}
+
+#[tokio::test]
+async fn rejects_fewer_accounts_than_num_accounts() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let accounts: Vec<Pubkey> = (0..2)
+        .map(|_| add_token_account(&mut pt, &mint, &user.pubkey(), 0))
+        .collect();
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = batch_clean_ix(&user.pubkey(), &vault(), &accounts);
+    ix.data = JanitorInstruction::BatchClean {
+        num_accounts: 3,
+        has_destination: false,
+        has_referrer: false,
+        has_user_stats: false,
+        has_governance_account: false,
+        relayer_fee: None,
+    }
+    .pack();
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::AccountCountMismatch));
+}
+
+#[tokio::test]
+async fn rejects_extra_trailing_accounts() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let accounts: Vec<Pubkey> = (0..2)
+        .map(|_| add_token_account(&mut pt, &mint, &user.pubkey(), 0))
+        .collect();
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = batch_clean_ix(&user.pubkey(), &vault(), &accounts);
+    ix.data = JanitorInstruction::BatchClean {
+        num_accounts: 1,
+        has_destination: false,
+        has_referrer: false,
+        has_user_stats: false,
+        has_governance_account: false,
+        relayer_fee: None,
+    }
+    .pack();
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::AccountCountMismatch));
+    assert!(account_exists(&mut ctx, &accounts[1]).await);
+}
+
+#[tokio::test]
+async fn rejects_duplicate_token_accounts() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_ix(&user.pubkey(), &vault(), &[account, account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::DuplicateAccount));
+}
+
+#[tokio::test]
+async fn rejects_readonly_token_account() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = batch_clean_ix(&user.pubkey(), &vault(), &[account]);
+    ix.accounts[5].is_writable = false;
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::AccountNotWritable));
+}
+
+#[tokio::test]
+async fn rejects_readonly_vault() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = batch_clean_ix(&user.pubkey(), &vault(), &[account]);
+    ix.accounts[1].is_writable = false;
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::AccountNotWritable));
+}
+
+#[tokio::test]
+async fn rejects_account_not_owned_by_token_program() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let impostor = add_wallet(&mut pt);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_ix(&user.pubkey(), &vault(), &[impostor.pubkey()]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidTokenAccount));
+}
+
+#[tokio::test]
+async fn rejects_token_account_of_another_owner() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let foreign = add_token_account(&mut pt, &mint, &Pubkey::new_unique(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_ix(&user.pubkey(), &vault(), &[foreign]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::TokenOwnerMismatch));
+}
+
+#[tokio::test]
+async fn rejects_wrong_treasury() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = batch_clean_ix(&user.pubkey(), &vault(), &[account]);
+    ix.accounts[2].pubkey = user.pubkey();
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidTreasury));
+}
+
+#[tokio::test]
+async fn rejects_foreign_token_program() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = batch_clean_ix(&user.pubkey(), &vault(), &[account]);
+    ix.accounts[3].pubkey = solana_program::system_program::id();
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidTokenProgram));
+    assert!(account_exists(&mut ctx, &account).await);
+}
+
+#[tokio::test]
+async fn rejects_account_with_balance() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 5);
+    let empty = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let funded = add_token_account(&mut pt, &mint, &user.pubkey(), 5);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_ix(&user.pubkey(), &vault(), &[empty, funded]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, InstructionError::Custom(spl_token::error::TokenError::NonNativeHasBalance as u32));
+    assert!(account_exists(&mut ctx, &empty).await);
+    assert_eq!(balance(&mut ctx, &user.pubkey()).await, USER_STARTING_LAMPORTS);
+}
+
+#[tokio::test]
+async fn unwraps_native_sol_without_fee_on_principal() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let empty = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let wrapped = 750_000_000;
+    let wsol = add_native_token_account(&mut pt, &user.pubkey(), wrapped);
+    let mut ctx = pt.start_with_context().await;
+    let vault_before = balance(&mut ctx, &vault()).await;
+    let treasury_before = balance(&mut ctx, &treasury()).await;
+
+    let ix = batch_clean_ix(&user.pubkey(), &vault(), &[empty, wsol]);
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    let rent = 2 * Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let fee = rent * FEE_BPS / BPS_DENOMINATOR;
+
+    assert!(!account_exists(&mut ctx, &wsol).await);
+    assert_eq!(balance(&mut ctx, &vault()).await, vault_before);
+    assert_eq!(balance(&mut ctx, &treasury()).await, treasury_before + fee);
+    assert_eq!(balance(&mut ctx, &user.pubkey()).await, USER_STARTING_LAMPORTS + rent - fee + wrapped);
+}
+
+#[test]
+fn error_codes_match_shared_table() {
+    use zera_shared::error::JanitorError as Shared;
+
+    let program = [
+        JanitorError::InvalidVaultPda,
+        JanitorError::MissingSigner,
+        JanitorError::NonZeroBalance,
+        JanitorError::Overflow,
+        JanitorError::AccountCountMismatch,
+        JanitorError::DuplicateAccount,
+        JanitorError::AccountNotWritable,
+        JanitorError::InvalidTokenAccount,
+        JanitorError::TokenOwnerMismatch,
+        JanitorError::InvalidTokenProgram,
+        JanitorError::InvalidTreasury,
+        JanitorError::VaultNotInitialized,
+        JanitorError::VaultAlreadyInitialized,
+        JanitorError::Unauthorized,
+        JanitorError::InvalidPayoutDestination,
+        JanitorError::InvalidConfigPda,
+        JanitorError::InvalidReferrer,
+        JanitorError::InvalidReferralBps,
+        JanitorError::InvalidUserStats,
+        JanitorError::UserStatsAlreadyInitialized,
+        JanitorError::InvalidGovernanceAccount,
+        JanitorError::InvalidFeeTier,
+        JanitorError::ProgramPaused,
+        JanitorError::InvalidJanitorPda,
+        JanitorError::NotDelegated,
+        JanitorError::InvalidIntent,
+        JanitorError::IntentExpired,
+        JanitorError::MintNotAllowed,
+        JanitorError::RelayerFeeTooHigh,
+        JanitorError::NotAssociatedAccount,
+        JanitorError::MintMismatch,
+        JanitorError::InvalidNft,
+        JanitorError::InvalidMetadataProgram,
+    ];
+    assert_eq!(program.len(), Shared::ALL.len());
+    for (err, shared) in program.into_iter().zip(Shared::ALL) {
+        assert_eq!(err.to_string(), shared.message());
+        assert_eq!(err as u32, shared.code());
+    }
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program::invoke;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_program_test::{processor, tokio, ProgramTest};
+use solana_sdk::account::Account;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use zera_program::error::JanitorError;
+use zera_program::state::{find_master_edition_pda, find_metadata_pda, METADATA_PROGRAM_ID};
+use zera_shared::instruction::burn_nft;
+use zera_shared::{BPS_DENOMINATOR, FEE_BPS};
+
+use common::*;
+
+/// Rent of the fake metadata and master edition accounts.
+const METADATA_LAMPORTS: u64 = 5_616_720;
+const EDITION_LAMPORTS: u64 = 2_853_600;
+
+/// Stand-in for Metaplex `BurnNft`: burn the token, close the token account and
+/// drain the metadata and edition PDAs, all to the owner.
+fn mock_burn_nft(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [metadata, owner, mint, token_account, edition, token_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if data != [29] || !owner.is_signer {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    invoke(
+        &spl_token::instruction::burn(token_program.key, token_account.key, mint.key, owner.key, &[], 1)?,
+        &[token_account.clone(), mint.clone(), owner.clone(), token_program.clone()],
+    )?;
+    invoke(
+        &spl_token::instruction::close_account(token_program.key, token_account.key, owner.key, owner.key, &[])?,
+        &[token_account.clone(), owner.clone(), owner.clone(), token_program.clone()],
+    )?;
+    for account in [metadata, edition] {
+        **owner.try_borrow_mut_lamports()? += account.lamports();
+        **account.try_borrow_mut_lamports()? = 0;
+    }
+    Ok(())
+}
+
+/// Add an NFT held by `owner`: mint, token account and Metaplex PDAs.
+fn add_nft(pt: &mut ProgramTest, owner: &Pubkey) -> (Pubkey, Pubkey) {
+    let mint = add_mint(pt, 1);
+    let token_account = add_token_account(pt, &mint, owner, 1);
+    for (address, lamports) in [
+        (find_metadata_pda(&mint), METADATA_LAMPORTS),
+        (find_master_edition_pda(&mint), EDITION_LAMPORTS),
+    ] {
+        pt.add_account(
+            address,
+            Account { lamports, data: vec![1; 8], owner: METADATA_PROGRAM_ID, ..Account::default() },
+        );
+    }
+    (mint, token_account)
+}
+
+fn program_test_with_metaplex() -> ProgramTest {
+    let mut pt = program_test();
+    pt.add_program("mpl_token_metadata", METADATA_PROGRAM_ID, processor!(mock_burn_nft));
+    pt
+}
+
+fn burn_ix(user: &Pubkey, mint: &Pubkey, token_account: &Pubkey) -> Instruction {
+    to_sdk(burn_nft(
+        user.to_bytes(),
+        config().to_bytes(),
+        mint.to_bytes(),
+        token_account.to_bytes(),
+        find_metadata_pda(mint).to_bytes(),
+        find_master_edition_pda(mint).to_bytes(),
+    ))
+}
+
+#[tokio::test]
+async fn burns_nft_and_charges_fee_on_reclaimed_rent() {
+    let mut pt = program_test_with_metaplex();
+    let user = add_wallet(&mut pt);
+    let (mint, token_account) = add_nft(&mut pt, &user.pubkey());
+    let mut ctx = pt.start_with_context().await;
+    let treasury_before = balance(&mut ctx, &treasury()).await;
+
+    let ix = burn_ix(&user.pubkey(), &mint, &token_account);
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    let token_rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let reclaimed = token_rent + METADATA_LAMPORTS + EDITION_LAMPORTS;
+    let fee = reclaimed * FEE_BPS / BPS_DENOMINATOR;
+    for account in [token_account, find_metadata_pda(&mint), find_master_edition_pda(&mint)] {
+        assert!(!account_exists(&mut ctx, &account).await);
+    }
+    assert_eq!(balance(&mut ctx, &treasury()).await, treasury_before + fee);
+    assert_eq!(balance(&mut ctx, &user.pubkey()).await, USER_STARTING_LAMPORTS + reclaimed - fee);
+}
+
+#[tokio::test]
+async fn rejects_metadata_of_another_mint() {
+    let mut pt = program_test_with_metaplex();
+    let user = add_wallet(&mut pt);
+    let (mint, token_account) = add_nft(&mut pt, &user.pubkey());
+    let (other_mint, _) = add_nft(&mut pt, &user.pubkey());
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = burn_ix(&user.pubkey(), &mint, &token_account);
+    ix.accounts[3].pubkey = find_metadata_pda(&other_mint);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidNft));
+}
+
+#[tokio::test]
+async fn rejects_empty_token_account() {
+    let mut pt = program_test_with_metaplex();
+    let user = add_wallet(&mut pt);
+    let (mint, _) = add_nft(&mut pt, &user.pubkey());
+    let empty = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = burn_ix(&user.pubkey(), &mint, &empty);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidNft));
+}
+
+#[tokio::test]
+async fn rejects_other_metadata_program() {
+    let mut pt = program_test_with_metaplex();
+    let user = add_wallet(&mut pt);
+    let (mint, token_account) = add_nft(&mut pt, &user.pubkey());
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = burn_ix(&user.pubkey(), &mint, &token_account);
+    ix.accounts[8].pubkey = Pubkey::new_unique();
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidMetadataProgram));
+}
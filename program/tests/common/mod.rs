@@ -0,0 +1,292 @@
+#![allow(dead_code)]
+
+use solana_program::bpf_loader_upgradeable;
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+use zera_program::error::JanitorError;
+use zera_program::state::{
+    find_config_pda, find_janitor_pda, find_program_data_address, find_user_stats_pda, find_vault_pda,
+    ProgramConfig, UserStats,
+};
+use zera_shared::{PROGRAM_ID, TREASURY_PUBKEY};
+
+pub const USER_STARTING_LAMPORTS: u64 = 1_000_000_000;
+
+pub fn program_id() -> Pubkey {
+    Pubkey::new_from_array(PROGRAM_ID)
+}
+
+pub fn vault() -> Pubkey {
+    find_vault_pda(&program_id()).0
+}
+
+pub fn config() -> Pubkey {
+    find_config_pda(&program_id()).0
+}
+
+pub fn janitor() -> Pubkey {
+    find_janitor_pda(&program_id()).0
+}
+
+pub fn treasury() -> Pubkey {
+    Pubkey::new_from_array(TREASURY_PUBKEY)
+}
+
+/// Program under test plus a rent-exempt vault and treasury.
+pub fn program_test() -> ProgramTest {
+    let mut pt = program_test_without_vault();
+    pt.add_account(
+        vault(),
+        Account {
+            lamports: Rent::default().minimum_balance(0),
+            owner: program_id(),
+            ..Account::default()
+        },
+    );
+    pt
+}
+
+/// Program under test plus a rent-exempt treasury; the vault is left uninitialized.
+pub fn program_test_without_vault() -> ProgramTest {
+    let mut pt = ProgramTest::new(
+        "zera_program",
+        program_id(),
+        processor!(zera_program::process_instruction),
+    );
+    pt.add_account(
+        treasury(),
+        Account {
+            lamports: Rent::default().minimum_balance(0),
+            ..Account::default()
+        },
+    );
+    pt
+}
+
+/// Add an initialized config PDA holding `state`.
+pub fn add_config(pt: &mut ProgramTest, state: ProgramConfig) {
+    pt.add_account(
+        config(),
+        Account {
+            lamports: Rent::default().minimum_balance(ProgramConfig::LEN),
+            data: state.pack(),
+            owner: program_id(),
+            ..Account::default()
+        },
+    );
+}
+
+/// Add `user`'s initialized stats PDA with `closed_accounts` lifetime volume.
+pub fn add_user_stats(pt: &mut ProgramTest, user: &Pubkey, closed_accounts: u64) -> Pubkey {
+    let address = find_user_stats_pda(&program_id(), user).0;
+    pt.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(UserStats::LEN),
+            data: UserStats { closed_accounts }.pack(),
+            owner: program_id(),
+            ..Account::default()
+        },
+    );
+    address
+}
+
+/// Add the program's `ProgramData` account with `authority` as upgrade authority.
+pub fn add_program_data(pt: &mut ProgramTest, authority: &Pubkey) -> Pubkey {
+    let address = find_program_data_address(&program_id());
+    pt.add_account(address, program_data_account(authority));
+    address
+}
+
+/// A loader-owned `ProgramData` account (no ELF bytes) naming `authority`.
+pub fn program_data_account(authority: &Pubkey) -> Account {
+    let mut data = Vec::with_capacity(45);
+    data.extend_from_slice(&3u32.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(authority.as_ref());
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: bpf_loader_upgradeable::id(),
+        ..Account::default()
+    }
+}
+
+/// Add a funded system account and return its keypair.
+pub fn add_wallet(pt: &mut ProgramTest) -> Keypair {
+    let wallet = Keypair::new();
+    pt.add_account(
+        wallet.pubkey(),
+        Account {
+            lamports: USER_STARTING_LAMPORTS,
+            ..Account::default()
+        },
+    );
+    wallet
+}
+
+/// Add an initialized SPL mint with `supply` tokens outstanding.
+pub fn add_mint(pt: &mut ProgramTest, supply: u64) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    let mut data = vec![0u8; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: Some(Pubkey::new_unique()).into(),
+        supply,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    }
+    .pack_into_slice(&mut data);
+    add_token_owned(pt, mint, data);
+    mint
+}
+
+/// Add an initialized SPL token account for `owner` holding `amount` of `mint`.
+pub fn add_token_account(pt: &mut ProgramTest, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+    add_token_account_with_close_authority(pt, mint, owner, amount, None)
+}
+
+/// Add `owner`'s associated token account for `mint`.
+pub fn add_associated_token_account(
+    pt: &mut ProgramTest,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let address = zera_program::state::find_associated_token_address(owner, mint);
+    add_token_account_at(pt, address, mint, owner, amount, None);
+    address
+}
+
+/// Add a wrapped SOL account holding `wrapped` lamports above its rent-exempt reserve.
+pub fn add_native_token_account(pt: &mut ProgramTest, owner: &Pubkey, wrapped: u64) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let reserve = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let mut data = vec![0u8; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: spl_token::native_mint::id(),
+        owner: *owner,
+        amount: wrapped,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: Some(reserve).into(),
+        ..spl_token::state::Account::default()
+    }
+    .pack_into_slice(&mut data);
+    pt.add_account(
+        address,
+        Account { lamports: reserve + wrapped, data, owner: spl_token::id(), ..Account::default() },
+    );
+    address
+}
+
+/// Add a token account whose close authority was delegated to the janitor PDA.
+pub fn add_delegated_token_account(
+    pt: &mut ProgramTest,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    add_token_account_with_close_authority(pt, mint, owner, amount, Some(janitor()))
+}
+
+fn add_token_account_with_close_authority(
+    pt: &mut ProgramTest,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+    close_authority: Option<Pubkey>,
+) -> Pubkey {
+    let address = Pubkey::new_unique();
+    add_token_account_at(pt, address, mint, owner, amount, close_authority);
+    address
+}
+
+fn add_token_account_at(
+    pt: &mut ProgramTest,
+    address: Pubkey,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+    close_authority: Option<Pubkey>,
+) {
+    let mut data = vec![0u8; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        close_authority: close_authority.into(),
+        ..spl_token::state::Account::default()
+    }
+    .pack_into_slice(&mut data);
+    add_token_owned(pt, address, data);
+}
+
+fn add_token_owned(pt: &mut ProgramTest, address: Pubkey, data: Vec<u8>) {
+    pt.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: spl_token::id(),
+            ..Account::default()
+        },
+    );
+}
+
+/// Convert a `zera-shared` instruction into an SDK instruction.
+pub fn to_sdk(ix: zera_shared::instruction::Instruction) -> Instruction {
+    Instruction {
+        program_id: Pubkey::new_from_array(ix.program_id),
+        accounts: ix
+            .accounts
+            .into_iter()
+            .map(|meta| AccountMeta {
+                pubkey: Pubkey::new_from_array(meta.pubkey),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: ix.data,
+    }
+}
+
+/// Send `ixs` paid by the context payer and signed by `signers`.
+pub async fn send(
+    ctx: &mut ProgramTestContext,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend_from_slice(signers);
+    let blockhash = ctx.banks_client.get_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(ixs, Some(&ctx.payer.pubkey()), &all_signers, blockhash);
+    ctx.banks_client.process_transaction(tx).await
+}
+
+pub async fn balance(ctx: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    ctx.banks_client.get_balance(*address).await.unwrap()
+}
+
+pub async fn account_exists(ctx: &mut ProgramTestContext, address: &Pubkey) -> bool {
+    ctx.banks_client.get_account(*address).await.unwrap().is_some()
+}
+
+/// Unwrap the instruction error of a failed single-instruction transaction.
+pub fn instruction_error(result: Result<(), BanksClientError>) -> InstructionError {
+    match result.expect_err("transaction should fail").unwrap() {
+        TransactionError::InstructionError(0, err) => err,
+        other => panic!("unexpected transaction error: {:?}", other),
+    }
+}
+
+pub fn janitor_error(err: JanitorError) -> InstructionError {
+    InstructionError::Custom(err as u32)
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use zera_program::error::JanitorError;
+use zera_shared::instruction::consolidate;
+use zera_shared::{BPS_DENOMINATOR, FEE_BPS};
+
+use common::*;
+
+fn consolidate_ix(user: &Pubkey, target: &Pubkey, auxiliaries: &[Pubkey]) -> Instruction {
+    let keys: Vec<[u8; 32]> = auxiliaries.iter().map(|k| k.to_bytes()).collect();
+    let ix = consolidate(user.to_bytes(), target.to_bytes(), vault().to_bytes(), config().to_bytes(), &keys);
+    to_sdk(ix.unwrap())
+}
+
+async fn token_amount(ctx: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    let account = ctx.banks_client.get_account(*address).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+#[tokio::test]
+async fn moves_balances_into_ata_and_closes_auxiliaries() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 100);
+    let ata = add_associated_token_account(&mut pt, &mint, &user.pubkey(), 10);
+    let auxiliaries = [
+        add_token_account(&mut pt, &mint, &user.pubkey(), 25),
+        add_token_account(&mut pt, &mint, &user.pubkey(), 0),
+        add_token_account(&mut pt, &mint, &user.pubkey(), 65),
+    ];
+    let mut ctx = pt.start_with_context().await;
+    let vault_before = balance(&mut ctx, &vault()).await;
+    let treasury_before = balance(&mut ctx, &treasury()).await;
+
+    let ix = consolidate_ix(&user.pubkey(), &ata, &auxiliaries);
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    let rent = 3 * Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let fee = rent * FEE_BPS / BPS_DENOMINATOR;
+    assert_eq!(token_amount(&mut ctx, &ata).await, 100);
+    for account in &auxiliaries {
+        assert!(!account_exists(&mut ctx, account).await);
+    }
+    assert_eq!(balance(&mut ctx, &vault()).await, vault_before);
+    assert_eq!(balance(&mut ctx, &treasury()).await, treasury_before + fee);
+    assert_eq!(balance(&mut ctx, &user.pubkey()).await, USER_STARTING_LAMPORTS + rent - fee);
+}
+
+#[tokio::test]
+async fn rejects_target_that_is_not_the_ata() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 10);
+    let target = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let aux = add_token_account(&mut pt, &mint, &user.pubkey(), 10);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = consolidate_ix(&user.pubkey(), &target, &[aux]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::NotAssociatedAccount));
+    assert_eq!(token_amount(&mut ctx, &aux).await, 10);
+}
+
+#[tokio::test]
+async fn rejects_auxiliary_of_another_mint() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let other = add_mint(&mut pt, 10);
+    let ata = add_associated_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let aux = add_token_account(&mut pt, &other, &user.pubkey(), 10);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = consolidate_ix(&user.pubkey(), &ata, &[aux]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::MintMismatch));
+}
+
+#[tokio::test]
+async fn rejects_target_listed_as_auxiliary() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 10);
+    let ata = add_associated_token_account(&mut pt, &mint, &user.pubkey(), 10);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = consolidate_ix(&user.pubkey(), &ata, &[ata]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::DuplicateAccount));
+}
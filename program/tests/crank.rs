@@ -0,0 +1,183 @@
+mod common;
+
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use zera_program::error::JanitorError;
+use zera_program::state::ProgramConfig;
+use zera_shared::instruction::{authorize, crank_clean, revoke, CrankTarget};
+use zera_shared::{BPS_DENOMINATOR, CRANK_REWARD_BPS, FEE_BPS};
+
+use common::*;
+
+fn authorize_ix(owner: &Pubkey, token_accounts: &[Pubkey]) -> Instruction {
+    let keys: Vec<[u8; 32]> = token_accounts.iter().map(|k| k.to_bytes()).collect();
+    to_sdk(authorize(owner.to_bytes(), janitor().to_bytes(), config().to_bytes(), &keys).unwrap())
+}
+
+fn revoke_ix(owner: &Pubkey, token_accounts: &[Pubkey]) -> Instruction {
+    let keys: Vec<[u8; 32]> = token_accounts.iter().map(|k| k.to_bytes()).collect();
+    to_sdk(revoke(owner.to_bytes(), janitor().to_bytes(), &keys).unwrap())
+}
+
+fn crank_ix(cranker: &Pubkey, targets: &[(Pubkey, Pubkey)]) -> Instruction {
+    let targets: Vec<CrankTarget> = targets
+        .iter()
+        .map(|(token_account, owner)| CrankTarget {
+            token_account: token_account.to_bytes(),
+            owner: owner.to_bytes(),
+        })
+        .collect();
+    to_sdk(crank_clean(
+        cranker.to_bytes(),
+        vault().to_bytes(),
+        config().to_bytes(),
+        janitor().to_bytes(),
+        &targets,
+    )
+    .unwrap())
+}
+
+async fn close_authority(ctx: &mut ProgramTestContext, address: &Pubkey) -> COption<Pubkey> {
+    let account = ctx.banks_client.get_account(*address).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().close_authority
+}
+
+fn token_rent() -> u64 {
+    Rent::default().minimum_balance(spl_token::state::Account::LEN)
+}
+
+#[tokio::test]
+async fn authorize_delegates_close_authority() {
+    let mut pt = program_test();
+    let owner = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 1_000);
+    let accounts = [
+        add_token_account(&mut pt, &mint, &owner.pubkey(), 0),
+        add_token_account(&mut pt, &mint, &owner.pubkey(), 500),
+    ];
+    let mut ctx = pt.start_with_context().await;
+
+    send(&mut ctx, &[authorize_ix(&owner.pubkey(), &accounts)], &[&owner]).await.unwrap();
+
+    for account in &accounts {
+        assert_eq!(close_authority(&mut ctx, account).await, COption::Some(janitor()));
+    }
+}
+
+#[tokio::test]
+async fn authorize_rejects_foreign_account() {
+    let mut pt = program_test();
+    let owner = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &Pubkey::new_unique(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = authorize_ix(&owner.pubkey(), &[account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&owner]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::TokenOwnerMismatch));
+}
+
+#[tokio::test]
+async fn crank_pays_owners_treasury_and_cranker() {
+    let mut pt = program_test();
+    let cranker = add_wallet(&mut pt);
+    let alice = add_wallet(&mut pt);
+    let bob = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let alice_accounts = [
+        add_delegated_token_account(&mut pt, &mint, &alice.pubkey(), 0),
+        add_delegated_token_account(&mut pt, &mint, &alice.pubkey(), 0),
+    ];
+    let bob_account = add_delegated_token_account(&mut pt, &mint, &bob.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+    let treasury_before = balance(&mut ctx, &treasury()).await;
+    let vault_before = balance(&mut ctx, &vault()).await;
+
+    let targets = [
+        (alice_accounts[0], alice.pubkey()),
+        (bob_account, bob.pubkey()),
+        (alice_accounts[1], alice.pubkey()),
+    ];
+    send(&mut ctx, &[crank_ix(&cranker.pubkey(), &targets)], &[&cranker]).await.unwrap();
+
+    let fee = token_rent() * FEE_BPS / BPS_DENOMINATOR;
+    let reward = token_rent() * CRANK_REWARD_BPS / BPS_DENOMINATOR;
+    let payout = token_rent() - fee - reward;
+    for (account, _) in &targets {
+        assert!(!account_exists(&mut ctx, account).await);
+    }
+    assert_eq!(balance(&mut ctx, &alice.pubkey()).await, USER_STARTING_LAMPORTS + 2 * payout);
+    assert_eq!(balance(&mut ctx, &bob.pubkey()).await, USER_STARTING_LAMPORTS + payout);
+    assert_eq!(balance(&mut ctx, &cranker.pubkey()).await, USER_STARTING_LAMPORTS + 3 * reward);
+    assert_eq!(balance(&mut ctx, &treasury()).await, treasury_before + 3 * fee);
+    assert_eq!(balance(&mut ctx, &vault()).await, vault_before);
+}
+
+#[tokio::test]
+async fn crank_rejects_account_not_delegated() {
+    let mut pt = program_test();
+    let cranker = add_wallet(&mut pt);
+    let owner = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &owner.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = crank_ix(&cranker.pubkey(), &[(account, owner.pubkey())]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&cranker]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::NotDelegated));
+}
+
+#[tokio::test]
+async fn crank_rejects_non_empty_account() {
+    let mut pt = program_test();
+    let cranker = add_wallet(&mut pt);
+    let owner = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 10);
+    let account = add_delegated_token_account(&mut pt, &mint, &owner.pubkey(), 10);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = crank_ix(&cranker.pubkey(), &[(account, owner.pubkey())]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&cranker]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::NonZeroBalance));
+}
+
+#[tokio::test]
+async fn crank_rejects_payout_to_someone_else() {
+    let mut pt = program_test();
+    let cranker = add_wallet(&mut pt);
+    let owner = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_delegated_token_account(&mut pt, &mint, &owner.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = crank_ix(&cranker.pubkey(), &[(account, cranker.pubkey())]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&cranker]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::TokenOwnerMismatch));
+}
+
+#[tokio::test]
+async fn revoke_works_while_paused_and_stops_crank() {
+    let mut pt = program_test();
+    add_config(&mut pt, ProgramConfig { paused: true, ..Default::default() });
+    let cranker = add_wallet(&mut pt);
+    let owner = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_delegated_token_account(&mut pt, &mint, &owner.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = crank_ix(&cranker.pubkey(), &[(account, owner.pubkey())]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&cranker]).await);
+    assert_eq!(err, janitor_error(JanitorError::ProgramPaused));
+
+    send(&mut ctx, &[revoke_ix(&owner.pubkey(), &[account])], &[&owner]).await.unwrap();
+    assert_eq!(close_authority(&mut ctx, &account).await, COption::None);
+}
@@ -0,0 +1,247 @@
+mod common;
+
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_program_test::{tokio, ProgramTest};
+use solana_sdk::account::Account;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use zera_program::error::JanitorError;
+use zera_program::state::{find_user_stats_pda, FeeTier, ProgramConfig, UserStats, MAX_FEE_TIERS};
+use zera_shared::instruction::{batch_clean, init_user_stats, set_fee_tiers, BatchCleanOptions};
+use zera_shared::{BPS_DENOMINATOR, FEE_BPS};
+
+use common::*;
+
+const VOLUME_TIER: FeeTier = FeeTier { min_closed: 1_000, min_holding: 0, fee_bps: 300 };
+const HOLDER_TIER: FeeTier = FeeTier { min_closed: 0, min_holding: 5_000, fee_bps: 200 };
+
+fn tiered_config(governance_mint: &Pubkey) -> ProgramConfig {
+    let mut config = ProgramConfig { governance_mint: governance_mint.to_bytes(), ..Default::default() };
+    config.fee_tiers[0] = VOLUME_TIER;
+    config.fee_tiers[1] = HOLDER_TIER;
+    config
+}
+
+fn batch_clean_with(user: &Pubkey, options: BatchCleanOptions, token_accounts: &[Pubkey]) -> Instruction {
+    let keys: Vec<[u8; 32]> = token_accounts.iter().map(|k| k.to_bytes()).collect();
+    to_sdk(batch_clean(user.to_bytes(), vault().to_bytes(), config().to_bytes(), &options, &keys))
+}
+
+fn user_stats_options(user_stats: &Pubkey) -> BatchCleanOptions {
+    BatchCleanOptions { user_stats: Some(user_stats.to_bytes()), ..Default::default() }
+}
+
+fn governance_options(account: &Pubkey) -> BatchCleanOptions {
+    BatchCleanOptions { governance_account: Some(account.to_bytes()), ..Default::default() }
+}
+
+/// Add a user, one empty token account and the tiered config.
+fn setup() -> (ProgramTest, Keypair, Pubkey, Pubkey) {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let governance_mint = add_mint(&mut pt, 1_000_000);
+    add_config(&mut pt, tiered_config(&governance_mint));
+    (pt, user, account, governance_mint)
+}
+
+fn expected_payout(fee_bps: u64) -> u64 {
+    let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    rent - rent * fee_bps / BPS_DENOMINATOR
+}
+
+async fn user_stats(ctx: &mut solana_program_test::ProgramTestContext, address: &Pubkey) -> UserStats {
+    let account = ctx.banks_client.get_account(*address).await.unwrap().unwrap();
+    UserStats::unpack(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn init_user_stats_creates_pda() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mut ctx = pt.start_with_context().await;
+    let address = find_user_stats_pda(&program_id(), &user.pubkey()).0;
+
+    let ix = to_sdk(init_user_stats(user.pubkey().to_bytes(), address.to_bytes(), config().to_bytes()));
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    assert_eq!(user_stats(&mut ctx, &address).await, UserStats::default());
+}
+
+#[tokio::test]
+async fn init_user_stats_twice_fails() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let address = add_user_stats(&mut pt, &user.pubkey(), 7);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = to_sdk(init_user_stats(user.pubkey().to_bytes(), address.to_bytes(), config().to_bytes()));
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::UserStatsAlreadyInitialized));
+    assert_eq!(user_stats(&mut ctx, &address).await.closed_accounts, 7);
+}
+
+#[tokio::test]
+async fn init_user_stats_succeeds_on_prefunded_pda() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let address = find_user_stats_pda(&program_id(), &user.pubkey()).0;
+    pt.add_account(address, Account { lamports: 1, ..Account::default() });
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = to_sdk(init_user_stats(user.pubkey().to_bytes(), address.to_bytes(), config().to_bytes()));
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    let account = ctx.banks_client.get_account(address).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id());
+    assert_eq!(account.lamports, Rent::default().minimum_balance(UserStats::LEN));
+}
+
+#[tokio::test]
+async fn counts_volume_at_standard_fee_below_tier() {
+    let (mut pt, user, account, _) = setup();
+    let stats = add_user_stats(&mut pt, &user.pubkey(), 998);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_with(&user.pubkey(), user_stats_options(&stats), &[account]);
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    assert_eq!(user_stats(&mut ctx, &stats).await.closed_accounts, 999);
+    assert_eq!(
+        balance(&mut ctx, &user.pubkey()).await,
+        USER_STARTING_LAMPORTS + expected_payout(FEE_BPS)
+    );
+}
+
+#[tokio::test]
+async fn applies_volume_tier() {
+    let (mut pt, user, account, _) = setup();
+    let stats = add_user_stats(&mut pt, &user.pubkey(), 1_000);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_with(&user.pubkey(), user_stats_options(&stats), &[account]);
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    assert_eq!(user_stats(&mut ctx, &stats).await.closed_accounts, 1_001);
+    assert_eq!(
+        balance(&mut ctx, &user.pubkey()).await,
+        USER_STARTING_LAMPORTS + expected_payout(VOLUME_TIER.fee_bps as u64)
+    );
+}
+
+#[tokio::test]
+async fn applies_holder_tier() {
+    let (mut pt, user, account, governance_mint) = setup();
+    let holding = add_token_account(&mut pt, &governance_mint, &user.pubkey(), 5_000);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_with(&user.pubkey(), governance_options(&holding), &[account]);
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    assert_eq!(
+        balance(&mut ctx, &user.pubkey()).await,
+        USER_STARTING_LAMPORTS + expected_payout(HOLDER_TIER.fee_bps as u64)
+    );
+}
+
+#[tokio::test]
+async fn rejects_governance_account_of_other_mint() {
+    let (mut pt, user, account, _) = setup();
+    let other_mint = add_mint(&mut pt, 1_000_000);
+    let holding = add_token_account(&mut pt, &other_mint, &user.pubkey(), 5_000);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_with(&user.pubkey(), governance_options(&holding), &[account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidGovernanceAccount));
+}
+
+#[tokio::test]
+async fn rejects_governance_account_of_other_owner() {
+    let (mut pt, user, account, governance_mint) = setup();
+    let holding = add_token_account(&mut pt, &governance_mint, &Pubkey::new_unique(), 5_000);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_with(&user.pubkey(), governance_options(&holding), &[account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidGovernanceAccount));
+}
+
+#[tokio::test]
+async fn rejects_another_users_stats() {
+    let (mut pt, user, account, _) = setup();
+    let whale = Pubkey::new_unique();
+    let stats = add_user_stats(&mut pt, &whale, 1_000_000);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_with(&user.pubkey(), user_stats_options(&stats), &[account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidUserStats));
+}
+
+#[tokio::test]
+async fn set_fee_tiers_grows_legacy_config() {
+    let mut pt = program_test();
+    let admin = add_wallet(&mut pt);
+    let program_data = add_program_data(&mut pt, &admin.pubkey());
+    let mut legacy = vec![0u8; 64];
+    legacy[..2].copy_from_slice(&1_000u16.to_le_bytes());
+    pt.add_account(
+        config(),
+        Account {
+            lamports: Rent::default().minimum_balance(legacy.len()),
+            data: legacy,
+            owner: program_id(),
+            ..Account::default()
+        },
+    );
+    let mut ctx = pt.start_with_context().await;
+
+    let governance_mint = Pubkey::new_unique();
+    let mut tiers = [FeeTier::default(); MAX_FEE_TIERS];
+    tiers[0] = VOLUME_TIER;
+    let ix = to_sdk(set_fee_tiers(
+        admin.pubkey().to_bytes(),
+        program_data.to_bytes(),
+        config().to_bytes(),
+        governance_mint.to_bytes(),
+        tiers,
+    ));
+    send(&mut ctx, &[ix], &[&admin]).await.unwrap();
+
+    let account = ctx.banks_client.get_account(config()).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), ProgramConfig::LEN);
+    assert_eq!(account.lamports, Rent::default().minimum_balance(ProgramConfig::LEN));
+    let state = ProgramConfig::unpack(&account.data).unwrap();
+    assert_eq!(state.referral_bps, 1_000);
+    assert_eq!(state.governance_mint, governance_mint.to_bytes());
+    assert_eq!(state.fee_tiers, tiers);
+}
+
+#[tokio::test]
+async fn set_fee_tiers_rejects_tier_above_standard_fee() {
+    let mut pt = program_test();
+    let admin = add_wallet(&mut pt);
+    let program_data = add_program_data(&mut pt, &admin.pubkey());
+    let mut ctx = pt.start_with_context().await;
+
+    let mut tiers = [FeeTier::default(); MAX_FEE_TIERS];
+    tiers[0] = FeeTier { min_closed: 10, min_holding: 0, fee_bps: FEE_BPS as u16 + 1 };
+    let ix = to_sdk(set_fee_tiers(
+        admin.pubkey().to_bytes(),
+        program_data.to_bytes(),
+        config().to_bytes(),
+        [1u8; 32],
+        tiers,
+    ));
+    let err = instruction_error(send(&mut ctx, &[ix], &[&admin]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidFeeTier));
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use solana_program::instruction::InstructionError;
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_program_test::{tokio, BanksClientError};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::TransactionError;
+use zera_program::error::JanitorError;
+use zera_program::state::ProgramConfig;
+use zera_shared::instruction::intent_clean;
+use zera_shared::intent::{ed25519_instruction_data, CleanIntent};
+use zera_shared::{BPS_DENOMINATOR, FEE_BPS};
+
+use common::*;
+
+fn intent(user: &Pubkey, destination: &Pubkey, mints: &[Pubkey]) -> CleanIntent {
+    CleanIntent {
+        user: user.to_bytes(),
+        destination: destination.to_bytes(),
+        allowed_mints: mints.iter().map(|m| m.to_bytes()).collect(),
+        expires_at: i64::MAX,
+    }
+}
+
+/// The Ed25519 verification of `intent` signed by `signer`, then `IntentClean`.
+fn intent_ixs(
+    submitter: &Pubkey,
+    signer: &Keypair,
+    intent: &CleanIntent,
+    token_accounts: &[Pubkey],
+) -> Vec<Instruction> {
+    let message = intent.message();
+    let signature: [u8; 64] = signer.sign_message(&message).into();
+    let verify = ed25519_instruction_data(&signer.pubkey().to_bytes(), &signature, &message);
+    let keys: Vec<[u8; 32]> = token_accounts.iter().map(|k| k.to_bytes()).collect();
+    vec![
+        Instruction::new_with_bytes(solana_sdk::ed25519_program::id(), &verify, vec![]),
+        to_sdk(intent_clean(
+            submitter.to_bytes(),
+            vault().to_bytes(),
+            config().to_bytes(),
+            janitor().to_bytes(),
+            intent,
+            &keys,
+        )
+        .unwrap()),
+    ]
+}
+
+/// Unwrap the error of the `IntentClean` instruction, second in the transaction.
+fn intent_error(result: Result<(), BanksClientError>) -> InstructionError {
+    match result.expect_err("transaction should fail").unwrap() {
+        TransactionError::InstructionError(1, err) => err,
+        other => panic!("unexpected transaction error: {:?}", other),
+    }
+}
+
+fn token_rent() -> u64 {
+    Rent::default().minimum_balance(spl_token::state::Account::LEN)
+}
+
+#[tokio::test]
+async fn relayer_cleans_with_signed_intent() {
+    let mut pt = program_test();
+    let relayer = add_wallet(&mut pt);
+    let user = add_wallet(&mut pt);
+    let destination = Pubkey::new_unique();
+    let mint = add_mint(&mut pt, 0);
+    let accounts = [
+        add_delegated_token_account(&mut pt, &mint, &user.pubkey(), 0),
+        add_delegated_token_account(&mut pt, &mint, &user.pubkey(), 0),
+    ];
+    let mut ctx = pt.start_with_context().await;
+    let treasury_before = balance(&mut ctx, &treasury()).await;
+    let vault_before = balance(&mut ctx, &vault()).await;
+
+    let intent = intent(&user.pubkey(), &destination, &[mint]);
+    let ixs = intent_ixs(&relayer.pubkey(), &user, &intent, &accounts);
+    send(&mut ctx, &ixs, &[&relayer]).await.unwrap();
+
+    let rent = 2 * token_rent();
+    let fee = rent * FEE_BPS / BPS_DENOMINATOR;
+    for account in &accounts {
+        assert!(!account_exists(&mut ctx, account).await);
+    }
+    assert_eq!(balance(&mut ctx, &destination).await, rent - fee);
+    assert_eq!(balance(&mut ctx, &treasury()).await, treasury_before + fee);
+    assert_eq!(balance(&mut ctx, &vault()).await, vault_before);
+    assert_eq!(balance(&mut ctx, &user.pubkey()).await, USER_STARTING_LAMPORTS);
+}
+
+#[tokio::test]
+async fn rejects_intent_signed_by_someone_else() {
+    let mut pt = program_test();
+    let relayer = add_wallet(&mut pt);
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_delegated_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let intent = intent(&user.pubkey(), &relayer.pubkey(), &[mint]);
+    // The relayer's own valid signature over the user's intent
+    let ixs = intent_ixs(&relayer.pubkey(), &relayer, &intent, &[account]);
+    let err = intent_error(send(&mut ctx, &ixs, &[&relayer]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidIntent));
+}
+
+#[tokio::test]
+async fn rejects_missing_signature_instruction() {
+    let mut pt = program_test();
+    let relayer = add_wallet(&mut pt);
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_delegated_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let intent = intent(&user.pubkey(), &user.pubkey(), &[mint]);
+    let ix = intent_ixs(&relayer.pubkey(), &user, &intent, &[account]).remove(1);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&relayer]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidIntent));
+}
+
+#[tokio::test]
+async fn rejects_expired_intent() {
+    let mut pt = program_test();
+    let relayer = add_wallet(&mut pt);
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_delegated_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let intent = CleanIntent { expires_at: 0, ..intent(&user.pubkey(), &user.pubkey(), &[mint]) };
+    let ixs = intent_ixs(&relayer.pubkey(), &user, &intent, &[account]);
+    let err = intent_error(send(&mut ctx, &ixs, &[&relayer]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::IntentExpired));
+}
+
+#[tokio::test]
+async fn rejects_mint_outside_intent() {
+    let mut pt = program_test();
+    let relayer = add_wallet(&mut pt);
+    let user = add_wallet(&mut pt);
+    let allowed = add_mint(&mut pt, 0);
+    let other = add_mint(&mut pt, 0);
+    let account = add_delegated_token_account(&mut pt, &other, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let intent = intent(&user.pubkey(), &user.pubkey(), &[allowed]);
+    let ixs = intent_ixs(&relayer.pubkey(), &user, &intent, &[account]);
+    let err = intent_error(send(&mut ctx, &ixs, &[&relayer]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::MintNotAllowed));
+}
+
+#[tokio::test]
+async fn rejects_destination_other_than_intent() {
+    let mut pt = program_test();
+    let relayer = add_wallet(&mut pt);
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_delegated_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let intent = intent(&user.pubkey(), &user.pubkey(), &[mint]);
+    let mut ixs = intent_ixs(&relayer.pubkey(), &user, &intent, &[account]);
+    ixs[1].accounts[1].pubkey = relayer.pubkey();
+    let err = intent_error(send(&mut ctx, &ixs, &[&relayer]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidPayoutDestination));
+}
+
+#[tokio::test]
+async fn rejects_intent_while_paused() {
+    let mut pt = program_test();
+    add_config(&mut pt, ProgramConfig { paused: true, ..Default::default() });
+    let relayer = add_wallet(&mut pt);
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_delegated_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let intent = intent(&user.pubkey(), &user.pubkey(), &[mint]);
+    let ixs = intent_ixs(&relayer.pubkey(), &user, &intent, &[account]);
+    let err = intent_error(send(&mut ctx, &ixs, &[&relayer]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::ProgramPaused));
+}
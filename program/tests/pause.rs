@@ -0,0 +1,118 @@
+mod common;
+
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use zera_program::error::JanitorError;
+use zera_program::state::{find_user_stats_pda, ProgramConfig};
+use zera_shared::instruction::{
+    batch_clean, init_user_stats, initialize_vault, set_paused, sweep_vault, BatchCleanOptions,
+};
+
+use common::*;
+
+fn paused_config() -> ProgramConfig {
+    ProgramConfig { paused: true, ..Default::default() }
+}
+
+fn batch_clean_ix(user: &Pubkey, token_accounts: &[Pubkey]) -> Instruction {
+    let keys: Vec<[u8; 32]> = token_accounts.iter().map(|k| k.to_bytes()).collect();
+    let options = BatchCleanOptions::default();
+    to_sdk(batch_clean(user.to_bytes(), vault().to_bytes(), config().to_bytes(), &options, &keys))
+}
+
+fn set_paused_ix(admin: &Pubkey, program_data: &Pubkey, paused: bool) -> Instruction {
+    to_sdk(set_paused(admin.to_bytes(), program_data.to_bytes(), config().to_bytes(), paused))
+}
+
+#[tokio::test]
+async fn batch_clean_fails_while_paused() {
+    let mut pt = program_test();
+    add_config(&mut pt, paused_config());
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_ix(&user.pubkey(), &[account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::ProgramPaused));
+    assert!(account_exists(&mut ctx, &account).await);
+}
+
+#[tokio::test]
+async fn init_user_stats_fails_while_paused() {
+    let mut pt = program_test();
+    add_config(&mut pt, paused_config());
+    let user = add_wallet(&mut pt);
+    let mut ctx = pt.start_with_context().await;
+
+    let user_stats = find_user_stats_pda(&program_id(), &user.pubkey()).0;
+    let ix = to_sdk(init_user_stats(user.pubkey().to_bytes(), user_stats.to_bytes(), config().to_bytes()));
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::ProgramPaused));
+}
+
+#[tokio::test]
+async fn initialize_vault_fails_while_paused() {
+    let mut pt = program_test_without_vault();
+    add_config(&mut pt, paused_config());
+    let payer = add_wallet(&mut pt);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = to_sdk(initialize_vault(payer.pubkey().to_bytes(), vault().to_bytes(), config().to_bytes()));
+    let err = instruction_error(send(&mut ctx, &[ix], &[&payer]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::ProgramPaused));
+}
+
+#[tokio::test]
+async fn admin_pauses_and_resumes() {
+    let mut pt = program_test();
+    let admin = add_wallet(&mut pt);
+    let program_data = add_program_data(&mut pt, &admin.pubkey());
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    send(&mut ctx, &[set_paused_ix(&admin.pubkey(), &program_data, true)], &[&admin]).await.unwrap();
+    let state = ctx.banks_client.get_account(config()).await.unwrap().unwrap();
+    assert!(ProgramConfig::unpack(&state.data).unwrap().paused);
+    let ix = batch_clean_ix(&user.pubkey(), &[account]);
+    let err = instruction_error(send(&mut ctx, std::slice::from_ref(&ix), &[&user]).await);
+    assert_eq!(err, janitor_error(JanitorError::ProgramPaused));
+
+    send(&mut ctx, &[set_paused_ix(&admin.pubkey(), &program_data, false)], &[&admin]).await.unwrap();
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+    assert!(!account_exists(&mut ctx, &account).await);
+}
+
+#[tokio::test]
+async fn admin_can_sweep_while_paused() {
+    let mut pt = program_test();
+    add_config(&mut pt, paused_config());
+    let admin = add_wallet(&mut pt);
+    let program_data = add_program_data(&mut pt, &admin.pubkey());
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = to_sdk(sweep_vault(admin.pubkey().to_bytes(), program_data.to_bytes(), vault().to_bytes()));
+    send(&mut ctx, &[ix], &[&admin]).await.unwrap();
+}
+
+#[tokio::test]
+async fn set_paused_rejects_non_admin() {
+    let mut pt = program_test();
+    let admin = add_wallet(&mut pt);
+    let program_data = add_program_data(&mut pt, &admin.pubkey());
+    let impostor = add_wallet(&mut pt);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = set_paused_ix(&impostor.pubkey(), &program_data, true);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&impostor]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::Unauthorized));
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use zera_program::error::JanitorError;
+use zera_shared::instruction::{batch_clean, BatchCleanOptions};
+use zera_shared::{BPS_DENOMINATOR, FEE_BPS};
+
+use common::*;
+
+fn batch_clean_to(user: &Pubkey, destination: &Pubkey, token_accounts: &[Pubkey]) -> Instruction {
+    let keys: Vec<[u8; 32]> = token_accounts.iter().map(|k| k.to_bytes()).collect();
+    let options = BatchCleanOptions { destination: Some(destination.to_bytes()), ..Default::default() };
+    to_sdk(batch_clean(user.to_bytes(), vault().to_bytes(), config().to_bytes(), &options, &keys))
+}
+
+#[tokio::test]
+async fn pays_out_to_destination() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let cold = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let accounts: Vec<Pubkey> = (0..2)
+        .map(|_| add_token_account(&mut pt, &mint, &user.pubkey(), 0))
+        .collect();
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_to(&user.pubkey(), &cold.pubkey(), &accounts);
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    let rent = 2 * Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let fee = rent * FEE_BPS / BPS_DENOMINATOR;
+    assert_eq!(balance(&mut ctx, &user.pubkey()).await, USER_STARTING_LAMPORTS);
+    assert_eq!(balance(&mut ctx, &cold.pubkey()).await, USER_STARTING_LAMPORTS + rent - fee);
+}
+
+#[tokio::test]
+async fn rejects_vault_as_destination() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_to(&user.pubkey(), &vault(), &[account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidPayoutDestination));
+}
+
+#[tokio::test]
+async fn rejects_closed_account_as_destination() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_to(&user.pubkey(), &account, &[account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidPayoutDestination));
+}
+
+#[tokio::test]
+async fn rejects_readonly_destination() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = batch_clean_to(&user.pubkey(), &Pubkey::new_unique(), &[account]);
+    ix.accounts[5].is_writable = false;
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::AccountNotWritable));
+}
+
+#[tokio::test]
+async fn missing_destination_shifts_token_accounts() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = batch_clean_to(&user.pubkey(), &Pubkey::new_unique(), &[account]);
+    ix.accounts.remove(5);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::AccountCountMismatch));
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use zera_program::error::JanitorError;
+use zera_program::state::ProgramConfig;
+use zera_shared::instruction::{batch_clean, set_config, BatchCleanOptions};
+use zera_shared::{BPS_DENOMINATOR, FEE_BPS};
+
+use common::*;
+
+fn batch_clean_referred(user: &Pubkey, referrer: &Pubkey, token_accounts: &[Pubkey]) -> Instruction {
+    let keys: Vec<[u8; 32]> = token_accounts.iter().map(|k| k.to_bytes()).collect();
+    let options = BatchCleanOptions { referrer: Some(referrer.to_bytes()), ..Default::default() };
+    to_sdk(batch_clean(user.to_bytes(), vault().to_bytes(), config().to_bytes(), &options, &keys))
+}
+
+fn set_config_ix(admin: &Pubkey, program_data: &Pubkey, referral_bps: u16) -> Instruction {
+    to_sdk(set_config(admin.to_bytes(), program_data.to_bytes(), config().to_bytes(), referral_bps))
+}
+
+#[tokio::test]
+async fn splits_fee_with_referrer() {
+    let mut pt = program_test();
+    add_config(&mut pt, ProgramConfig { referral_bps: 2_000, ..Default::default() });
+    let user = add_wallet(&mut pt);
+    let referrer = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let accounts: Vec<Pubkey> = (0..3)
+        .map(|_| add_token_account(&mut pt, &mint, &user.pubkey(), 0))
+        .collect();
+    let mut ctx = pt.start_with_context().await;
+    let treasury_before = balance(&mut ctx, &treasury()).await;
+
+    let ix = batch_clean_referred(&user.pubkey(), &referrer.pubkey(), &accounts);
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    let rent = 3 * Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let fee = rent * FEE_BPS / BPS_DENOMINATOR;
+    let referral_fee = fee * 2_000 / BPS_DENOMINATOR;
+    assert_eq!(balance(&mut ctx, &referrer.pubkey()).await, USER_STARTING_LAMPORTS + referral_fee);
+    assert_eq!(balance(&mut ctx, &treasury()).await, treasury_before + fee - referral_fee);
+    assert_eq!(balance(&mut ctx, &user.pubkey()).await, USER_STARTING_LAMPORTS + rent - fee);
+}
+
+#[tokio::test]
+async fn referrer_gets_nothing_without_config() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let referrer = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+    let treasury_before = balance(&mut ctx, &treasury()).await;
+
+    let ix = batch_clean_referred(&user.pubkey(), &referrer.pubkey(), &[account]);
+    send(&mut ctx, &[ix], &[&user]).await.unwrap();
+
+    let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    assert_eq!(balance(&mut ctx, &referrer.pubkey()).await, USER_STARTING_LAMPORTS);
+    assert_eq!(balance(&mut ctx, &treasury()).await, treasury_before + rent * FEE_BPS / BPS_DENOMINATOR);
+}
+
+#[tokio::test]
+async fn rejects_self_referral() {
+    let mut pt = program_test();
+    add_config(&mut pt, ProgramConfig { referral_bps: 5_000, ..Default::default() });
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = batch_clean_referred(&user.pubkey(), &user.pubkey(), &[account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidReferrer));
+}
+
+#[tokio::test]
+async fn rejects_wrong_config_account() {
+    let mut pt = program_test();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = batch_clean_referred(&user.pubkey(), &Pubkey::new_unique(), &[account]);
+    ix.accounts[4].pubkey = Pubkey::new_unique();
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidConfigPda));
+}
+
+#[tokio::test]
+async fn set_config_creates_then_updates_config() {
+    let mut pt = program_test();
+    let admin = add_wallet(&mut pt);
+    let program_data = add_program_data(&mut pt, &admin.pubkey());
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = set_config_ix(&admin.pubkey(), &program_data, 1_500);
+    send(&mut ctx, &[ix], &[&admin]).await.unwrap();
+    let account = ctx.banks_client.get_account(config()).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id());
+    assert_eq!(ProgramConfig::unpack(&account.data), Some(ProgramConfig { referral_bps: 1_500, ..Default::default() }));
+
+    let ix = set_config_ix(&admin.pubkey(), &program_data, 3_000);
+    send(&mut ctx, &[ix], &[&admin]).await.unwrap();
+    let account = ctx.banks_client.get_account(config()).await.unwrap().unwrap();
+    assert_eq!(ProgramConfig::unpack(&account.data), Some(ProgramConfig { referral_bps: 3_000, ..Default::default() }));
+}
+
+#[tokio::test]
+async fn set_config_rejects_non_admin() {
+    let mut pt = program_test();
+    let admin = add_wallet(&mut pt);
+    let program_data = add_program_data(&mut pt, &admin.pubkey());
+    let impostor = add_wallet(&mut pt);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = set_config_ix(&impostor.pubkey(), &program_data, 1_000);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&impostor]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::Unauthorized));
+}
+
+#[tokio::test]
+async fn set_config_rejects_share_above_fee() {
+    let mut pt = program_test();
+    let admin = add_wallet(&mut pt);
+    let program_data = add_program_data(&mut pt, &admin.pubkey());
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = set_config_ix(&admin.pubkey(), &program_data, 10_001);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&admin]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::InvalidReferralBps));
+}
+
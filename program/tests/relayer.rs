@@ -0,0 +1,95 @@
+mod common;
+
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_program_test::{tokio, BanksClientError, ProgramTestContext};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use zera_program::error::JanitorError;
+use zera_shared::instruction::{batch_clean, BatchCleanOptions};
+use zera_shared::{BPS_DENOMINATOR, FEE_BPS, MAX_RELAYER_FEE};
+
+use common::*;
+
+fn relayed_clean(
+    user: &Pubkey,
+    relayer: &Pubkey,
+    relayer_fee: u64,
+    token_accounts: &[Pubkey],
+) -> Instruction {
+    let keys: Vec<[u8; 32]> = token_accounts.iter().map(|k| k.to_bytes()).collect();
+    let options =
+        BatchCleanOptions { relayer: Some((relayer.to_bytes(), relayer_fee)), ..Default::default() };
+    to_sdk(batch_clean(user.to_bytes(), vault().to_bytes(), config().to_bytes(), &options, &keys))
+}
+
+/// Send `ix` with `relayer` paying the transaction fee; returns that fee.
+async fn send_relayed(
+    ctx: &mut ProgramTestContext,
+    relayer: &Keypair,
+    user: &Keypair,
+    ix: Instruction,
+) -> Result<u64, BanksClientError> {
+    let blockhash = ctx.banks_client.get_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&relayer.pubkey()), &[relayer, user], blockhash);
+    let network_fee = ctx.banks_client.get_fee_for_message(tx.message.clone()).await?.unwrap_or(0);
+    ctx.banks_client.process_transaction(tx).await?;
+    Ok(network_fee)
+}
+
+#[tokio::test]
+async fn relayer_pays_and_is_reimbursed_for_wallet_without_sol() {
+    let mut pt = program_test();
+    let relayer = add_wallet(&mut pt);
+    let user = Keypair::new();
+    let mint = add_mint(&mut pt, 0);
+    let accounts: Vec<Pubkey> = (0..2)
+        .map(|_| add_token_account(&mut pt, &mint, &user.pubkey(), 0))
+        .collect();
+    let mut ctx = pt.start_with_context().await;
+
+    let relayer_fee = 10_000;
+    let ix = relayed_clean(&user.pubkey(), &relayer.pubkey(), relayer_fee, &accounts);
+    let network_fee = send_relayed(&mut ctx, &relayer, &user, ix).await.unwrap();
+
+    let rent = 2 * Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let fee = rent * FEE_BPS / BPS_DENOMINATOR;
+    assert_eq!(balance(&mut ctx, &user.pubkey()).await, rent - fee - relayer_fee);
+    assert_eq!(
+        balance(&mut ctx, &relayer.pubkey()).await,
+        USER_STARTING_LAMPORTS - network_fee + relayer_fee
+    );
+}
+
+#[tokio::test]
+async fn rejects_reimbursement_above_cap() {
+    let mut pt = program_test();
+    let relayer = add_wallet(&mut pt);
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = relayed_clean(&user.pubkey(), &relayer.pubkey(), MAX_RELAYER_FEE + 1, &[account]);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user, &relayer]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::RelayerFeeTooHigh));
+}
+
+#[tokio::test]
+async fn rejects_fee_payer_that_did_not_sign() {
+    let mut pt = program_test();
+    let relayer = add_wallet(&mut pt);
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = relayed_clean(&user.pubkey(), &relayer.pubkey(), MAX_RELAYER_FEE, &[account]);
+    ix.accounts[5].is_signer = false;
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::MissingSigner));
+}
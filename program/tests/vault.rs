@@ -0,0 +1,143 @@
+mod common;
+
+use solana_program::rent::Rent;
+use solana_program_test::tokio;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use zera_program::error::JanitorError;
+use zera_shared::instruction::{batch_clean, initialize_vault, sweep_vault, BatchCleanOptions};
+
+use common::*;
+
+fn initialize_vault_ix(payer: &Pubkey) -> solana_sdk::instruction::Instruction {
+    to_sdk(initialize_vault(payer.to_bytes(), vault().to_bytes(), config().to_bytes()))
+}
+
+fn sweep_vault_ix(admin: &Pubkey, program_data: &Pubkey) -> solana_sdk::instruction::Instruction {
+    to_sdk(sweep_vault(admin.to_bytes(), program_data.to_bytes(), vault().to_bytes()))
+}
+
+#[tokio::test]
+async fn initialize_funds_vault_and_assigns_it() {
+    let mut pt = program_test_without_vault();
+    let payer = add_wallet(&mut pt);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = initialize_vault_ix(&payer.pubkey());
+    send(&mut ctx, &[ix], &[&payer]).await.unwrap();
+
+    let account = ctx.banks_client.get_account(vault()).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id());
+    assert_eq!(account.lamports, Rent::default().minimum_balance(0));
+}
+
+#[tokio::test]
+async fn initialize_tops_up_prefunded_vault() {
+    let mut pt = program_test_without_vault();
+    let payer = add_wallet(&mut pt);
+    let minimum = Rent::default().minimum_balance(0);
+    pt.add_account(
+        vault(),
+        Account {
+            lamports: minimum / 2,
+            ..Account::default()
+        },
+    );
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = initialize_vault_ix(&payer.pubkey());
+    send(&mut ctx, &[ix], &[&payer]).await.unwrap();
+
+    assert_eq!(balance(&mut ctx, &vault()).await, minimum);
+    assert_eq!(
+        balance(&mut ctx, &payer.pubkey()).await,
+        USER_STARTING_LAMPORTS - (minimum - minimum / 2)
+    );
+}
+
+#[tokio::test]
+async fn initialize_twice_fails() {
+    let mut pt = program_test();
+    let payer = add_wallet(&mut pt);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = initialize_vault_ix(&payer.pubkey());
+    let err = instruction_error(send(&mut ctx, &[ix], &[&payer]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::VaultAlreadyInitialized));
+}
+
+#[tokio::test]
+async fn batch_clean_requires_initialized_vault() {
+    let mut pt = program_test_without_vault();
+    let user = add_wallet(&mut pt);
+    let mint = add_mint(&mut pt, 0);
+    let account = add_token_account(&mut pt, &mint, &user.pubkey(), 0);
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = to_sdk(batch_clean(
+        user.pubkey().to_bytes(),
+        vault().to_bytes(),
+        config().to_bytes(),
+        &BatchCleanOptions::default(),
+        &[account.to_bytes()],
+    ));
+    let err = instruction_error(send(&mut ctx, &[ix], &[&user]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::VaultNotInitialized));
+}
+
+#[tokio::test]
+async fn sweep_moves_excess_to_treasury() {
+    let mut pt = program_test_without_vault();
+    let admin = add_wallet(&mut pt);
+    let program_data = add_program_data(&mut pt, &admin.pubkey());
+    let minimum = Rent::default().minimum_balance(0);
+    let excess = 42_000;
+    pt.add_account(
+        vault(),
+        Account {
+            lamports: minimum + excess,
+            owner: program_id(),
+            ..Account::default()
+        },
+    );
+    let mut ctx = pt.start_with_context().await;
+    let treasury_before = balance(&mut ctx, &treasury()).await;
+
+    let ix = sweep_vault_ix(&admin.pubkey(), &program_data);
+    send(&mut ctx, &[ix], &[&admin]).await.unwrap();
+
+    assert_eq!(balance(&mut ctx, &vault()).await, minimum);
+    assert_eq!(balance(&mut ctx, &treasury()).await, treasury_before + excess);
+}
+
+#[tokio::test]
+async fn sweep_rejects_non_admin() {
+    let mut pt = program_test();
+    let admin = add_wallet(&mut pt);
+    let program_data = add_program_data(&mut pt, &admin.pubkey());
+    let impostor = Keypair::new();
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = sweep_vault_ix(&impostor.pubkey(), &program_data);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&impostor]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::Unauthorized));
+}
+
+#[tokio::test]
+async fn sweep_rejects_forged_program_data() {
+    let mut pt = program_test();
+    let admin = add_wallet(&mut pt);
+    add_program_data(&mut pt, &admin.pubkey());
+    let forged = Pubkey::new_unique();
+    pt.add_account(forged, program_data_account(&admin.pubkey()));
+    let mut ctx = pt.start_with_context().await;
+
+    let ix = sweep_vault_ix(&admin.pubkey(), &forged);
+    let err = instruction_error(send(&mut ctx, &[ix], &[&admin]).await);
+
+    assert_eq!(err, janitor_error(JanitorError::Unauthorized));
+}
@@ -0,0 +1,25 @@
+use thiserror::Error;
+use zera_client::ClientError;
+
+#[derive(Error, Debug)]
+pub enum RelayError {
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Transaction rejected: {0}")]
+    Rejected(String),
+
+    #[error(transparent)]
+    Client(#[from] ClientError),
+}
+
+impl RelayError {
+    /// HTTP status reported to the caller.
+    pub fn status(&self) -> u16 {
+        match self {
+            RelayError::BadRequest(_) => 400,
+            RelayError::Rejected(_) => 422,
+            RelayError::Client(_) => 502,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Fee-paying relayer for gasless cleaning.
+//!
+//! Users without SOL build a `BatchClean` with this relayer as fee payer and
+//! a `relayer_fee` reimbursement, sign it and POST it here; the relayer checks
+//! it can only pay the network fee, co-signs and submits it.
+//!
+//! `GET /info` returns the fee payer and asking fee, `POST /relay` takes
+//! `{"transaction": "<base64>"}` and returns `{"signature": "..."}`.
+//!
+//! Local testing: run `solana-test-validator` with the janitor program
+//! deployed, fund a relayer key and start
+//! `zera-relayer --url http://127.0.0.1:8899 --keypair relayer.json`, then
+//! tick "Gasless" in the app (it talks to `RELAYER_URL`, 127.0.0.1:8080).
+
+mod error;
+mod http;
+mod policy;
+mod relay;
+mod transport;
+
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use clap::Parser;
+use serde_json::json;
+use solana_sdk::signature::{read_keypair_file, Signer};
+use zera_client::failover::Failover;
+use zera_shared::MAX_RELAYER_FEE;
+
+use crate::relay::Relayer;
+use crate::transport::HttpTransport;
+
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+const DEFAULT_LISTEN: &str = "127.0.0.1:8080";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Connections handled at once; further ones are turned away until one finishes.
+const MAX_CONNECTIONS: usize = 64;
+/// How often the RPC endpoints are re-ranked when several are configured.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Parser)]
+#[command(name = "zera-relayer", version, about = "Co-sign and pay for gasless janitor cleans")]
+struct Cli {
+    /// Solana JSON-RPC endpoints, comma-separated in order of preference
+    #[arg(long, env = "ZERA_RPC_URL", value_delimiter = ',', default_value = DEFAULT_RPC_URL)]
+    url: Vec<String>,
+
+    /// Path to the fee payer keypair JSON file
+    #[arg(long, env = "ZERA_RELAYER_KEYPAIR")]
+    keypair: PathBuf,
+
+    /// Address to listen on
+    #[arg(long, default_value = DEFAULT_LISTEN)]
+    listen: String,
+
+    /// Lamports reimbursed per transaction, at most the program's cap
+    #[arg(long, default_value_t = 10_000, value_parser = clap::value_parser!(u64).range(0..=MAX_RELAYER_FEE))]
+    fee: u64,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let keypair = match read_keypair_file(&cli.keypair) {
+        Ok(keypair) => keypair,
+        Err(e) => {
+            eprintln!("Error: Failed to read keypair {}: {}", cli.keypair.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let listener = match TcpListener::bind(&cli.listen) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Error: Failed to listen on {}: {}", cli.listen, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let transport = Failover::new(cli.url.iter().map(|url| HttpTransport::new(url)).collect());
+    let relayer = Arc::new(Relayer { keypair, fee: cli.fee, transport });
+    println!(
+        "Relaying for {} on http://{} (fee {} lamports, RPC {})",
+        relayer.keypair.pubkey(),
+        cli.listen,
+        relayer.fee,
+        cli.url.join(", ")
+    );
+
+    if cli.url.len() > 1 {
+        let (relayer, urls) = (Arc::clone(&relayer), cli.url.clone());
+        thread::spawn(move || loop {
+            let slots = futures::executor::block_on(relayer.transport.check_health());
+            if let Some(active) = relayer.transport.active() {
+                println!("Using RPC {} (slots {:?})", urls[active], slots);
+            }
+            thread::sleep(HEALTH_CHECK_INTERVAL);
+        });
+    }
+
+    // A thread per connection, so a slow client can't hold up the others
+    let connections = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Accept failed: {}", e);
+                continue;
+            }
+        };
+        if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            connections.fetch_sub(1, Ordering::SeqCst);
+            let _ = http::write_json(&stream, 503, &json!({ "error": "Too many connections" }));
+            continue;
+        }
+        let (relayer, connections) = (Arc::clone(&relayer), Arc::clone(&connections));
+        thread::spawn(move || {
+            handle(&relayer, &stream);
+            connections.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+    ExitCode::SUCCESS
+}
+
+fn handle(relayer: &Relayer<Failover<HttpTransport>>, stream: &TcpStream) {
+    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+    let request = match http::read_request(stream) {
+        Ok(request) => request,
+        Err(e) => {
+            let _ = http::write_json(stream, 400, &json!({ "error": e.to_string() }));
+            return;
+        }
+    };
+
+    let (status, body) = match (request.method.as_str(), request.path.as_str()) {
+        ("OPTIONS", _) => (204, json!({})),
+        ("GET", "/info") => (200, relayer.info()),
+        ("POST", "/relay") => match futures::executor::block_on(relayer.relay(&request.body)) {
+            Ok(signature) => {
+                println!("Relayed {}", signature);
+                (200, json!({ "signature": signature }))
+            }
+            Err(e) => {
+                println!("Refused: {}", e);
+                (e.status(), json!({ "error": e.to_string() }))
+            }
+        },
+        _ => (404, json!({ "error": "Not found" })),
+    };
+    if let Err(e) = http::write_json(stream, status, &body) {
+        eprintln!("Failed to respond: {}", e);
+    }
+}
@@ -0,0 +1,138 @@
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use zera_shared::instruction::JanitorInstruction;
+use zera_shared::{MAX_RELAYER_FEE, PROGRAM_ID};
+
+use crate::error::RelayError;
+
+/// Signatures a relayed transaction may require: the relayer's and the user's.
+const REQUIRED_SIGNATURES: u8 = 2;
+/// Header accounts of a `BatchClean` before its optional accounts.
+const BATCH_CLEAN_HEADER_ACCOUNTS: usize = 5;
+
+/// Check a user-signed transaction only spends the relayer's lamports on the
+/// network fee and reimburses at least `min_fee` per transaction.
+///
+/// Every instruction must be a janitor `BatchClean` naming the relayer as its
+/// fee payer, and the relayer may appear nowhere else, so co-signing can't
+/// authorize anything but paying for the clean.
+pub fn check(tx: &Transaction, relayer: &Pubkey, min_fee: u64) -> Result<(), RelayError> {
+    let message = &tx.message;
+    if message.account_keys.first() != Some(relayer) {
+        return Err(RelayError::Rejected("fee payer is not this relayer".to_string()));
+    }
+    if message.header.num_required_signatures != REQUIRED_SIGNATURES {
+        return Err(RelayError::Rejected("expected exactly the user and relayer signatures".to_string()));
+    }
+    if message.instructions.is_empty() {
+        return Err(RelayError::Rejected("no instructions".to_string()));
+    }
+
+    let program_id = Pubkey::new_from_array(PROGRAM_ID);
+    for ix in &message.instructions {
+        if message.account_keys.get(ix.program_id_index as usize) != Some(&program_id) {
+            return Err(RelayError::Rejected("only janitor instructions can be relayed".to_string()));
+        }
+        let Some(JanitorInstruction::BatchClean {
+            has_destination,
+            has_referrer,
+            has_user_stats,
+            has_governance_account,
+            relayer_fee: Some(relayer_fee),
+            ..
+        }) = JanitorInstruction::unpack(&ix.data)
+        else {
+            return Err(RelayError::Rejected("instruction is not a relayed BatchClean".to_string()));
+        };
+        if relayer_fee < min_fee || relayer_fee > MAX_RELAYER_FEE {
+            return Err(RelayError::Rejected(format!(
+                "relayer fee must be between {} and {} lamports",
+                min_fee, MAX_RELAYER_FEE
+            )));
+        }
+
+        let fee_payer_slot = BATCH_CLEAN_HEADER_ACCOUNTS
+            + [has_destination, has_referrer, has_user_stats, has_governance_account]
+                .into_iter()
+                .filter(|&present| present)
+                .count();
+        for (slot, &index) in ix.accounts.iter().enumerate() {
+            if (index == 0) != (slot == fee_payer_slot) {
+                return Err(RelayError::Rejected("relayer must only appear as the fee payer".to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::system_instruction;
+    use zera_shared::instruction::{batch_clean, BatchCleanOptions};
+
+    fn clean_ix(user: &Pubkey, options: &BatchCleanOptions) -> Instruction {
+        let ix = batch_clean(user.to_bytes(), [2u8; 32], [3u8; 32], options, &[[4u8; 32]]);
+        Instruction {
+            program_id: Pubkey::new_from_array(ix.program_id),
+            accounts: ix
+                .accounts
+                .into_iter()
+                .map(|meta| AccountMeta {
+                    pubkey: Pubkey::new_from_array(meta.pubkey),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: ix.data,
+        }
+    }
+
+    fn relayed(relayer: &Pubkey, fee: u64) -> BatchCleanOptions {
+        BatchCleanOptions { relayer: Some((relayer.to_bytes(), fee)), ..Default::default() }
+    }
+
+    fn tx(relayer: &Pubkey, ixs: &[Instruction]) -> Transaction {
+        Transaction::new_with_payer(ixs, Some(relayer))
+    }
+
+    #[test]
+    fn accepts_relayed_batch_clean() {
+        let (relayer, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let options = BatchCleanOptions { referrer: Some([9u8; 32]), ..relayed(&relayer, 10_000) };
+        let tx = tx(&relayer, &[clean_ix(&user, &options), clean_ix(&user, &options)]);
+        assert!(check(&tx, &relayer, 10_000).is_ok());
+    }
+
+    #[test]
+    fn rejects_other_fee_payer_or_low_fee() {
+        let (relayer, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = clean_ix(&user, &relayed(&relayer, 5_000));
+        assert!(check(&tx(&user, std::slice::from_ref(&ix)), &relayer, 5_000).is_err());
+        assert!(check(&tx(&relayer, &[ix]), &relayer, 10_000).is_err());
+    }
+
+    #[test]
+    fn rejects_instructions_spending_relayer_funds() {
+        let (relayer, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let clean = clean_ix(&user, &relayed(&relayer, 10_000));
+        let transfer = system_instruction::transfer(&relayer, &user, 1_000_000);
+        assert!(check(&tx(&relayer, &[clean, transfer]), &relayer, 10_000).is_err());
+    }
+
+    #[test]
+    fn rejects_relayer_outside_fee_payer_slot() {
+        let (relayer, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let options =
+            BatchCleanOptions { destination: Some(relayer.to_bytes()), ..relayed(&relayer, 10_000) };
+        assert!(check(&tx(&relayer, &[clean_ix(&user, &options)]), &relayer, 10_000).is_err());
+    }
+
+    #[test]
+    fn rejects_clean_without_relayer_fee() {
+        let (relayer, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = clean_ix(&user, &BatchCleanOptions::default());
+        assert!(check(&tx(&relayer, &[ix]), &relayer, 0).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use zera_client::rpc::send_transaction;
+use zera_client::RpcTransport;
+
+use crate::error::RelayError;
+use crate::policy;
+
+#[derive(Deserialize)]
+struct RelayRequest {
+    /// Wire transaction signed by the user, base64
+    transaction: String,
+}
+
+/// Fee payer key and asking price, co-signing `BatchClean`s sent by users.
+pub struct Relayer<T> {
+    pub keypair: Keypair,
+    /// Lamports the relayer asks to be reimbursed per transaction.
+    pub fee: u64,
+    pub transport: T,
+}
+
+impl<T: RpcTransport> Relayer<T> {
+    /// What the app needs to build a relayed transaction.
+    pub fn info(&self) -> Value {
+        json!({ "feePayer": self.keypair.pubkey().to_string(), "relayerFee": self.fee })
+    }
+
+    /// Check, co-sign and submit a user-signed transaction; returns its signature.
+    pub async fn relay(&self, body: &[u8]) -> Result<String, RelayError> {
+        let request: RelayRequest =
+            serde_json::from_slice(body).map_err(|e| RelayError::BadRequest(e.to_string()))?;
+        let wire = STANDARD
+            .decode(&request.transaction)
+            .map_err(|e| RelayError::BadRequest(format!("Invalid base64: {}", e)))?;
+        let mut tx: Transaction = bincode::deserialize(&wire)
+            .map_err(|e| RelayError::BadRequest(format!("Invalid transaction: {}", e)))?;
+
+        policy::check(&tx, &self.keypair.pubkey(), self.fee)?;
+        let blockhash = tx.message.recent_blockhash;
+        tx.try_partial_sign(&[&self.keypair], blockhash)
+            .map_err(|e| RelayError::Rejected(e.to_string()))?;
+        if tx.verify().is_err() {
+            return Err(RelayError::Rejected("user signature is missing or invalid".to_string()));
+        }
+
+        let wire = bincode::serialize(&tx).map_err(|e| RelayError::BadRequest(e.to_string()))?;
+        // The node simulates before forwarding, so failing cleans never cost the relayer
+        Ok(send_transaction(&self.transport, &STANDARD.encode(wire)).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program_test::{processor, tokio, BanksClient, ProgramTest};
+    use solana_sdk::account::Account;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::program_pack::Pack;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::rent::Rent;
+    use zera_client::batch::{build_batches, max_accounts_per_tx, MAX_ACCOUNTS_PER_TX, PACKET_DATA_SIZE};
+    use zera_client::pda::{find_config_pda, find_user_stats_pda, find_vault_pda};
+    use zera_client::{ClientError, TokenAccountInfo};
+    use zera_shared::config::{ProgramConfig, UserStats};
+    use zera_shared::instruction::BatchCleanOptions;
+    use zera_shared::{PROGRAM_ID, TREASURY_PUBKEY};
+
+    use super::*;
+
+    /// Submits transactions straight to a program-test bank.
+    struct BanksTransport(BanksClient);
+
+    impl RpcTransport for BanksTransport {
+        async fn request(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+            assert_eq!(method, "sendTransaction");
+            let wire = STANDARD.decode(params[0].as_str().unwrap()).unwrap();
+            let tx: Transaction = bincode::deserialize(&wire).unwrap();
+            let signature = tx.signatures[0].to_string();
+            match self.0.clone().process_transaction(tx).await {
+                Ok(()) => Ok(json!(signature)),
+                Err(e) => Err(ClientError::TransactionFailed(signature, e.to_string())),
+            }
+        }
+    }
+
+    fn add_account(pt: &mut ProgramTest, address: [u8; 32], owner: Pubkey, data: Vec<u8>) {
+        let lamports = Rent::default().minimum_balance(data.len());
+        let account = Account { lamports, data, owner, ..Account::default() };
+        pt.add_account(Pubkey::new_from_array(address), account);
+    }
+
+    fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+
+    fn to_sdk(ix: &zera_shared::instruction::Instruction) -> Instruction {
+        Instruction {
+            program_id: Pubkey::new_from_array(ix.program_id),
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: Pubkey::new_from_array(meta.pubkey),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: ix.data.clone(),
+        }
+    }
+
+    #[tokio::test]
+    async fn relays_full_size_batch_with_every_optional_account() {
+        let program_id = Pubkey::new_from_array(PROGRAM_ID);
+        let mut pt =
+            ProgramTest::new("zera_program", program_id, processor!(zera_program::process_instruction));
+        let (relayer, user) = (Keypair::new(), Keypair::new());
+        pt.add_account(relayer.pubkey(), Account { lamports: 1_000_000_000, ..Account::default() });
+        add_account(&mut pt, find_vault_pda().0, program_id, vec![]);
+        add_account(&mut pt, TREASURY_PUBKEY, Pubkey::default(), vec![]);
+        let governance_mint = Pubkey::new_unique();
+        let config = ProgramConfig { governance_mint: governance_mint.to_bytes(), ..Default::default() };
+        add_account(&mut pt, find_config_pda().0, program_id, config.pack());
+        let user_stats = find_user_stats_pda(&user.pubkey().to_bytes()).0;
+        add_account(&mut pt, user_stats, program_id, UserStats::default().pack());
+        let governance_account = Pubkey::new_unique();
+        let holding = token_account(&governance_mint, &user.pubkey(), 5_000);
+        add_account(&mut pt, governance_account.to_bytes(), spl_token::id(), holding);
+
+        let mint = Pubkey::new_unique();
+        let accounts: Vec<TokenAccountInfo> = (0..MAX_ACCOUNTS_PER_TX)
+            .map(|_| {
+                let address = Pubkey::new_unique();
+                let data = token_account(&mint, &user.pubkey(), 0);
+                add_account(&mut pt, address.to_bytes(), spl_token::id(), data);
+                TokenAccountInfo {
+                    address: address.to_string(),
+                    mint: mint.to_string(),
+                    amount: 0,
+                    lamports: Rent::default().minimum_balance(spl_token::state::Account::LEN),
+                    is_native: false,
+                    close_authority: None,
+                    frozen: false,
+                }
+            })
+            .collect();
+        let (banks, _, blockhash) = pt.start().await;
+
+        let fee = 10_000;
+        let options = BatchCleanOptions {
+            destination: Some(Pubkey::new_unique().to_bytes()),
+            referrer: Some(Pubkey::new_unique().to_bytes()),
+            user_stats: Some(user_stats),
+            governance_account: Some(governance_account.to_bytes()),
+            relayer: Some((relayer.pubkey().to_bytes(), fee)),
+        };
+        let owner = user.pubkey().to_string();
+        let batches = build_batches(&owner, &options, &accounts, MAX_ACCOUNTS_PER_TX).unwrap();
+        let batch = &batches[0];
+        assert_eq!(batch.accounts.len(), max_accounts_per_tx(&options));
+
+        let mut tx = Transaction::new_with_payer(&[to_sdk(&batch.instruction)], Some(&relayer.pubkey()));
+        tx.partial_sign(&[&user], blockhash);
+        let wire = bincode::serialize(&tx).unwrap();
+        assert!(wire.len() <= PACKET_DATA_SIZE, "{} bytes", wire.len());
+
+        let fee_payer = relayer.pubkey();
+        let relayer = Relayer { keypair: relayer, fee, transport: BanksTransport(banks.clone()) };
+        let body = json!({ "transaction": STANDARD.encode(wire) }).to_string();
+        relayer.relay(body.as_bytes()).await.unwrap();
+
+        // The reimbursement covers the two signatures the relayer paid for
+        assert!(banks.clone().get_balance(fee_payer).await.unwrap() >= 1_000_000_000);
+        for (account, closed) in accounts.iter().map(|a| (a, batch.accounts.contains(a))) {
+            let address = account.address.parse().unwrap();
+            assert_eq!(banks.clone().get_account(address).await.unwrap().is_none(), closed);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use alloc::vec::Vec;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{BPS_DENOMINATOR, FEE_BPS};
+
+/// Number of fee tier slots in [`ProgramConfig`].
+pub const MAX_FEE_TIERS: usize = 4;
+
+/// Discounted fee unlocked by cleaning volume or governance token holdings.
+///
+/// A zero threshold disables that criterion; a tier with both thresholds at
+/// zero is an unused slot.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeTier {
+    /// Lifetime closed accounts, per the user stats account, that unlock the tier.
+    pub min_closed: u64,
+    /// Governance token balance (base units) that unlocks the tier.
+    pub min_holding: u64,
+    /// Fee charged in this tier, in basis points.
+    pub fee_bps: u16,
+}
+
+impl FeeTier {
+    /// Whether `closed` lifetime accounts or a `holding` balance unlock this tier.
+    pub fn qualifies(&self, closed: u64, holding: u64) -> bool {
+        (self.min_closed > 0 && closed >= self.min_closed)
+            || (self.min_holding > 0 && holding >= self.min_holding)
+    }
+}
+
+/// Contents of the config PDA, adjustable by the program admin.
+///
+/// A missing config account behaves like [`ProgramConfig::default`].
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProgramConfig {
+    /// Share of the protocol fee paid to the referrer, in basis points.
+    pub referral_bps: u16,
+    /// Mint whose holdings unlock fee tiers; all zeroes when unset.
+    pub governance_mint: [u8; 32],
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+    /// Emergency stop: every non-admin instruction fails while set.
+    pub paused: bool,
+}
+
+impl ProgramConfig {
+    /// Allocated size of the config account. New fields go into the zeroed
+    /// tail; accounts created with a smaller size are grown on the next update.
+    pub const LEN: usize = 256;
+
+    /// Borsh-encode the config, zero-padded to [`ProgramConfig::LEN`].
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = borsh::to_vec(self).expect("Failed to serialize config");
+        data.resize(Self::LEN, 0);
+        data
+    }
+
+    /// Decode a config account, ignoring the unused tail. Fields missing
+    /// from accounts created by older versions decode as zero.
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        let mut padded = data.to_vec();
+        padded.resize(data.len().max(Self::LEN), 0);
+        Self::deserialize(&mut &padded[..]).ok()
+    }
+
+    /// Split a protocol fee into `(treasury, referrer)` shares.
+    pub fn split_fee(&self, fee: u64) -> (u64, u64) {
+        let referral = (fee as u128 * self.referral_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        (fee - referral, referral)
+    }
+
+    /// Cheapest tier unlocked by `closed` lifetime accounts or a `holding` balance,
+    /// with its slot index.
+    pub fn fee_tier(&self, closed: u64, holding: u64) -> Option<(usize, FeeTier)> {
+        self.fee_tiers
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, tier)| tier.qualifies(closed, holding))
+            .min_by_key(|(_, tier)| tier.fee_bps)
+    }
+
+    /// Fee in basis points for a user, falling back to [`FEE_BPS`].
+    pub fn fee_bps(&self, closed: u64, holding: u64) -> u16 {
+        self.fee_tier(closed, holding)
+            .map_or(FEE_BPS as u16, |(_, tier)| tier.fee_bps.min(FEE_BPS as u16))
+    }
+
+    pub fn has_governance_mint(&self) -> bool {
+        self.governance_mint != [0u8; 32]
+    }
+}
+
+/// Per-user PDA tracking lifetime cleaning volume for fee tiers.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UserStats {
+    pub closed_accounts: u64,
+}
+
+impl UserStats {
+    /// Allocated size of a user stats account, leaving room for new counters.
+    pub const LEN: usize = 32;
+
+    /// Borsh-encode the stats, zero-padded to [`UserStats::LEN`].
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = borsh::to_vec(self).expect("Failed to serialize user stats");
+        data.resize(Self::LEN, 0);
+        data
+    }
+
+    /// Decode a user stats account, ignoring the unused tail.
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        Self::deserialize(&mut &data[..]).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn tiered() -> ProgramConfig {
+        let mut config = ProgramConfig::default();
+        config.fee_tiers[0] = FeeTier { min_closed: 100, min_holding: 0, fee_bps: 400 };
+        config.fee_tiers[1] = FeeTier { min_closed: 1_000, min_holding: 5_000, fee_bps: 250 };
+        config
+    }
+
+    #[test]
+    fn config_round_trips_through_padded_account() {
+        let config = ProgramConfig {
+            referral_bps: 2_500,
+            governance_mint: [4u8; 32],
+            paused: true,
+            ..tiered()
+        };
+        let data = config.pack();
+        assert_eq!(data.len(), ProgramConfig::LEN);
+        assert_eq!(ProgramConfig::unpack(&data), Some(config));
+        assert_eq!(ProgramConfig::unpack(&[0u8; ProgramConfig::LEN]), Some(ProgramConfig::default()));
+    }
+
+    #[test]
+    fn short_config_accounts_decode_with_zeroed_fields() {
+        let mut data = vec![0u8; 64];
+        data[..2].copy_from_slice(&1_000u16.to_le_bytes());
+        let config = ProgramConfig::unpack(&data).unwrap();
+        assert_eq!(config.referral_bps, 1_000);
+        assert!(!config.has_governance_mint());
+        assert!(!config.paused);
+    }
+
+    #[test]
+    fn split_fee_rounds_referral_down() {
+        let config = ProgramConfig { referral_bps: 3_333, ..Default::default() };
+        assert_eq!(config.split_fee(101_964), (67_980, 33_984));
+        assert_eq!(ProgramConfig::default().split_fee(101_964), (101_964, 0));
+    }
+
+    #[test]
+    fn cheapest_qualifying_tier_applies() {
+        let config = tiered();
+        assert_eq!(config.fee_bps(0, 0), FEE_BPS as u16);
+        assert_eq!(config.fee_bps(99, 4_999), FEE_BPS as u16);
+        assert_eq!(config.fee_bps(100, 0), 400);
+        assert_eq!(config.fee_bps(100, 5_000), 250);
+        assert_eq!(config.fee_tier(1_000, 0).map(|(i, _)| i), Some(1));
+    }
+}
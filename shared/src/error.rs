@@ -0,0 +1,133 @@
+use core::fmt;
+
+/// Janitor program error codes, as surfaced in `ProgramError::Custom(n)`.
+///
+/// Mirrors `zera_program::error::JanitorError` variant for variant so
+/// off-chain code can explain failures without depending on the program crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum JanitorError {
+    InvalidVaultPda,
+    MissingSigner,
+    NonZeroBalance,
+    Overflow,
+    AccountCountMismatch,
+    DuplicateAccount,
+    AccountNotWritable,
+    InvalidTokenAccount,
+    TokenOwnerMismatch,
+    InvalidTokenProgram,
+    InvalidTreasury,
+    VaultNotInitialized,
+    VaultAlreadyInitialized,
+    Unauthorized,
+    InvalidPayoutDestination,
+    InvalidConfigPda,
+    InvalidReferrer,
+    InvalidReferralBps,
+    InvalidUserStats,
+    UserStatsAlreadyInitialized,
+    InvalidGovernanceAccount,
+    InvalidFeeTier,
+    ProgramPaused,
+    InvalidJanitorPda,
+    NotDelegated,
+    InvalidIntent,
+    IntentExpired,
+    MintNotAllowed,
+    RelayerFeeTooHigh,
+    NotAssociatedAccount,
+    MintMismatch,
+    InvalidNft,
+    InvalidMetadataProgram,
+}
+
+impl JanitorError {
+    pub const ALL: &'static [JanitorError] = &[
+        JanitorError::InvalidVaultPda,
+        JanitorError::MissingSigner,
+        JanitorError::NonZeroBalance,
+        JanitorError::Overflow,
+        JanitorError::AccountCountMismatch,
+        JanitorError::DuplicateAccount,
+        JanitorError::AccountNotWritable,
+        JanitorError::InvalidTokenAccount,
+        JanitorError::TokenOwnerMismatch,
+        JanitorError::InvalidTokenProgram,
+        JanitorError::InvalidTreasury,
+        JanitorError::VaultNotInitialized,
+        JanitorError::VaultAlreadyInitialized,
+        JanitorError::Unauthorized,
+        JanitorError::InvalidPayoutDestination,
+        JanitorError::InvalidConfigPda,
+        JanitorError::InvalidReferrer,
+        JanitorError::InvalidReferralBps,
+        JanitorError::InvalidUserStats,
+        JanitorError::UserStatsAlreadyInitialized,
+        JanitorError::InvalidGovernanceAccount,
+        JanitorError::InvalidFeeTier,
+        JanitorError::ProgramPaused,
+        JanitorError::InvalidJanitorPda,
+        JanitorError::NotDelegated,
+        JanitorError::InvalidIntent,
+        JanitorError::IntentExpired,
+        JanitorError::MintNotAllowed,
+        JanitorError::RelayerFeeTooHigh,
+        JanitorError::NotAssociatedAccount,
+        JanitorError::MintMismatch,
+        JanitorError::InvalidNft,
+        JanitorError::InvalidMetadataProgram,
+    ];
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.get(code as usize).copied()
+    }
+
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            JanitorError::InvalidVaultPda => "Invalid vault PDA derivation",
+            JanitorError::MissingSigner => "Missing required signer",
+            JanitorError::NonZeroBalance => "Token account has non-zero balance",
+            JanitorError::Overflow => "Arithmetic overflow",
+            JanitorError::AccountCountMismatch => "Token account count does not match num_accounts",
+            JanitorError::DuplicateAccount => "Token account passed more than once",
+            JanitorError::AccountNotWritable => "Account must be writable",
+            JanitorError::InvalidTokenAccount => "Token account is not owned by the SPL Token program",
+            JanitorError::TokenOwnerMismatch => "Token account owner is not the signer",
+            JanitorError::InvalidTokenProgram => "Token program is not SPL Token",
+            JanitorError::InvalidTreasury => "Treasury account does not match the protocol treasury",
+            JanitorError::VaultNotInitialized => "Vault is not initialized or below rent-exemption",
+            JanitorError::VaultAlreadyInitialized => "Vault is already initialized",
+            JanitorError::Unauthorized => "Signer is not the program admin",
+            JanitorError::InvalidPayoutDestination => "Payout destination cannot be the vault or a closed account",
+            JanitorError::InvalidConfigPda => "Invalid config PDA derivation",
+            JanitorError::InvalidReferrer => "Referrer cannot be the user, payout destination or a protocol account",
+            JanitorError::InvalidReferralBps => "Referral share exceeds 100% of the fee",
+            JanitorError::InvalidUserStats => "User stats account is not the signer's initialized stats PDA",
+            JanitorError::UserStatsAlreadyInitialized => "User stats account already exists",
+            JanitorError::InvalidGovernanceAccount => "Governance token account is not the signer's account for the governance mint",
+            JanitorError::InvalidFeeTier => "Fee tier charges more than the standard fee",
+            JanitorError::ProgramPaused => "Program is paused",
+            JanitorError::InvalidJanitorPda => "Invalid janitor PDA",
+            JanitorError::NotDelegated => "Token account close authority is not the janitor",
+            JanitorError::InvalidIntent => "Missing or malformed signed intent",
+            JanitorError::IntentExpired => "Signed intent has expired",
+            JanitorError::MintNotAllowed => "Token mint is not allowed by the signed intent",
+            JanitorError::RelayerFeeTooHigh => "Relayer reimbursement exceeds the cap or the payout",
+            JanitorError::NotAssociatedAccount => "Target is not the owner's associated token account",
+            JanitorError::MintMismatch => "Token account mint does not match the target account",
+            JanitorError::InvalidNft => "Accounts are not the signer's NFT with its metadata and master edition",
+            JanitorError::InvalidMetadataProgram => "Metadata program is not Metaplex Token Metadata",
+        }
+    }
+}
+
+impl fmt::Display for JanitorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
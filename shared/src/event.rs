@@ -0,0 +1,91 @@
+use alloc::vec::Vec;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Events logged by the program as `Program data: <base64>` lines.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum JanitorEvent {
+    /// Emitted once per successful `BatchClean`.
+    BatchClean {
+        user: [u8; 32],
+        /// Wallet credited with `payout`; the user unless a destination was given.
+        destination: [u8; 32],
+        /// Referrer credited with `referral_fee`, if one was passed.
+        referrer: Option<[u8; 32]>,
+        accounts_closed: u8,
+        /// Rent released by the closed accounts, the only amount the fee applies to.
+        rent_collected: u64,
+        /// Wrapped SOL principal returned from native-mint accounts, included in `payout`.
+        unwrapped: u64,
+        /// Fee rate applied: the standard rate or the user's fee tier.
+        fee_bps: u16,
+        /// Total protocol fee, including `referral_fee`.
+        fee: u64,
+        referral_fee: u64,
+        /// Reimbursed to the relayer that paid the transaction fee, out of the payout.
+        relayer_fee: u64,
+        payout: u64,
+    },
+
+    /// Emitted when the admin sweeps vault excess to the treasury.
+    VaultSwept { amount: u64 },
+
+    /// Emitted once per successful `CrankClean`; `payout` is summed over all owners.
+    CrankClean {
+        cranker: [u8; 32],
+        accounts_closed: u8,
+        rent_collected: u64,
+        fee: u64,
+        crank_reward: u64,
+        payout: u64,
+    },
+
+    /// Emitted once per successful `Consolidate`.
+    Consolidate {
+        user: [u8; 32],
+        mint: [u8; 32],
+        /// Associated token account the balances were moved into.
+        target: [u8; 32],
+        accounts_closed: u8,
+        /// Tokens moved into `target`, in base units.
+        amount: u64,
+        rent_collected: u64,
+        fee: u64,
+        payout: u64,
+    },
+}
+
+impl JanitorEvent {
+    /// Borsh-encode the event for `sol_log_data`.
+    pub fn pack(&self) -> Vec<u8> {
+        borsh::to_vec(self).expect("Failed to serialize event")
+    }
+
+    /// Decode an event from the raw bytes of a `Program data:` log entry.
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        Self::try_from_slice(data).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_clean_event_round_trips() {
+        let event = JanitorEvent::BatchClean {
+            user: [9u8; 32],
+            destination: [8u8; 32],
+            referrer: Some([7u8; 32]),
+            accounts_closed: 3,
+            rent_collected: 6_117_840,
+            unwrapped: 0,
+            fee_bps: 500,
+            fee: 305_892,
+            referral_fee: 61_178,
+            relayer_fee: 5_000,
+            payout: 5_806_948,
+        };
+        assert_eq!(JanitorEvent::unpack(&event.pack()), Some(event));
+    }
+}
@@ -0,0 +1,725 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::config::{FeeTier, MAX_FEE_TIERS};
+use crate::intent::{ed25519_instruction_data, CleanIntent};
+use crate::{
+    ED25519_PROGRAM_ID, INSTRUCTIONS_SYSVAR_ID, METADATA_PROGRAM_ID, PROGRAM_ID, SPL_TOKEN_PROGRAM_ID,
+    SYSTEM_PROGRAM_ID, TREASURY_PUBKEY,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum JanitorInstruction {
+    /// Close `num_accounts` empty token accounts, collecting rent into the vault,
+    /// then splitting: a fee to treasury (5%, or the user's fee tier; shared
+    /// with the referrer, if any), the rest to the user or payout destination.
+    ///
+    /// With `relayer_fee`, a relayer pays the transaction fee and is reimbursed
+    /// that many lamports (at most `MAX_RELAYER_FEE`) out of the payout, so
+    /// wallets without SOL can still clean.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]`   User wallet
+    /// 1. `[writable]` Vault PDA (program-owned)
+    /// 2. `[writable]` Treasury wallet
+    /// 3. `[]`          SPL Token program
+    /// 4. `[]`          Config PDA
+    /// 5. `[writable]` Payout destination, only if `has_destination`
+    /// 6. `[writable]` Referrer, only if `has_referrer`
+    /// 7. `[writable]` User stats PDA, only if `has_user_stats`
+    /// 8. `[]`          User's governance token account, only if `has_governance_account`
+    /// 9. `[signer, writable]` Relayer fee payer, only if `relayer_fee` is set
+    /// 10. `[writable]` Token accounts to close (`num_accounts` of them)
+    BatchClean {
+        num_accounts: u8,
+        has_destination: bool,
+        has_referrer: bool,
+        has_user_stats: bool,
+        has_governance_account: bool,
+        relayer_fee: Option<u64>,
+    },
+
+    /// Fund the vault PDA to rent-exemption and assign it to the program.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Payer
+    /// 1. `[writable]`         Vault PDA
+    /// 2. `[]`                 System program
+    /// 3. `[]`                 Config PDA
+    InitializeVault,
+
+    /// Move vault lamports above rent-exemption to the treasury. Admin only.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]`   Admin (program upgrade authority)
+    /// 1. `[]`          Program data account
+    /// 2. `[writable]` Vault PDA
+    /// 3. `[writable]` Treasury wallet
+    SweepVault,
+
+    /// Create or update the config PDA. Admin only.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Admin (program upgrade authority), pays for creation
+    /// 1. `[]`                 Program data account
+    /// 2. `[writable]`         Config PDA
+    /// 3. `[]`                 System program
+    SetConfig { referral_bps: u16 },
+
+    /// Replace the governance mint and fee tiers in the config PDA. Admin only.
+    ///
+    /// Accounts expected: same as `SetConfig`.
+    SetFeeTiers {
+        governance_mint: [u8; 32],
+        fee_tiers: [FeeTier; MAX_FEE_TIERS],
+    },
+
+    /// Create the caller's user stats PDA so cleaning volume counts towards fee tiers.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` User wallet, pays for creation
+    /// 1. `[writable]`         User stats PDA
+    /// 2. `[]`                 System program
+    /// 3. `[]`                 Config PDA
+    InitUserStats,
+
+    /// Pause or resume every non-admin instruction. Admin only.
+    ///
+    /// Accounts expected: same as `SetConfig`.
+    SetPaused { paused: bool },
+
+    /// Hand the close authority of the owner's token accounts to the janitor PDA
+    /// so `CrankClean` can close them once they are empty.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]`   Token account owner
+    /// 1. `[]`          Janitor PDA
+    /// 2. `[]`          SPL Token program
+    /// 3. `[]`          Config PDA
+    /// 4. `[writable]` Token accounts to delegate (`num_accounts` of them)
+    Authorize { num_accounts: u8 },
+
+    /// Return the close authority of delegated token accounts to their owner.
+    /// Allowed while the program is paused.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]`   Token account owner
+    /// 1. `[]`          Janitor PDA
+    /// 2. `[]`          SPL Token program
+    /// 3. `[writable]` Token accounts to revoke (`num_accounts` of them)
+    Revoke { num_accounts: u8 },
+
+    /// Close empty token accounts delegated via `Authorize`. Permissionless: the
+    /// caller earns `CRANK_REWARD_BPS` of the rent, the protocol fee goes to the
+    /// treasury and the rest to each account's owner.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Cranker, receives the reward
+    /// 1. `[writable]`         Vault PDA
+    /// 2. `[writable]`         Treasury wallet
+    /// 3. `[]`                 SPL Token program
+    /// 4. `[]`                 Config PDA
+    /// 5. `[]`                 Janitor PDA
+    /// 6. `[writable]`         Pairs of token account and its owner wallet (`num_accounts` pairs)
+    CrankClean { num_accounts: u8 },
+
+    /// Close delegated token accounts on behalf of a user who signed a
+    /// [`CleanIntent`] off-chain. The previous instruction in the transaction
+    /// must be the Ed25519 precompile verifying the user's signature over
+    /// [`CleanIntent::message`]; the user does not sign the transaction.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]`   Submitter (e.g. a relayer)
+    /// 1. `[writable]` Payout destination named in the intent
+    /// 2. `[writable]` Vault PDA
+    /// 3. `[writable]` Treasury wallet
+    /// 4. `[]`          SPL Token program
+    /// 5. `[]`          Config PDA
+    /// 6. `[]`          Janitor PDA
+    /// 7. `[]`          Instructions sysvar
+    /// 8. `[writable]` Empty token accounts of allowed mints, delegated via `Authorize` (`num_accounts`)
+    IntentClean { num_accounts: u8 },
+
+    /// Move the balances of a user's auxiliary token accounts into their
+    /// associated token account for the same mint, then close the auxiliaries.
+    /// The rent is split like `BatchClean` at the standard fee.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` User wallet, receives the payout
+    /// 1. `[writable]`         User's associated token account for the mint
+    /// 2. `[writable]`         Vault PDA
+    /// 3. `[writable]`         Treasury wallet
+    /// 4. `[]`                 SPL Token program
+    /// 5. `[]`                 Config PDA
+    /// 6. `[writable]`         Auxiliary token accounts of the same mint (`num_accounts`)
+    Consolidate { num_accounts: u8 },
+
+    /// Burn one of the user's NFTs through Metaplex `BurnNft`, reclaiming the
+    /// token account, metadata and master edition rent. The standard fee on
+    /// the reclaimed lamports goes to the treasury.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` User wallet, owner of the NFT
+    /// 1. `[writable]`         Treasury wallet
+    /// 2. `[]`                 Config PDA
+    /// 3. `[writable]`         Metadata PDA
+    /// 4. `[writable]`         Mint
+    /// 5. `[writable]`         User's token account holding the NFT
+    /// 6. `[writable]`         Master edition PDA
+    /// 7. `[]`                 SPL Token program
+    /// 8. `[]`                 Metaplex Token Metadata program
+    /// 9. `[]`                 System program
+    BurnNft,
+}
+
+impl JanitorInstruction {
+    /// Borsh-encode the instruction for the on-chain program.
+    pub fn pack(&self) -> Vec<u8> {
+        borsh::to_vec(self).expect("Failed to serialize instruction")
+    }
+
+    /// Decode instruction data produced by [`JanitorInstruction::pack`].
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        Self::try_from_slice(data).ok()
+    }
+}
+
+/// Account reference in a janitor instruction, mirroring Solana's `AccountMeta`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub pubkey: [u8; 32],
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountMeta {
+    pub fn new(pubkey: [u8; 32], is_signer: bool) -> Self {
+        Self { pubkey, is_signer, is_writable: true }
+    }
+
+    pub fn new_readonly(pubkey: [u8; 32], is_signer: bool) -> Self {
+        Self { pubkey, is_signer, is_writable: false }
+    }
+}
+
+/// A fully encoded instruction: `(program_id, accounts, data)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub program_id: [u8; 32],
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+/// More accounts than an instruction's one-byte `num_accounts` can count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TooManyAccounts(pub usize);
+
+impl fmt::Display for TooManyAccounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} accounts passed, at most {} fit in one instruction", self.0, u8::MAX)
+    }
+}
+
+/// `num_accounts` for a list of `len` accounts.
+fn account_count(len: usize) -> Result<u8, TooManyAccounts> {
+    u8::try_from(len).map_err(|_| TooManyAccounts(len))
+}
+
+/// Optional accounts of a `BatchClean`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BatchCleanOptions {
+    /// Wallet receiving the payout instead of the signing user.
+    pub destination: Option<[u8; 32]>,
+    /// Partner wallet earning the configured share of the protocol fee.
+    pub referrer: Option<[u8; 32]>,
+    /// The user's stats PDA, counting this batch towards volume tiers.
+    pub user_stats: Option<[u8; 32]>,
+    /// The user's governance token account, proving holdings for a fee tier.
+    pub governance_account: Option<[u8; 32]>,
+    /// Relayer paying the transaction fee, and the lamports it is reimbursed.
+    pub relayer: Option<([u8; 32], u64)>,
+}
+
+/// Build a `BatchClean` instruction closing `token_accounts` owned by `user`.
+///
+/// `vault` and `config` must be the PDAs derived from `PROGRAM_ID` with
+/// `VAULT_SEED` and `CONFIG_SEED`.
+/// Panics if more than `u8::MAX` token accounts are passed.
+pub fn batch_clean(
+    user: [u8; 32],
+    vault: [u8; 32],
+    config: [u8; 32],
+    options: &BatchCleanOptions,
+    token_accounts: &[[u8; 32]],
+) -> Instruction {
+    let num_accounts =
+        u8::try_from(token_accounts.len()).expect("Too many token accounts for one instruction");
+
+    let mut accounts = Vec::with_capacity(9 + token_accounts.len());
+    accounts.push(AccountMeta::new(user, true));
+    accounts.push(AccountMeta::new(vault, false));
+    accounts.push(AccountMeta::new(TREASURY_PUBKEY, false));
+    accounts.push(AccountMeta::new_readonly(SPL_TOKEN_PROGRAM_ID, false));
+    accounts.push(AccountMeta::new_readonly(config, false));
+    if let Some(destination) = options.destination {
+        accounts.push(AccountMeta::new(destination, false));
+    }
+    if let Some(referrer) = options.referrer {
+        accounts.push(AccountMeta::new(referrer, false));
+    }
+    if let Some(user_stats) = options.user_stats {
+        accounts.push(AccountMeta::new(user_stats, false));
+    }
+    if let Some(governance_account) = options.governance_account {
+        accounts.push(AccountMeta::new_readonly(governance_account, false));
+    }
+    if let Some((fee_payer, _)) = options.relayer {
+        accounts.push(AccountMeta::new(fee_payer, true));
+    }
+    accounts.extend(token_accounts.iter().map(|&acc| AccountMeta::new(acc, false)));
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: JanitorInstruction::BatchClean {
+            num_accounts,
+            has_destination: options.destination.is_some(),
+            has_referrer: options.referrer.is_some(),
+            has_user_stats: options.user_stats.is_some(),
+            has_governance_account: options.governance_account.is_some(),
+            relayer_fee: options.relayer.map(|(_, fee)| fee),
+        }
+        .pack(),
+    }
+}
+
+/// Build an `InitializeVault` instruction funded by `payer`.
+pub fn initialize_vault(payer: [u8; 32], vault: [u8; 32], config: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(config, false),
+        ],
+        data: JanitorInstruction::InitializeVault.pack(),
+    }
+}
+
+/// Build a `SweepVault` instruction signed by the program's upgrade authority.
+///
+/// `program_data` is the janitor's `ProgramData` account under the upgradeable loader.
+pub fn sweep_vault(admin: [u8; 32], program_data: [u8; 32], vault: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(TREASURY_PUBKEY, false),
+        ],
+        data: JanitorInstruction::SweepVault.pack(),
+    }
+}
+
+/// Build a `SetConfig` instruction signed by the program's upgrade authority.
+pub fn set_config(
+    admin: [u8; 32],
+    program_data: [u8; 32],
+    config: [u8; 32],
+    referral_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(admin, true),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: JanitorInstruction::SetConfig { referral_bps }.pack(),
+    }
+}
+
+/// Build a `SetFeeTiers` instruction signed by the program's upgrade authority.
+pub fn set_fee_tiers(
+    admin: [u8; 32],
+    program_data: [u8; 32],
+    config: [u8; 32],
+    governance_mint: [u8; 32],
+    fee_tiers: [FeeTier; MAX_FEE_TIERS],
+) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(admin, true),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: JanitorInstruction::SetFeeTiers { governance_mint, fee_tiers }.pack(),
+    }
+}
+
+/// Build an `InitUserStats` instruction creating `user_stats` for `user`.
+pub fn init_user_stats(user: [u8; 32], user_stats: [u8; 32], config: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_stats, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(config, false),
+        ],
+        data: JanitorInstruction::InitUserStats.pack(),
+    }
+}
+
+/// Build a `SetPaused` instruction signed by the program's upgrade authority.
+pub fn set_paused(admin: [u8; 32], program_data: [u8; 32], config: [u8; 32], paused: bool) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(admin, true),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: JanitorInstruction::SetPaused { paused }.pack(),
+    }
+}
+
+/// Build an `Authorize` instruction delegating `token_accounts` to the janitor PDA.
+pub fn authorize(
+    owner: [u8; 32],
+    janitor: [u8; 32],
+    config: [u8; 32],
+    token_accounts: &[[u8; 32]],
+) -> Result<Instruction, TooManyAccounts> {
+    let num_accounts = account_count(token_accounts.len())?;
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(owner, true),
+        AccountMeta::new_readonly(janitor, false),
+        AccountMeta::new_readonly(SPL_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(config, false),
+    ];
+    accounts.extend(token_accounts.iter().map(|ta| AccountMeta::new(*ta, false)));
+
+    Ok(Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: JanitorInstruction::Authorize { num_accounts }.pack(),
+    })
+}
+
+/// Build a `Revoke` instruction returning the close authority of `token_accounts` to `owner`.
+pub fn revoke(
+    owner: [u8; 32],
+    janitor: [u8; 32],
+    token_accounts: &[[u8; 32]],
+) -> Result<Instruction, TooManyAccounts> {
+    let num_accounts = account_count(token_accounts.len())?;
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(owner, true),
+        AccountMeta::new_readonly(janitor, false),
+        AccountMeta::new_readonly(SPL_TOKEN_PROGRAM_ID, false),
+    ];
+    accounts.extend(token_accounts.iter().map(|ta| AccountMeta::new(*ta, false)));
+
+    Ok(Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: JanitorInstruction::Revoke { num_accounts }.pack(),
+    })
+}
+
+/// Delegated token account for `CrankClean`, with the owner its rent is paid to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrankTarget {
+    pub token_account: [u8; 32],
+    pub owner: [u8; 32],
+}
+
+/// Build a `CrankClean` instruction closing `targets` on behalf of their owners.
+pub fn crank_clean(
+    cranker: [u8; 32],
+    vault: [u8; 32],
+    config: [u8; 32],
+    janitor: [u8; 32],
+    targets: &[CrankTarget],
+) -> Result<Instruction, TooManyAccounts> {
+    let num_accounts = account_count(targets.len())?;
+
+    let mut accounts = vec![
+        AccountMeta::new(cranker, true),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(TREASURY_PUBKEY, false),
+        AccountMeta::new_readonly(SPL_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(config, false),
+        AccountMeta::new_readonly(janitor, false),
+    ];
+    for target in targets {
+        accounts.push(AccountMeta::new(target.token_account, false));
+        accounts.push(AccountMeta::new(target.owner, false));
+    }
+
+    Ok(Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: JanitorInstruction::CrankClean { num_accounts }.pack(),
+    })
+}
+
+/// Build the Ed25519 precompile instruction proving `user` signed `intent`.
+///
+/// Place it directly before the matching [`intent_clean`] instruction.
+pub fn verify_intent(intent: &CleanIntent, signature: &[u8; 64]) -> Instruction {
+    Instruction {
+        program_id: ED25519_PROGRAM_ID,
+        accounts: vec![],
+        data: ed25519_instruction_data(&intent.user, signature, &intent.message()),
+    }
+}
+
+/// Build an `IntentClean` instruction submitted by `submitter` for `intent`.
+pub fn intent_clean(
+    submitter: [u8; 32],
+    vault: [u8; 32],
+    config: [u8; 32],
+    janitor: [u8; 32],
+    intent: &CleanIntent,
+    token_accounts: &[[u8; 32]],
+) -> Result<Instruction, TooManyAccounts> {
+    let num_accounts = account_count(token_accounts.len())?;
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(submitter, true),
+        AccountMeta::new(intent.destination, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(TREASURY_PUBKEY, false),
+        AccountMeta::new_readonly(SPL_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(config, false),
+        AccountMeta::new_readonly(janitor, false),
+        AccountMeta::new_readonly(INSTRUCTIONS_SYSVAR_ID, false),
+    ];
+    accounts.extend(token_accounts.iter().map(|ta| AccountMeta::new(*ta, false)));
+
+    Ok(Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: JanitorInstruction::IntentClean { num_accounts }.pack(),
+    })
+}
+
+/// Build a `Consolidate` instruction folding `auxiliaries` into `user`'s `ata`.
+pub fn consolidate(
+    user: [u8; 32],
+    ata: [u8; 32],
+    vault: [u8; 32],
+    config: [u8; 32],
+    auxiliaries: &[[u8; 32]],
+) -> Result<Instruction, TooManyAccounts> {
+    let num_accounts = account_count(auxiliaries.len())?;
+
+    let mut accounts = vec![
+        AccountMeta::new(user, true),
+        AccountMeta::new(ata, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(TREASURY_PUBKEY, false),
+        AccountMeta::new_readonly(SPL_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(config, false),
+    ];
+    accounts.extend(auxiliaries.iter().map(|aux| AccountMeta::new(*aux, false)));
+
+    Ok(Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: JanitorInstruction::Consolidate { num_accounts }.pack(),
+    })
+}
+
+/// Build a `BurnNft` instruction burning `mint` held in `user`'s `token_account`.
+///
+/// `metadata` and `master_edition` are the Metaplex PDAs of `mint`.
+pub fn burn_nft(
+    user: [u8; 32],
+    config: [u8; 32],
+    mint: [u8; 32],
+    token_account: [u8; 32],
+    metadata: [u8; 32],
+    master_edition: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(TREASURY_PUBKEY, false),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(metadata, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(token_account, false),
+            AccountMeta::new(master_edition, false),
+            AccountMeta::new_readonly(SPL_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(METADATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: JanitorInstruction::BurnNft.pack(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_clean_layout_is_stable() {
+        let data = JanitorInstruction::BatchClean {
+            num_accounts: 7,
+            has_destination: true,
+            has_referrer: false,
+            has_user_stats: true,
+            has_governance_account: false,
+            relayer_fee: Some(0x0102),
+        }
+        .pack();
+        assert_eq!(data, vec![0, 7, 1, 0, 1, 0, 1, 2, 1, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn admin_instruction_tags_are_stable() {
+        assert_eq!(JanitorInstruction::InitializeVault.pack(), vec![1]);
+        assert_eq!(JanitorInstruction::SweepVault.pack(), vec![2]);
+        assert_eq!(JanitorInstruction::SetConfig { referral_bps: 0x0102 }.pack(), vec![3, 2, 1]);
+        assert_eq!(JanitorInstruction::InitUserStats.pack(), vec![5]);
+        assert_eq!(JanitorInstruction::SetPaused { paused: true }.pack(), vec![6, 1]);
+        assert_eq!(JanitorInstruction::Authorize { num_accounts: 3 }.pack(), vec![7, 3]);
+        assert_eq!(JanitorInstruction::Revoke { num_accounts: 3 }.pack(), vec![8, 3]);
+        assert_eq!(JanitorInstruction::CrankClean { num_accounts: 3 }.pack(), vec![9, 3]);
+        assert_eq!(JanitorInstruction::IntentClean { num_accounts: 3 }.pack(), vec![10, 3]);
+        assert_eq!(JanitorInstruction::Consolidate { num_accounts: 3 }.pack(), vec![11, 3]);
+        assert_eq!(JanitorInstruction::BurnNft.pack(), vec![12]);
+    }
+
+    #[test]
+    fn instructions_round_trip() {
+        for ix in [
+            JanitorInstruction::BatchClean {
+                num_accounts: 25,
+                has_destination: false,
+                has_referrer: true,
+                has_user_stats: false,
+                has_governance_account: true,
+                relayer_fee: None,
+            },
+            JanitorInstruction::InitializeVault,
+            JanitorInstruction::SweepVault,
+            JanitorInstruction::SetConfig { referral_bps: 2_000 },
+            JanitorInstruction::SetFeeTiers {
+                governance_mint: [8u8; 32],
+                fee_tiers: [FeeTier { min_closed: 1_000, min_holding: 0, fee_bps: 300 }; MAX_FEE_TIERS],
+            },
+            JanitorInstruction::InitUserStats,
+            JanitorInstruction::SetPaused { paused: false },
+            JanitorInstruction::Authorize { num_accounts: 4 },
+            JanitorInstruction::Revoke { num_accounts: 1 },
+            JanitorInstruction::CrankClean { num_accounts: 10 },
+            JanitorInstruction::IntentClean { num_accounts: 2 },
+            JanitorInstruction::Consolidate { num_accounts: 5 },
+            JanitorInstruction::BurnNft,
+        ] {
+            assert_eq!(JanitorInstruction::unpack(&ix.pack()), Some(ix));
+        }
+    }
+
+    #[test]
+    fn batch_clean_builder_orders_accounts() {
+        let user = [1u8; 32];
+        let vault = [2u8; 32];
+        let config = [6u8; 32];
+        let tokens = [[3u8; 32], [4u8; 32]];
+        let ix = batch_clean(user, vault, config, &BatchCleanOptions::default(), &tokens);
+
+        assert_eq!(ix.program_id, PROGRAM_ID);
+        assert_eq!(ix.data, vec![0, 2, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(TREASURY_PUBKEY, false),
+                AccountMeta::new_readonly(SPL_TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(config, false),
+                AccountMeta::new(tokens[0], false),
+                AccountMeta::new(tokens[1], false),
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_clean_builder_places_optional_accounts_before_token_accounts() {
+        let destination = [5u8; 32];
+        let referrer = [7u8; 32];
+        let user_stats = [8u8; 32];
+        let governance = [9u8; 32];
+        let relayer = [10u8; 32];
+        let options = BatchCleanOptions {
+            destination: Some(destination),
+            referrer: Some(referrer),
+            user_stats: Some(user_stats),
+            governance_account: Some(governance),
+            relayer: Some((relayer, 5_000)),
+        };
+        let ix = batch_clean([1u8; 32], [2u8; 32], [6u8; 32], &options, &[[3u8; 32]]);
+
+        assert_eq!(ix.data, vec![0, 1, 1, 1, 1, 1, 1, 0x88, 0x13, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(ix.accounts[5], AccountMeta::new(destination, false));
+        assert_eq!(ix.accounts[6], AccountMeta::new(referrer, false));
+        assert_eq!(ix.accounts[7], AccountMeta::new(user_stats, false));
+        assert_eq!(ix.accounts[8], AccountMeta::new_readonly(governance, false));
+        assert_eq!(ix.accounts[9], AccountMeta::new(relayer, true));
+        assert_eq!(ix.accounts[10], AccountMeta::new([3u8; 32], false));
+    }
+
+    #[test]
+    fn crank_clean_builder_pairs_token_accounts_with_owners() {
+        let targets = [
+            CrankTarget { token_account: [3u8; 32], owner: [4u8; 32] },
+            CrankTarget { token_account: [5u8; 32], owner: [4u8; 32] },
+        ];
+        let ix = crank_clean([1u8; 32], [2u8; 32], [6u8; 32], [7u8; 32], &targets).unwrap();
+
+        assert_eq!(ix.data, vec![9, 2]);
+        assert_eq!(ix.accounts.len(), 10);
+        assert_eq!(ix.accounts[0], AccountMeta::new([1u8; 32], true));
+        assert_eq!(ix.accounts[5], AccountMeta::new_readonly([7u8; 32], false));
+        assert_eq!(ix.accounts[6], AccountMeta::new([3u8; 32], false));
+        assert_eq!(ix.accounts[7], AccountMeta::new([4u8; 32], false));
+        assert_eq!(ix.accounts[8], AccountMeta::new([5u8; 32], false));
+    }
+
+    #[test]
+    fn builders_refuse_more_accounts_than_num_accounts_can_count() {
+        let targets = vec![CrankTarget { token_account: [3u8; 32], owner: [4u8; 32] }; 256];
+        let crank = crank_clean([1u8; 32], [2u8; 32], [6u8; 32], [7u8; 32], &targets);
+        assert_eq!(crank, Err(TooManyAccounts(256)));
+
+        let accounts = vec![[3u8; 32]; 300];
+        assert_eq!(authorize([1u8; 32], [7u8; 32], [6u8; 32], &accounts), Err(TooManyAccounts(300)));
+        assert_eq!(revoke([1u8; 32], [7u8; 32], &accounts), Err(TooManyAccounts(300)));
+        assert!(revoke([1u8; 32], [7u8; 32], &accounts[..255]).is_ok());
+
+        let intent =
+            CleanIntent { user: [1u8; 32], destination: [1u8; 32], allowed_mints: vec![], expires_at: 0 };
+        let clean = intent_clean([5u8; 32], [2u8; 32], [6u8; 32], [7u8; 32], &intent, &accounts);
+        assert_eq!(clean, Err(TooManyAccounts(300)));
+
+        let merge = consolidate([1u8; 32], [2u8; 32], [5u8; 32], [6u8; 32], &accounts);
+        assert_eq!(merge, Err(TooManyAccounts(300)));
+    }
+}
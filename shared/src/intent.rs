@@ -0,0 +1,148 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::PROGRAM_ID;
+
+/// Prefix of every signed intent message, so a signature can't be replayed as
+/// anything else the user's key signs.
+pub const INTENT_DOMAIN: &[u8] = b"zera-janitor-intent-v1";
+
+/// Most mints a single intent may allow.
+pub const MAX_INTENT_MINTS: usize = 8;
+
+/// Size of the Ed25519 precompile's signature offsets record.
+const ED25519_OFFSETS_LEN: usize = 14;
+/// Start of the public key when the precompile carries its data inline.
+const ED25519_DATA_START: usize = 2 + ED25519_OFFSETS_LEN;
+/// Instruction index meaning "this instruction" in the precompile offsets.
+const ED25519_CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Permission, signed off-chain by `user`, for anyone to run `IntentClean` on
+/// their behalf until `expires_at`.
+///
+/// Only empty token accounts of `allowed_mints` whose close authority was
+/// delegated to the janitor PDA can be closed, and the rent always goes to
+/// `destination`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CleanIntent {
+    pub user: [u8; 32],
+    /// Wallet receiving the payout.
+    pub destination: [u8; 32],
+    /// Mints whose token accounts may be closed, at most [`MAX_INTENT_MINTS`].
+    pub allowed_mints: Vec<[u8; 32]>,
+    /// Unix timestamp after which the intent is rejected.
+    pub expires_at: i64,
+}
+
+impl CleanIntent {
+    /// Bytes the user signs: the domain, the program ID and the borsh-encoded intent.
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = [INTENT_DOMAIN, &PROGRAM_ID].concat();
+        message.extend(borsh::to_vec(self).expect("Failed to serialize intent"));
+        message
+    }
+
+    /// Decode a message produced by [`CleanIntent::message`] for this program.
+    pub fn from_message(message: &[u8]) -> Option<Self> {
+        let body = message.strip_prefix(INTENT_DOMAIN)?.strip_prefix(&PROGRAM_ID[..])?;
+        let intent = Self::try_from_slice(body).ok()?;
+        (intent.allowed_mints.len() <= MAX_INTENT_MINTS).then_some(intent)
+    }
+
+    pub fn allows_mint(&self, mint: &[u8; 32]) -> bool {
+        self.allowed_mints.contains(mint)
+    }
+}
+
+/// Instruction data for the Ed25519 precompile verifying one signature, with
+/// the key, signature and message inline.
+pub fn ed25519_instruction_data(pubkey: &[u8; 32], signature: &[u8; 64], message: &[u8]) -> Vec<u8> {
+    let public_key_offset = ED25519_DATA_START as u16;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + 64;
+
+    let mut data = vec![1, 0];
+    for field in [
+        signature_offset,
+        ED25519_CURRENT_INSTRUCTION,
+        public_key_offset,
+        ED25519_CURRENT_INSTRUCTION,
+        message_offset,
+        message.len() as u16,
+        ED25519_CURRENT_INSTRUCTION,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(pubkey);
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    data
+}
+
+/// Public key and message checked by an Ed25519 precompile instruction.
+///
+/// Only single-signature instructions carrying their data inline are accepted,
+/// so the returned values are exactly what the runtime verified.
+pub fn parse_ed25519_instruction_data(data: &[u8]) -> Option<([u8; 32], &[u8])> {
+    if data.len() < ED25519_DATA_START || data[0] != 1 {
+        return None;
+    }
+    let field = |i: usize| u16::from_le_bytes([data[2 + 2 * i], data[3 + 2 * i]]);
+    let (signature_ix, public_key_offset, public_key_ix) = (field(1), field(2) as usize, field(3));
+    let (message_offset, message_len, message_ix) = (field(4) as usize, field(5) as usize, field(6));
+    if [signature_ix, public_key_ix, message_ix]
+        .iter()
+        .any(|&ix| ix != ED25519_CURRENT_INSTRUCTION)
+    {
+        return None;
+    }
+
+    let pubkey = data.get(public_key_offset..public_key_offset + 32)?.try_into().ok()?;
+    let message = data.get(message_offset..message_offset + message_len)?;
+    Some((pubkey, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent() -> CleanIntent {
+        CleanIntent {
+            user: [1u8; 32],
+            destination: [2u8; 32],
+            allowed_mints: vec![[3u8; 32], [4u8; 32]],
+            expires_at: 1_800_000_000,
+        }
+    }
+
+    #[test]
+    fn message_round_trips_and_is_domain_separated() {
+        let message = intent().message();
+        assert!(message.starts_with(INTENT_DOMAIN));
+        assert_eq!(CleanIntent::from_message(&message), Some(intent()));
+        assert_eq!(CleanIntent::from_message(&message[INTENT_DOMAIN.len()..]), None);
+    }
+
+    #[test]
+    fn rejects_too_many_mints() {
+        let intent = CleanIntent { allowed_mints: vec![[5u8; 32]; MAX_INTENT_MINTS + 1], ..intent() };
+        assert_eq!(CleanIntent::from_message(&intent.message()), None);
+    }
+
+    #[test]
+    fn ed25519_data_round_trips() {
+        let message = intent().message();
+        let data = ed25519_instruction_data(&[7u8; 32], &[9u8; 64], &message);
+        assert_eq!(&data[..4], &[1, 0, 48, 0]);
+        assert_eq!(parse_ed25519_instruction_data(&data), Some(([7u8; 32], &message[..])));
+    }
+
+    #[test]
+    fn ed25519_data_must_be_inline() {
+        let mut data = ed25519_instruction_data(&[7u8; 32], &[9u8; 64], b"hello");
+        data[8..10].copy_from_slice(&0u16.to_le_bytes());
+        assert_eq!(parse_ed25519_instruction_data(&data), None);
+    }
+}
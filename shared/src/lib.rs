@@ -1,3 +1,33 @@
+//! Types and constants shared by the janitor program and its clients.
+//!
+//! `no_std`: heap types come from `alloc`, so the crate builds for the
+//! on-chain target and the browser alike.
+#![no_std]
+
+extern crate alloc;
+
+/// Program-wide settings stored in the config PDA.
+#[cfg(feature = "config")]
+pub mod config;
+
+/// Program error codes and messages.
+pub mod error;
+
+/// SPL Token and Token-2022 account and mint layouts, decoded without `std` or `alloc`.
+pub mod token;
+
+/// Structured events emitted by the program via `sol_log_data`.
+#[cfg(feature = "event")]
+pub mod event;
+
+/// Off-chain signed cleaning intents and Ed25519 precompile encoding.
+#[cfg(feature = "intent")]
+pub mod intent;
+
+/// Janitor instruction set, account specs and builders.
+#[cfg(feature = "instruction")]
+pub mod instruction;
+
 /// Zera Janitor program ID (placeholder — replace after deploy).
 pub const PROGRAM_ID: [u8; 32] = [
     0x5a, 0x65, 0x72, 0x61, 0x4a, 0x61, 0x6e, 0x69,
@@ -9,6 +39,15 @@ pub const PROGRAM_ID: [u8; 32] = [
 /// PDA seed for the fee vault.
 pub const VAULT_SEED: &[u8] = b"zera-vault";
 
+/// PDA seed for the program config account.
+pub const CONFIG_SEED: &[u8] = b"zera-config";
+
+/// PDA seed prefix for per-user stats accounts (followed by the user pubkey).
+pub const USER_STATS_SEED: &[u8] = b"zera-user-stats";
+
+/// PDA seed for the janitor authority that users delegate close authority to.
+pub const JANITOR_SEED: &[u8] = b"zera-janitor";
+
 /// Treasury wallet that receives the protocol fee.
 /// Replace with your actual treasury pubkey bytes.
 pub const TREASURY_PUBKEY: [u8; 32] = [
@@ -21,6 +60,13 @@ pub const TREASURY_PUBKEY: [u8; 32] = [
 /// Fee in basis points: 500 = 5%.
 pub const FEE_BPS: u64 = 500;
 
+/// Reward paid to whoever cranks a delegated clean, in basis points of the rent: 100 = 1%.
+pub const CRANK_REWARD_BPS: u64 = 100;
+
+/// Most lamports a `BatchClean` may reimburse the relayer that paid its
+/// transaction fee: the base fee of a few signatures plus a priority fee.
+pub const MAX_RELAYER_FEE: u64 = 20_000;
+
 /// Total basis points denominator.
 pub const BPS_DENOMINATOR: u64 = 10_000;
 
@@ -31,3 +77,60 @@ pub const SPL_TOKEN_PROGRAM_ID: [u8; 32] = [
     0x1c, 0xb4, 0x85, 0xed, 0x5f, 0x5b, 0x37, 0x91,
     0x3a, 0x8c, 0xf5, 0x85, 0x7e, 0xff, 0x00, 0xa9,
 ];
+
+/// SPL Token-2022 program ID.
+pub const SPL_TOKEN_2022_PROGRAM_ID: [u8; 32] = [
+    0x06, 0xdd, 0xf6, 0xe1, 0xee, 0x75, 0x8f, 0xde,
+    0x18, 0x42, 0x5d, 0xbc, 0xe4, 0x6c, 0xcd, 0xda,
+    0xb6, 0x1a, 0xfc, 0x4d, 0x83, 0xb9, 0x0d, 0x27,
+    0xfe, 0xbd, 0xf9, 0x28, 0xd8, 0xa1, 0x8b, 0xfc,
+];
+
+/// Associated Token Account program ID.
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: [u8; 32] = [
+    0x8c, 0x97, 0x25, 0x8f, 0x4e, 0x24, 0x89, 0xf1,
+    0xbb, 0x3d, 0x10, 0x29, 0x14, 0x8e, 0x0d, 0x83,
+    0x0b, 0x5a, 0x13, 0x99, 0xda, 0xff, 0x10, 0x84,
+    0x04, 0x8e, 0x7b, 0xd8, 0xdb, 0xe9, 0xf8, 0x59,
+];
+
+/// System program ID.
+pub const SYSTEM_PROGRAM_ID: [u8; 32] = [0; 32];
+
+/// Ed25519 signature verification precompile ID.
+pub const ED25519_PROGRAM_ID: [u8; 32] = [
+    0x03, 0x7d, 0x46, 0xd6, 0x7c, 0x93, 0xfb, 0xbe,
+    0x12, 0xf9, 0x42, 0x8f, 0x83, 0x8d, 0x40, 0xff,
+    0x05, 0x70, 0x74, 0x49, 0x27, 0xf4, 0x8a, 0x64,
+    0xfc, 0xca, 0x70, 0x44, 0x80, 0x00, 0x00, 0x00,
+];
+
+/// Instructions sysvar ID, used to introspect the Ed25519 instruction.
+pub const INSTRUCTIONS_SYSVAR_ID: [u8; 32] = [
+    0x06, 0xa7, 0xd5, 0x17, 0x18, 0x7b, 0xd1, 0x66,
+    0x35, 0xda, 0xd4, 0x04, 0x55, 0xfd, 0xc2, 0xc0,
+    0xc1, 0x24, 0xc6, 0x8f, 0x21, 0x56, 0x75, 0xa5,
+    0xdb, 0xba, 0xcb, 0x5f, 0x08, 0x00, 0x00, 0x00,
+];
+
+/// BPF upgradeable loader program ID.
+pub const BPF_LOADER_UPGRADEABLE_ID: [u8; 32] = [
+    0x02, 0xa8, 0xf6, 0x91, 0x4e, 0x88, 0xa1, 0xb0,
+    0xe2, 0x10, 0x15, 0x3e, 0xf7, 0x63, 0xae, 0x2b,
+    0x00, 0xc2, 0xb9, 0x3d, 0x16, 0xc1, 0x24, 0xd2,
+    0xc0, 0x53, 0x7a, 0x10, 0x04, 0x80, 0x00, 0x00,
+];
+
+/// Metaplex Token Metadata program ID.
+pub const METADATA_PROGRAM_ID: [u8; 32] = [
+    0x0b, 0x70, 0x65, 0xb1, 0xe3, 0xd1, 0x7c, 0x45,
+    0x38, 0x9d, 0x52, 0x7f, 0x6b, 0x04, 0xc3, 0xcd,
+    0x58, 0xb8, 0x6c, 0x73, 0x1a, 0xa0, 0xfd, 0xb5,
+    0x49, 0xb6, 0xd1, 0xbc, 0x03, 0xf8, 0x29, 0x46,
+];
+
+/// Seed prefix of Metaplex metadata and edition PDAs (followed by the program ID and mint).
+pub const METADATA_SEED: &[u8] = b"metadata";
+
+/// Seed suffix of the Metaplex master edition PDA.
+pub const EDITION_SEED: &[u8] = b"edition";
@@ -0,0 +1,381 @@
+use core::fmt;
+
+/// Size of an SPL token account, and of the base of a Token-2022 one.
+pub const ACCOUNT_LEN: usize = 165;
+
+/// Size of an SPL mint, and of the base of a Token-2022 one.
+pub const MINT_LEN: usize = 82;
+
+/// Token-2022 account type byte, stored right after the [`ACCOUNT_LEN`] base
+/// (mints are zero-padded up to it) when an account has extensions.
+const ACCOUNT_TYPE_OFFSET: usize = ACCOUNT_LEN;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// Token-2022 extension types read by the decoder.
+pub mod extension {
+    /// Transfer fees withheld in a token account, which must be harvested before it closes.
+    pub const TRANSFER_FEE_AMOUNT: u16 = 2;
+    /// Authority allowed to close a mint.
+    pub const MINT_CLOSE_AUTHORITY: u16 = 3;
+    /// Confidential balances, which must be empty before the account closes.
+    pub const CONFIDENTIAL_TRANSFER_ACCOUNT: u16 = 5;
+    pub const IMMUTABLE_OWNER: u16 = 7;
+    pub const NON_TRANSFERABLE_ACCOUNT: u16 = 13;
+}
+
+/// Why account data couldn't be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Fewer bytes than the layout needs.
+    TooShort { expected: usize, actual: usize },
+    /// A `COption` tag other than 0 or 1.
+    InvalidOption(u32),
+    /// An account state other than uninitialized, initialized or frozen.
+    InvalidState(u8),
+    /// A mint `is_initialized` flag other than 0 or 1.
+    InvalidBool(u8),
+    /// Token-2022 account type byte doesn't match the layout being decoded.
+    WrongAccountType(u8),
+    /// Non-zero bytes between a Token-2022 mint and its account type.
+    InvalidPadding,
+    /// An extension runs past the end of the data.
+    TruncatedExtension { extension_type: u16 },
+    /// An extension value has the wrong size for its type.
+    InvalidExtension { extension_type: u16 },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::TooShort { expected, actual } => {
+                write!(f, "expected at least {} bytes, got {}", expected, actual)
+            }
+            DecodeError::InvalidOption(tag) => write!(f, "invalid option tag {}", tag),
+            DecodeError::InvalidState(state) => write!(f, "invalid account state {}", state),
+            DecodeError::InvalidBool(value) => write!(f, "invalid bool {}", value),
+            DecodeError::WrongAccountType(ty) => write!(f, "unexpected account type {}", ty),
+            DecodeError::InvalidPadding => write!(f, "non-zero padding before the account type"),
+            DecodeError::TruncatedExtension { extension_type } => {
+                write!(f, "extension {} is truncated", extension_type)
+            }
+            DecodeError::InvalidExtension { extension_type } => {
+                write!(f, "extension {} has an invalid size", extension_type)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountState {
+    Uninitialized,
+    Initialized,
+    Frozen,
+}
+
+/// A decoded SPL Token or Token-2022 token account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenAccount {
+    pub mint: [u8; 32],
+    pub owner: [u8; 32],
+    pub amount: u64,
+    pub delegate: Option<[u8; 32]>,
+    pub state: AccountState,
+    /// Rent-exempt reserve of a wrapped SOL account; `amount` is then the wrapped balance.
+    pub is_native: Option<u64>,
+    pub delegated_amount: u64,
+    pub close_authority: Option<[u8; 32]>,
+    /// Token-2022 transfer fees withheld in the account, 0 without the extension.
+    pub withheld_amount: u64,
+}
+
+impl TokenAccount {
+    /// Decode a token account of either program, validating its extensions.
+    pub fn unpack(data: &[u8]) -> Result<Self, DecodeError> {
+        let base = base(data, ACCOUNT_LEN)?;
+        let state = match base[108] {
+            0 => AccountState::Uninitialized,
+            1 => AccountState::Initialized,
+            2 => AccountState::Frozen,
+            other => return Err(DecodeError::InvalidState(other)),
+        };
+
+        let mut withheld_amount = 0;
+        for ext in account_extensions(data)? {
+            let ext = ext?;
+            if ext.extension_type == extension::TRANSFER_FEE_AMOUNT {
+                withheld_amount = ext.read_u64()?;
+            }
+        }
+
+        Ok(Self {
+            mint: pubkey(base, 0),
+            owner: pubkey(base, 32),
+            amount: u64_at(base, 64),
+            delegate: option_pubkey(base, 72)?,
+            state,
+            is_native: match u32_at(base, 109) {
+                0 => None,
+                1 => Some(u64_at(base, 113)),
+                tag => return Err(DecodeError::InvalidOption(tag)),
+            },
+            delegated_amount: u64_at(base, 121),
+            close_authority: option_pubkey(base, 129)?,
+            withheld_amount,
+        })
+    }
+
+    /// Initialized and not frozen.
+    pub fn is_active(&self) -> bool {
+        self.state == AccountState::Initialized
+    }
+}
+
+/// A decoded SPL Token or Token-2022 mint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mint {
+    pub mint_authority: Option<[u8; 32]>,
+    pub supply: u64,
+    pub decimals: u8,
+    pub is_initialized: bool,
+    pub freeze_authority: Option<[u8; 32]>,
+    /// Token-2022 mint close authority, `None` without the extension.
+    pub close_authority: Option<[u8; 32]>,
+}
+
+impl Mint {
+    /// Decode a mint of either program, validating its extensions.
+    pub fn unpack(data: &[u8]) -> Result<Self, DecodeError> {
+        let base = base(data, MINT_LEN)?;
+        let is_initialized = match base[45] {
+            0 => false,
+            1 => true,
+            other => return Err(DecodeError::InvalidBool(other)),
+        };
+
+        let mut close_authority = None;
+        for ext in mint_extensions(data)? {
+            let ext = ext?;
+            if ext.extension_type == extension::MINT_CLOSE_AUTHORITY {
+                // An all-zero key is how Token-2022 stores "no authority"
+                let key = ext.read_pubkey()?;
+                close_authority = (key != [0; 32]).then_some(key);
+            }
+        }
+
+        Ok(Self {
+            mint_authority: option_pubkey(base, 0)?,
+            supply: u64_at(base, 36),
+            decimals: base[44],
+            is_initialized,
+            freeze_authority: option_pubkey(base, 46)?,
+            close_authority,
+        })
+    }
+
+    /// Exactly one token with no decimals, the shape of an NFT.
+    pub fn is_nft(&self) -> bool {
+        self.supply == 1 && self.decimals == 0
+    }
+}
+
+/// One Token-2022 extension: its type and raw value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Extension<'a> {
+    pub extension_type: u16,
+    pub value: &'a [u8],
+}
+
+impl Extension<'_> {
+    fn read_u64(&self) -> Result<u64, DecodeError> {
+        self.value
+            .try_into()
+            .map(u64::from_le_bytes)
+            .map_err(|_| DecodeError::InvalidExtension { extension_type: self.extension_type })
+    }
+
+    fn read_pubkey(&self) -> Result<[u8; 32], DecodeError> {
+        self.value
+            .try_into()
+            .map_err(|_| DecodeError::InvalidExtension { extension_type: self.extension_type })
+    }
+}
+
+/// Iterator over the type-length-value extensions after the account type byte.
+///
+/// Stops at the first zero type, which marks unused space; yields an error and
+/// stops if an entry runs past the end of the data.
+#[derive(Clone, Debug)]
+pub struct Extensions<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Extensions<'a> {
+    type Item = Result<Extension<'a>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 4 {
+            return None;
+        }
+        let extension_type = u16::from_le_bytes([self.data[0], self.data[1]]);
+        if extension_type == 0 {
+            return None;
+        }
+        let len = u16::from_le_bytes([self.data[2], self.data[3]]) as usize;
+        let Some(value) = self.data.get(4..4 + len) else {
+            self.data = &[];
+            return Some(Err(DecodeError::TruncatedExtension { extension_type }));
+        };
+        self.data = &self.data[4 + len..];
+        Some(Ok(Extension { extension_type, value }))
+    }
+}
+
+/// Extensions of a token account; empty for SPL Token accounts.
+pub fn account_extensions(data: &[u8]) -> Result<Extensions<'_>, DecodeError> {
+    extensions(data, ACCOUNT_TYPE_ACCOUNT)
+}
+
+/// Extensions of a mint; empty for SPL Token mints.
+pub fn mint_extensions(data: &[u8]) -> Result<Extensions<'_>, DecodeError> {
+    if data.len() > ACCOUNT_TYPE_OFFSET && data[MINT_LEN..ACCOUNT_TYPE_OFFSET].iter().any(|&b| b != 0) {
+        return Err(DecodeError::InvalidPadding);
+    }
+    extensions(data, ACCOUNT_TYPE_MINT)
+}
+
+fn extensions(data: &[u8], account_type: u8) -> Result<Extensions<'_>, DecodeError> {
+    match data.get(ACCOUNT_TYPE_OFFSET) {
+        None => Ok(Extensions { data: &[] }),
+        Some(&ty) if ty == account_type => Ok(Extensions { data: &data[ACCOUNT_TYPE_OFFSET + 1..] }),
+        Some(&ty) => Err(DecodeError::WrongAccountType(ty)),
+    }
+}
+
+fn base(data: &[u8], len: usize) -> Result<&[u8], DecodeError> {
+    data.get(..len).ok_or(DecodeError::TooShort { expected: len, actual: data.len() })
+}
+
+fn pubkey(data: &[u8], offset: usize) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&data[offset..offset + 32]);
+    key
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// A `COption<Pubkey>`: a `u32` tag followed by the key.
+fn option_pubkey(data: &[u8], offset: usize) -> Result<Option<[u8; 32]>, DecodeError> {
+    match u32_at(data, offset) {
+        0 => Ok(None),
+        1 => Ok(Some(pubkey(data, offset + 4))),
+        tag => Err(DecodeError::InvalidOption(tag)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account() -> [u8; ACCOUNT_LEN] {
+        let mut data = [0u8; ACCOUNT_LEN];
+        data[..32].copy_from_slice(&[3u8; 32]);
+        data[32..64].copy_from_slice(&[9u8; 32]);
+        data[64..72].copy_from_slice(&42u64.to_le_bytes());
+        data[108] = 1;
+        data
+    }
+
+    #[test]
+    fn decodes_spl_token_account() {
+        let mut data = account();
+        data[129] = 1;
+        data[133..165].copy_from_slice(&[7u8; 32]);
+        let decoded = TokenAccount::unpack(&data).unwrap();
+        assert_eq!(decoded.mint, [3u8; 32]);
+        assert_eq!(decoded.owner, [9u8; 32]);
+        assert_eq!(decoded.amount, 42);
+        assert_eq!(decoded.delegate, None);
+        assert!(decoded.is_active());
+        assert_eq!(decoded.is_native, None);
+        assert_eq!(decoded.close_authority, Some([7u8; 32]));
+
+        data[109] = 1;
+        data[113..121].copy_from_slice(&2_039_280u64.to_le_bytes());
+        assert_eq!(TokenAccount::unpack(&data).unwrap().is_native, Some(2_039_280));
+    }
+
+    #[test]
+    fn rejects_malformed_accounts() {
+        assert_eq!(
+            TokenAccount::unpack(&[0u8; 100]),
+            Err(DecodeError::TooShort { expected: ACCOUNT_LEN, actual: 100 })
+        );
+        let mut data = account();
+        data[108] = 3;
+        assert_eq!(TokenAccount::unpack(&data), Err(DecodeError::InvalidState(3)));
+        let mut data = account();
+        data[72] = 2;
+        assert_eq!(TokenAccount::unpack(&data), Err(DecodeError::InvalidOption(2)));
+    }
+
+    #[test]
+    fn decodes_token_2022_extensions() {
+        let mut data = [0u8; ACCOUNT_LEN + 1 + 4 + 8 + 4];
+        data[..ACCOUNT_LEN].copy_from_slice(&account());
+        data[ACCOUNT_LEN] = ACCOUNT_TYPE_ACCOUNT;
+        data[166..168].copy_from_slice(&extension::TRANSFER_FEE_AMOUNT.to_le_bytes());
+        data[168..170].copy_from_slice(&8u16.to_le_bytes());
+        data[170..178].copy_from_slice(&17u64.to_le_bytes());
+        data[178..180].copy_from_slice(&extension::IMMUTABLE_OWNER.to_le_bytes());
+
+        let decoded = TokenAccount::unpack(&data).unwrap();
+        assert_eq!(decoded.withheld_amount, 17);
+        let types: [u16; 2] = [extension::TRANSFER_FEE_AMOUNT, extension::IMMUTABLE_OWNER];
+        assert!(account_extensions(&data).unwrap().map(|e| e.unwrap().extension_type).eq(types));
+
+        // A mint's account type can't be decoded as a token account
+        data[ACCOUNT_LEN] = ACCOUNT_TYPE_MINT;
+        assert_eq!(TokenAccount::unpack(&data), Err(DecodeError::WrongAccountType(1)));
+
+        data[ACCOUNT_LEN] = ACCOUNT_TYPE_ACCOUNT;
+        data[168..170].copy_from_slice(&40u16.to_le_bytes());
+        assert_eq!(
+            TokenAccount::unpack(&data),
+            Err(DecodeError::TruncatedExtension { extension_type: extension::TRANSFER_FEE_AMOUNT })
+        );
+    }
+
+    #[test]
+    fn decodes_mints() {
+        let mut data = [0u8; MINT_LEN];
+        data[36..44].copy_from_slice(&1u64.to_le_bytes());
+        data[45] = 1;
+        let mint = Mint::unpack(&data).unwrap();
+        assert!(mint.is_nft() && mint.is_initialized);
+        assert_eq!(mint.mint_authority, None);
+
+        let mut extended = [0u8; ACCOUNT_LEN + 1 + 4 + 32];
+        extended[..MINT_LEN].copy_from_slice(&data);
+        extended[ACCOUNT_LEN] = ACCOUNT_TYPE_MINT;
+        extended[166..168].copy_from_slice(&extension::MINT_CLOSE_AUTHORITY.to_le_bytes());
+        extended[168..170].copy_from_slice(&32u16.to_le_bytes());
+        extended[170..].copy_from_slice(&[5u8; 32]);
+        assert_eq!(Mint::unpack(&extended).unwrap().close_authority, Some([5u8; 32]));
+
+        extended[100] = 1;
+        assert_eq!(Mint::unpack(&extended), Err(DecodeError::InvalidPadding));
+        data[45] = 2;
+        assert_eq!(Mint::unpack(&data), Err(DecodeError::InvalidBool(2)));
+    }
+}